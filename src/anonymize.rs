@@ -0,0 +1,354 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex, RegexBuilder};
+
+use crate::commit::{Author, Commit};
+use crate::provider::PullRequest;
+
+lazy_static! {
+    // People credited in trailers, e.g. `Co-authored-by: Jon <jon@email.ca>`
+    static ref TRAILER_REGEX: Regex =
+        Regex::new(r"(?mi)^[a-z-]+-by:\s*(.+?)\s*<([^>]+)>").unwrap();
+    static ref EMAIL_REGEX: Regex = Regex::new(EMAIL_PATTERN).unwrap();
+}
+
+const EMAIL_PATTERN: &str = r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+";
+
+/// Pseudonyms like `Author A` for everyone in a history, whether they
+/// committed or were only credited in a trailer like `Co-authored-by`,
+/// lettered in the order they first appear so everyone keeps theirs as the
+/// history grows. Made from the whole history before it's filtered so a person
+/// has the same one in every range.
+pub struct Pseudonyms {
+    /// By lowercase email
+    authors: HashMap<String, Author>,
+    /// What replaces each lowercase name and email wherever messages mention
+    /// them
+    replacements: HashMap<String, String>,
+    mentions: Regex,
+}
+
+impl Pseudonyms {
+    pub fn of(commits: &[Commit]) -> Pseudonyms {
+        let mut oldest: Vec<&Commit> = commits.iter().collect();
+        oldest.sort_by_key(|commit| (commit.date, &commit.hash));
+
+        let mut authors = HashMap::new();
+        let mut replacements = HashMap::new();
+
+        for commit in oldest {
+            let credits = TRAILER_REGEX
+                .captures_iter(&commit.message)
+                .map(|credit| (credit[1].to_string(), credit[2].to_string()));
+
+            for (name, email) in [(commit.author.name.clone(), commit.author.email.clone())]
+                .into_iter()
+                .chain(credits)
+            {
+                if authors.contains_key(&email.to_lowercase()) {
+                    continue;
+                }
+
+                let letters = letters(authors.len());
+                let pseudonym = Author::new(
+                    format!("Author {}", letters),
+                    format!("author.{}@anonymous.invalid", letters.to_lowercase()),
+                );
+
+                for (mention, replacement) in [(&name, &pseudonym.name), (&email, &pseudonym.email)]
+                {
+                    // Too short to tell from other words
+                    if mention.trim().chars().count() > 1 {
+                        replacements
+                            .entry(mention.trim().to_lowercase())
+                            .or_insert_with(|| replacement.clone());
+                    }
+                }
+
+                authors.insert(email.to_lowercase(), pseudonym);
+            }
+        }
+
+        let mentions = mentions(&replacements);
+
+        Pseudonyms {
+            authors,
+            replacements,
+            mentions,
+        }
+    }
+
+    /// Replace every author with their pseudonym, and every name and email
+    /// messages mention with theirs. Emails of anyone else are redacted like
+    /// `emails()` does.
+    pub fn apply(&self, commits: &mut [Commit]) {
+        for commit in commits {
+            commit.author = self.authors[&commit.author.email.to_lowercase()].clone();
+
+            let message = self
+                .mentions
+                .replace_all(&commit.message, |mention: &Captures| {
+                    let mention = &mention[0];
+
+                    match self.replacements.get(&mention.to_lowercase()) {
+                        Some(replacement) => replacement.clone(),
+                        None => redact(mention),
+                    }
+                });
+
+            commit.message = message.into_owned();
+        }
+    }
+}
+
+/// Any of the names or emails, ignoring case, or any other email. Emails come
+/// first so a name isn't found inside one, then the longest so a full name
+/// isn't replaced a word at a time.
+fn mentions(replacements: &HashMap<String, String>) -> Regex {
+    let (mut emails, mut names): (Vec<&String>, Vec<&String>) = replacements
+        .keys()
+        .partition(|mention| EMAIL_REGEX.is_match(mention));
+
+    for mentions in [&mut emails, &mut names] {
+        mentions.sort_by_key(|mention| (Reverse(mention.len()), mention.as_str()));
+    }
+
+    let word = |mention: &str| {
+        let bounded = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+        let start = if bounded(mention.chars().next()) {
+            r"\b"
+        } else {
+            ""
+        };
+        let end = if bounded(mention.chars().last()) {
+            r"\b"
+        } else {
+            ""
+        };
+
+        format!("{}{}{}", start, regex::escape(mention), end)
+    };
+
+    let pattern: Vec<String> = emails
+        .into_iter()
+        .map(|email| word(email))
+        .chain([EMAIL_PATTERN.to_string()])
+        .chain(names.into_iter().map(|name| word(name)))
+        .collect();
+
+    // Histories with thousands of people are more than the default allows
+    RegexBuilder::new(&pattern.join("|"))
+        .case_insensitive(true)
+        .size_limit(1 << 28)
+        .build()
+        .unwrap()
+}
+
+/// Replace every author's email with a hash of it, e.g.
+/// `1c8bd4a4e4f0a1b2@redacted.invalid`, keeping their names, and every other
+/// email in messages too, e.g. in `Co-authored-by` trailers. The same email
+/// always hashes the same, with any build of yeesh, so people are still told
+/// apart, and can be matched up by anyone who already knows their email, but
+/// it can't be read back.
+pub fn emails(commits: &mut [Commit]) {
+    for commit in commits {
+        commit.author.email = redact(&commit.author.email);
+
+        let message =
+            EMAIL_REGEX.replace_all(&commit.message, |email: &Captures| redact(&email[0]));
+        commit.message = message.into_owned();
+    }
+}
+
+/// 64-bit FNV-1a, since the standard library's hashes may change between
+/// releases of Rust
+fn redact(email: &str) -> String {
+    let hash = email
+        .to_lowercase()
+        .bytes()
+        .fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+    format!("{:016x}@redacted.invalid", hash)
+}
+
+/// Replace every pull request author with a pseudonym like `User A`, lettered
+/// in the order they first opened one. Providers only know usernames, which
+/// can't be matched up with commit authors, hence the other name.
+pub fn pull_requests(pulls: &mut [PullRequest]) {
+    let mut oldest: Vec<&PullRequest> = pulls.iter().collect();
+    oldest.sort_by_key(|pull| (pull.created_at, pull.number));
+
+    let mut pseudonyms: HashMap<String, String> = HashMap::new();
+
+    for pull in oldest {
+        let letters = letters(pseudonyms.len());
+
+        pseudonyms
+            .entry(pull.author.clone())
+            .or_insert_with(|| format!("User {}", letters));
+    }
+
+    for pull in pulls {
+        pull.author = pseudonyms[&pull.author].clone();
+    }
+}
+
+/// Like spreadsheet columns: A to Z, then AA, AB, and so on
+fn letters(n: usize) -> String {
+    let mut n = n + 1;
+    let mut letters = Vec::new();
+
+    while n > 0 {
+        n -= 1;
+        letters.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+
+    letters.iter().rev().map(|&letter| letter as char).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::graph;
+    use crate::test_support::commit;
+
+    use super::Pseudonyms;
+
+    #[test]
+    fn pseudonyms() {
+        let mut commits = vec![
+            commit()
+                .author("jon", "jon@email.ca")
+                .on(datetime!(2022-11-29 12:00 UTC))
+                .message("Second")
+                .build(),
+            commit()
+                .author("other", "other@email.ca")
+                .on(datetime!(2022-11-28 12:00 UTC))
+                .message("First\n\nCo-authored-by: jon <jon@email.ca>")
+                .build(),
+            commit()
+                .author("jon", "jon@email.ca")
+                .on(datetime!(2022-11-27 12:00 UTC))
+                .message("Zeroth")
+                .build(),
+        ];
+
+        let pseudonyms = Pseudonyms::of(&commits);
+        pseudonyms.apply(&mut commits);
+
+        assert_eq!(commits[0].author.name, "Author A");
+        assert_eq!(commits[0].author.email, "author.a@anonymous.invalid");
+        assert_eq!(commits[1].author.name, "Author B");
+        assert_eq!(
+            commits[1].message,
+            "First\n\nCo-authored-by: Author A <author.a@anonymous.invalid>"
+        );
+        assert_eq!(commits[2].author.name, "Author A");
+    }
+
+    #[test]
+    fn co_authors_and_mentions() {
+        let history = vec![
+            commit()
+                .author("Jon Doe", "jon@email.ca")
+                .on(datetime!(2022-11-29 12:00 UTC))
+                .message(
+                    "Fix the build Sam Smith broke, thanks jon doe\n\n\
+                 Ask sam@email.ca or ops@email.ca\n\n\
+                 Co-authored-by: Sam Smith <SAM@email.ca>",
+                )
+                .build(),
+            commit()
+                .author("Kim", "kim@email.ca")
+                .on(datetime!(2022-11-28 12:00 UTC))
+                .message("Start")
+                .build(),
+        ];
+
+        // Lettered over the whole history, then given to part of it
+        let pseudonyms = Pseudonyms::of(&history);
+        let mut commits = history[..1].to_vec();
+        pseudonyms.apply(&mut commits);
+
+        let commit = &commits[0];
+        assert_eq!(commit.author.name, "Author B");
+
+        let message = &commit.message;
+        assert!(message.starts_with("Fix the build Author C broke, thanks Author B\n"));
+        assert!(message.contains("Co-authored-by: Author C <author.c@anonymous.invalid>"));
+        assert!(message.contains("Ask author.c@anonymous.invalid or "));
+        assert!(!message.contains("ops@email.ca"));
+        assert!(message.contains("@redacted.invalid"));
+
+        // Co-authors who never committed are named by their pseudonyms too
+        let graph = graph::dot(&graph::collaboration(&commits));
+        assert!(graph.contains("Author C"));
+        assert!(!graph.to_lowercase().contains("sam"));
+    }
+
+    #[test]
+    fn emails() {
+        let mut commits = vec![
+            commit()
+                .author("Jon", "jon@email.ca")
+                .message("First")
+                .build(),
+            commit()
+                .author("Jon", "JON@email.ca")
+                .message(
+                    "Second, ask ops@email.ca\n\n\
+                 Signed-off-by: Jon <jon@email.ca>\n\
+                 Co-authored-by: Sam <sam@email.ca>",
+                )
+                .build(),
+            commit()
+                .author("Jon", "other@email.ca")
+                .message("Third")
+                .build(),
+        ];
+
+        super::emails(&mut commits);
+
+        let email = &commits[0].author.email;
+        assert!(email.ends_with("@redacted.invalid"));
+        assert!(!email.contains("jon"));
+        assert_eq!(commits[0].author.name, "Jon");
+        assert_eq!(&commits[1].author.email, email);
+        assert_eq!(
+            commits[1].message,
+            format!(
+                "Second, ask {}\n\nSigned-off-by: Jon {}\nCo-authored-by: Sam <{}>",
+                super::redact("ops@email.ca"),
+                format_args!("<{}>", email),
+                super::redact("sam@email.ca"),
+            )
+        );
+        assert_ne!(&commits[2].author.email, email);
+    }
+
+    #[test]
+    fn redact() {
+        // Hashes are the same from build to build
+        assert_eq!(
+            super::redact("Jon@Email.ca"),
+            "eac9af716be5235e@redacted.invalid"
+        );
+        assert_eq!(super::redact(""), "cbf29ce484222325@redacted.invalid");
+    }
+
+    #[test]
+    fn letters() {
+        assert_eq!(super::letters(0), "A");
+        assert_eq!(super::letters(25), "Z");
+        assert_eq!(super::letters(26), "AA");
+        assert_eq!(super::letters(27), "AB");
+        assert_eq!(super::letters(26 * 27), "AAA");
+    }
+}
@@ -0,0 +1,104 @@
+use crate::table;
+
+/// What Latin-1 letters from `À` to `ÿ` are spelled as, e.g. `e` for `é`
+const LATIN_1: &str = "AAAAAAACEEEEIIIIDNOOOOOxOUUUUYTsaaaaaaaceeeeiiiidnooooo/ouuuuyty";
+
+/// The text with color and style escapes removed and every other character
+/// outside ASCII spelled in ASCII, for `--ascii`. Bars, sparklines, borders,
+/// arrows, and accented letters have lookalikes, and anything else is a `?`
+/// per column it took up so tables still line up.
+pub fn text(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            skip_escape(&mut chars);
+            continue;
+        }
+
+        if c.is_ascii() {
+            ascii.push(c);
+            continue;
+        }
+
+        match c {
+            'À'..='ÿ' => ascii.push(LATIN_1.as_bytes()[c as usize - 'À' as usize] as char),
+            '‘' | '’' => ascii.push('\''),
+            '“' | '”' => ascii.push('"'),
+            '–' | '—' | '─' | '━' => ascii.push('-'),
+            '│' | '┃' => ascii.push('|'),
+            '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => {
+                ascii.push('+')
+            }
+            '…' => ascii.push_str("..."),
+            '•' => ascii.push('*'),
+            '·' => ascii.push('.'),
+            '↑' => ascii.push('^'),
+            '↓' => ascii.push('v'),
+            '→' => ascii.push('>'),
+            '←' => ascii.push('<'),
+            '▁' => ascii.push('_'),
+            '▂' => ascii.push('.'),
+            '▃' => ascii.push(':'),
+            '▄' => ascii.push('-'),
+            '▅' => ascii.push('='),
+            '▆' => ascii.push('+'),
+            '▇' => ascii.push('*'),
+            '█' => ascii.push('#'),
+            _ => ascii.push_str(&"?".repeat(table::width(&c.to_string()))),
+        }
+    }
+
+    ascii
+}
+
+/// The text with color and style escapes removed but every other character
+/// kept, for output that isn't shown on a terminal understanding them
+pub fn plain(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => skip_escape(&mut chars),
+            _ => plain.push(c),
+        }
+    }
+
+    plain
+}
+
+/// Past the rest of an escape sequence like `\x1b[38;5;12m`, given the
+/// characters after the escape
+fn skip_escape(chars: &mut impl Iterator<Item = char>) {
+    if chars.next() != Some('[') {
+        return;
+    }
+
+    for c in chars {
+        if ('@'..='~').contains(&c) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn text() {
+        assert_eq!(super::text("\x1b[1m\x1b[38;5;5mBy hour:\x1b[m"), "By hour:");
+        assert_eq!(super::text("José Müller"), "Jose Muller");
+        assert_eq!(super::text("│ a │ ▁▄█ ↑ 25% │"), "| a | _-# ^ 25% |");
+        assert_eq!(super::text("🐛 fix… 日本"), "?? fix... ????");
+        assert_eq!(super::text("⚡\u{FE0F}"), "?");
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            super::plain("\x1b[1m\x1b[38;5;5mBy hour:\x1b[m\n│ José ▁▄█"),
+            "By hour:\n│ José ▁▄█"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+/// How long histogram bars are drawn, when asked for rather than left to
+/// whatever draws them
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Bars {
+    /// Commits per character, e.g. `10.0` for a character per ten commits
+    pub scale: Option<f64>,
+    /// The widest a line with a bar on it may be, in columns, e.g. `72` for a
+    /// commit message. Bars shrink to fit.
+    pub max_width: Option<usize>,
+}
+
+impl Bars {
+    /// Characters in the bar for `count` when the largest in the histogram is
+    /// `max` and `prefix` columns come before bars on each line. Without a
+    /// scale the longest bar is `longest` characters or, without that, there's
+    /// a character per commit.
+    pub fn width(&self, count: u64, max: u64, prefix: usize, longest: Option<usize>) -> usize {
+        let max = max.max(1) as f64;
+
+        let mut per_commit = match (self.scale, longest) {
+            (Some(scale), _) => 1.0 / scale,
+            (None, Some(longest)) => longest as f64 / max,
+            (None, None) => 1.0,
+        };
+
+        if let Some(columns) = self.max_width {
+            let room = columns.saturating_sub(prefix) as f64;
+            per_commit = per_commit.min(room / max);
+        }
+
+        (count as f64 * per_commit).round() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bars;
+
+    #[test]
+    fn width() {
+        let default = Bars::default();
+        assert_eq!(default.width(42, 50, 5, None), 42);
+        assert_eq!(default.width(25, 50, 5, Some(20)), 10);
+
+        let scaled = Bars {
+            scale: Some(10.0),
+            ..Default::default()
+        };
+        assert_eq!(scaled.width(42, 50, 5, Some(20)), 4);
+
+        let narrow = Bars {
+            max_width: Some(25),
+            ..Default::default()
+        };
+        assert_eq!(narrow.width(50, 50, 5, None), 20);
+        assert_eq!(narrow.width(10, 50, 5, None), 4);
+        assert_eq!(narrow.width(10, 20, 5, None), 10);
+        assert_eq!(narrow.width(10, 20, 30, None), 0);
+    }
+}
@@ -0,0 +1,50 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+/// Where yeesh keeps state between runs, following the XDG base directory
+/// spec, e.g. `~/.cache/yeesh`
+pub fn dir() -> anyhow::Result<PathBuf> {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .context("Can't find a cache directory, neither $XDG_CACHE_HOME nor $HOME are set")?;
+
+    Ok(base.join("yeesh"))
+}
+
+/// A file in the cache for a repository, named after the repository's full
+/// path so repositories sharing a directory name don't collide. The cache
+/// directory is created if it doesn't exist.
+pub fn file(repo: &Path, name: &str) -> anyhow::Result<PathBuf> {
+    let dir = dir()?;
+    fs::create_dir_all(&dir).with_context(|| format!("Can't create {}", dir.display()))?;
+
+    let repo = repo.canonicalize().unwrap_or_else(|_| repo.to_path_buf());
+
+    Ok(dir.join(format!("{}-{}", key(&repo), name)))
+}
+
+/// The path with anything but letters and numbers replaced, e.g.
+/// `home_jon_yeesh`
+pub fn key(repo: &Path) -> String {
+    repo.to_string_lossy()
+        .trim_start_matches('/')
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn key() {
+        assert_eq!(super::key(Path::new("/home/jon/yeesh")), "home_jon_yeesh");
+        assert_eq!(super::key(Path::new("/tmp/my repo")), "tmp_my_repo");
+    }
+}
@@ -0,0 +1,87 @@
+//! What the terminal yeesh prints to can show, decided once for every report
+//! rather than by each feature. Output piped to a file or another program, or
+//! shown by a dumb terminal like an editor's shell, gets no escapes and keeps
+//! every column.
+
+use std::env;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Escapes for color and style are understood rather than printed as is
+    pub color: bool,
+    /// Columns there are to fit tables in, or none to never cut them short
+    pub width: Option<usize>,
+}
+
+impl Capabilities {
+    /// For standard output, from whether it's a terminal, `TERM`, and
+    /// `NO_COLOR`
+    pub fn detect() -> Capabilities {
+        let tty = termion::is_tty(&io::stdout());
+        let term = env::var("TERM").ok();
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        let width = || {
+            termion::terminal_size()
+                .ok()
+                .map(|(columns, _)| columns as usize)
+        };
+
+        Capabilities::of(tty, term.as_deref(), no_color, width)
+    }
+
+    fn of(
+        tty: bool,
+        term: Option<&str>,
+        no_color: bool,
+        width: impl FnOnce() -> Option<usize>,
+    ) -> Capabilities {
+        // A terminal that didn't say what it is can't be trusted with escapes
+        let dumb = matches!(term, None | Some("") | Some("dumb"));
+
+        Capabilities {
+            color: tty && !dumb && !no_color,
+            width: if tty { width() } else { None },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Capabilities;
+
+    #[test]
+    fn of() {
+        let width = || Some(80);
+        let of = |tty, term, no_color| Capabilities::of(tty, term, no_color, width);
+
+        assert_eq!(
+            of(true, Some("xterm-256color"), false),
+            Capabilities {
+                color: true,
+                width: Some(80)
+            }
+        );
+
+        // Piped
+        assert_eq!(
+            of(false, Some("xterm-256color"), false),
+            Capabilities {
+                color: false,
+                width: None
+            }
+        );
+
+        // Dumb terminals still have a width
+        assert_eq!(
+            of(true, Some("dumb"), false),
+            Capabilities {
+                color: false,
+                width: Some(80)
+            }
+        );
+        assert!(!of(true, None, false).color);
+        assert!(!of(true, Some("xterm"), true).color);
+    }
+}
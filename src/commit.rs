@@ -1,4 +1,9 @@
-use time::OffsetDateTime;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::git::Tag;
 
 #[derive(Debug, Clone, Default)]
 pub struct Author {
@@ -10,16 +15,152 @@ impl Author {
     pub fn new(name: String, email: String) -> Author {
         Author { name, email }
     }
+
+    /// Authors are told apart by email so, like imported commits, those
+    /// without one are told apart by name instead. Those without a name are
+    /// named after their email.
+    pub fn identified(name: &str, email: &str) -> Author {
+        match (name.is_empty(), email.is_empty()) {
+            (_, true) => Author::new(name.to_string(), name.to_string()),
+            (true, false) => Author::new(email.to_string(), email.to_string()),
+            (false, false) => Author::new(name.to_string(), email.to_string()),
+        }
+    }
+
+    /// Whether the name or email contains the text, ignoring case
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.name.to_lowercase().contains(&text) || self.email.to_lowercase().contains(&text)
+    }
+}
+
+/// Lines changed in a single file by a commit. Renamed files are recorded
+/// under their new path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    /// Inserts plus deletes since git's stat block doesn't separate them per
+    /// file. Always zero for binary files.
+    pub changes: u32,
+}
+
+impl FileChange {
+    pub fn new(path: String, changes: u32) -> FileChange {
+        FileChange { path, changes }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RefKind {
+    /// Local or remote, e.g. `main` or `origin/main`
+    Branch,
+    Tag,
+}
+
+impl RefKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RefKind::Branch => "branch",
+            RefKind::Tag => "tag",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<RefKind> {
+        match name {
+            "branch" => Some(RefKind::Branch),
+            "tag" => Some(RefKind::Tag),
+            _ => None,
+        }
+    }
+}
+
+/// A branch or tag pointing at a commit
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ref {
+    pub name: String,
+    pub kind: RefKind,
+}
+
+impl Ref {
+    pub fn new(name: String, kind: RefKind) -> Ref {
+        Ref { name, kind }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Commit {
     pub hash: String,
+    /// Hashes of the commit's parents, first parent first, and empty for a
+    /// root. Histories read without them, e.g. saved git logs, only have
+    /// merges' parents, abbreviated.
+    pub parents: Vec<String>,
+    /// Branches and tags pointing at the commit
+    pub refs: Vec<Ref>,
     pub author: Author,
     pub date: OffsetDateTime,
+    /// When the commit landed, which is later than `date` once it's been
+    /// rebased, amended, or applied by someone else. `None` when the history
+    /// didn't say, e.g. imported from CSV.
+    pub committed: Option<OffsetDateTime>,
+    pub message: String,
     pub files: u32,
     pub inserts: u32,
     pub deletes: u32,
+    pub file_changes: Vec<FileChange>,
+}
+
+impl Commit {
+    /// Lines inserted plus lines deleted
+    pub fn churn(&self) -> u64 {
+        self.inserts as u64 + self.deletes as u64
+    }
+
+    /// The first line of the message
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+
+    /// Everything in the message after the subject and the blank line
+    /// separating them
+    pub fn body(&self) -> &str {
+        match self.message.split_once('\n') {
+            Some((_, body)) => body.trim(),
+            None => "",
+        }
+    }
+}
+
+/// A commit borrowing its text from the log it was parsed from, see
+/// `parser::parse_borrowed()`. Only the subject of the message is kept and
+/// none of the files.
+#[derive(Debug, Clone, Copy)]
+pub struct CommitRef<'a> {
+    /// Space separated, as git prints them
+    pub parents: &'a str,
+    pub name: &'a str,
+    pub email: &'a str,
+    pub date: OffsetDateTime,
+    pub committed: Option<OffsetDateTime>,
+    pub subject: &'a str,
+    pub files: u32,
+    pub inserts: u32,
+    pub deletes: u32,
+}
+
+impl Default for CommitRef<'_> {
+    fn default() -> Self {
+        Self {
+            parents: Default::default(),
+            name: Default::default(),
+            email: Default::default(),
+            date: OffsetDateTime::UNIX_EPOCH,
+            committed: Default::default(),
+            subject: Default::default(),
+            files: Default::default(),
+            inserts: Default::default(),
+            deletes: Default::default(),
+        }
+    }
 }
 
 impl Default for Commit {
@@ -28,11 +169,504 @@ impl Default for Commit {
 
         Self {
             hash: Default::default(),
+            parents: Default::default(),
+            refs: Default::default(),
             author: Default::default(),
             date: right_now,
+            committed: Default::default(),
+            message: Default::default(),
             files: Default::default(),
             inserts: Default::default(),
             deletes: Default::default(),
+            file_changes: Default::default(),
+        }
+    }
+}
+
+/// Which way commits are sorted by date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Like git log
+    Newest,
+    Oldest,
+}
+
+impl FromStr for Order {
+    type Err = String;
+
+    fn from_str(order: &str) -> Result<Order, String> {
+        match order {
+            "newest" => Ok(Order::Newest),
+            "oldest" => Ok(Order::Oldest),
+            _ => Err(format!("Unknown order {}", order)),
+        }
+    }
+}
+
+/// Sort commits by date, e.g. ones imported from several files or merged from
+/// several repositories, with ties broken by hash so the order is stable
+pub fn sort(commits: &mut [Commit], order: Order) {
+    match order {
+        Order::Newest => commits.sort_by(|a, b| b.date.cmp(&a.date).then(a.hash.cmp(&b.hash))),
+        Order::Oldest => commits.sort_by(|a, b| a.date.cmp(&b.date).then(a.hash.cmp(&b.hash))),
+    }
+}
+
+/// Group commits by author. Authors are identified by email since names are
+/// more likely to vary, and each is named after their first commit given, i.e.
+/// the most recent in git log's order. Groups are in the order authors first
+/// appear.
+pub fn by_author(commits: &[Commit]) -> Vec<(&Author, Vec<&Commit>)> {
+    let mut groups: Vec<(&Author, Vec<&Commit>)> = Vec::new();
+    let mut by_email: HashMap<&str, usize> = HashMap::new();
+
+    for commit in commits {
+        let i = *by_email.entry(&commit.author.email).or_insert_with(|| {
+            groups.push((&commit.author, Vec::new()));
+            groups.len() - 1
+        });
+
+        groups[i].1.push(commit);
+    }
+
+    groups
+}
+
+/// Keep only commits made since `since`, inclusive, and before `until`, e.g.
+/// the end of the day `range::parse_until()` reads a date as
+pub fn retain_between(
+    commits: &mut Vec<Commit>,
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+) {
+    commits.retain(|commit| is_between(commit.date, since, until));
+}
+
+/// Whether the date is from `since` on and before `until`, see
+/// `retain_between()`
+pub fn is_between(
+    date: OffsetDateTime,
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+) -> bool {
+    since.is_none_or(|since| date >= since) && until.is_none_or(|until| date < until)
+}
+
+/// Keep only commits by an author matching any of the texts, or every commit
+/// when there are none, e.g. for `--author`
+pub fn retain_authors(commits: &mut Vec<Commit>, authors: &[String]) {
+    if !authors.is_empty() {
+        commits.retain(|commit| authors.iter().any(|author| commit.author.matches(author)));
+    }
+}
+
+/// What authors without a team are grouped as by `into_teams()`
+pub const NO_TEAM: &str = "(no team)";
+
+/// Make each commit's author their team, e.g. from `Config::teams()`, so every
+/// report by author is by team instead. Emails are matched ignoring case.
+pub fn into_teams(commits: &mut [Commit], teams: &HashMap<String, String>) {
+    for commit in commits {
+        let team = teams
+            .get(&commit.author.email.to_lowercase())
+            .map(String::as_str)
+            .unwrap_or(NO_TEAM);
+
+        commit.author = Author::new(team.to_string(), team.to_string());
+    }
+}
+
+/// Move commits recorded in UTC to their author's offset, e.g. from
+/// `Config::timezones()`, for authors whose machines are set to UTC but who
+/// work elsewhere, so reports by time of day see their own hours. Commits
+/// recorded in another offset already say where they were made. Emails are
+/// matched ignoring case.
+pub fn into_timezones(commits: &mut [Commit], timezones: &HashMap<String, UtcOffset>) {
+    for commit in commits {
+        commit.date = in_timezone(commit.date, &commit.author.email, timezones);
+    }
+}
+
+/// The date in the author's timezone when it's UTC, see `into_timezones()`
+pub fn in_timezone(
+    date: OffsetDateTime,
+    email: &str,
+    timezones: &HashMap<String, UtcOffset>,
+) -> OffsetDateTime {
+    match timezones.get(&email.to_lowercase()) {
+        Some(offset) if date.offset() == UtcOffset::UTC => date.to_offset(*offset),
+        _ => date,
+    }
+}
+
+/// One identity to count as another, e.g. `Jon Doe=Jonathan Neufeld` from
+/// `--alias`, for merges too one-off for `.mailmap`. Either side is a name or
+/// an email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alias {
+    pub from: String,
+    pub to: String,
+}
+
+impl FromStr for Alias {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Alias, String> {
+        match text.split_once('=') {
+            Some((from, to)) if !from.trim().is_empty() && !to.trim().is_empty() => Ok(Alias {
+                from: from.trim().to_string(),
+                to: to.trim().to_string(),
+            }),
+            _ => Err(format!("Expected an alias like from=to, not {}", text)),
+        }
+    }
+}
+
+/// Make authors named or emailed like an alias's `from` the author its `to`
+/// names or emails, so they're counted as one. When no commit is by the `to`
+/// author they're a new author named `to`. Names and emails are matched
+/// ignoring case.
+pub fn alias(commits: &mut [Commit], aliases: &[Alias]) {
+    let is = |author: &Author, identity: &str| {
+        let identity = identity.to_lowercase();
+        author.name.to_lowercase() == identity || author.email.to_lowercase() == identity
+    };
+
+    for alias in aliases {
+        let to = commits
+            .iter()
+            .map(|commit| &commit.author)
+            .find(|author| is(author, &alias.to) && !is(author, &alias.from))
+            .cloned()
+            .unwrap_or_else(|| Author::new(alias.to.clone(), alias.to.clone()));
+
+        for commit in commits.iter_mut() {
+            if is(&commit.author, &alias.from) {
+                commit.author = to.clone();
+            }
+        }
+    }
+}
+
+/// Every tag pointing at one of the commits, e.g. for release reports
+pub fn tags(commits: &[Commit]) -> Vec<Tag> {
+    commits
+        .iter()
+        .flat_map(|commit| {
+            commit
+                .refs
+                .iter()
+                .filter(|r| r.kind == RefKind::Tag)
+                .map(|r| Tag::new(r.name.clone(), commit.hash.clone()))
+        })
+        .collect()
+}
+
+/// Split commits into one pseudo-history per directory, e.g. each project in a
+/// monorepo, using the first `depth` directories of each changed path. Files
+/// in shallower directories belong to those, and files at the top to
+/// `(root)`. A commit touching several
+/// directories appears in each with only its changes there, and its inserts
+/// and deletes are divided in proportion since git doesn't split them per
+/// file.
+pub fn by_dir(commits: &[Commit], depth: usize) -> Vec<(String, Vec<Commit>)> {
+    let mut dirs: BTreeMap<String, Vec<Commit>> = BTreeMap::new();
+
+    for commit in commits {
+        let mut changes: BTreeMap<String, Vec<FileChange>> = BTreeMap::new();
+
+        for change in &commit.file_changes {
+            changes
+                .entry(dir(&change.path, depth))
+                .or_default()
+                .push(change.clone());
+        }
+
+        let total: u64 = commit.file_changes.iter().map(|c| c.changes as u64).sum();
+
+        for (dir, file_changes) in changes {
+            let share = match total {
+                0 => 1.0 / commit.file_changes.len() as f64,
+                _ => file_changes.iter().map(|c| c.changes as f64).sum::<f64>() / total as f64,
+            };
+
+            dirs.entry(dir).or_default().push(Commit {
+                files: file_changes.len() as u32,
+                inserts: (commit.inserts as f64 * share).round() as u32,
+                deletes: (commit.deletes as f64 * share).round() as u32,
+                file_changes,
+                ..commit.clone()
+            });
         }
     }
+
+    dirs.into_iter().collect()
+}
+
+/// Split commits into one history per month they were made in, oldest first,
+/// named like `2022-11`
+pub fn by_month(commits: &[Commit]) -> Vec<(String, Vec<Commit>)> {
+    let mut months: BTreeMap<String, Vec<Commit>> = BTreeMap::new();
+
+    for commit in commits {
+        let month = format!("{}-{:02}", commit.date.year(), commit.date.month() as u8);
+        months.entry(month).or_default().push(commit.clone());
+    }
+
+    months.into_iter().collect()
+}
+
+/// The first `depth` directories of a path, e.g. `src/report` for
+/// `src/report/size.rs` at depth 2
+fn dir(path: &str, depth: usize) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    let depth = depth.min(parts.len() - 1);
+
+    if depth == 0 {
+        return "(root)".to_string();
+    }
+
+    parts[..depth].join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use std::collections::HashMap;
+
+    use crate::range;
+    use crate::test_support::commit;
+
+    use super::{Alias, Commit, Order};
+
+    #[test]
+    fn retain_authors() {
+        let mut commits = vec![
+            commit().author("Jon", "jon@email.ca").build(),
+            commit().author("Sam", "sam@email.ca").build(),
+            commit().author("Ana", "jon.bot@ci.dev").build(),
+        ];
+
+        super::retain_authors(&mut commits, &[]);
+        assert_eq!(commits.len(), 3);
+
+        super::retain_authors(&mut commits, &["JON".to_string(), "kim".to_string()]);
+
+        let names: Vec<&str> = commits
+            .iter()
+            .map(|commit| commit.author.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Jon", "Ana"]);
+    }
+
+    #[test]
+    fn retain_between() {
+        let mut commits = vec![
+            commit().on(datetime!(2023-07-01 0:00 UTC)).build(),
+            commit().on(datetime!(2023-06-30 18:00 UTC)).build(),
+            commit().on(datetime!(2023-01-01 0:00 UTC)).build(),
+            commit().on(datetime!(2022-12-31 23:59 UTC)).build(),
+            // Before 2023 in its own offset but not in UTC
+            commit().on(datetime!(2022-12-31 23:00 -2)).build(),
+        ];
+
+        super::retain_between(&mut commits, None, None);
+        assert_eq!(commits.len(), 5);
+
+        // Like --since 2023-01-01 --until 2023-06-30, which includes June 30th
+        let now = datetime!(2024-01-01 0:00 UTC);
+        super::retain_between(
+            &mut commits,
+            Some(range::parse("2023-01-01", now).unwrap()),
+            Some(range::parse_until("2023-06-30", now).unwrap()),
+        );
+
+        let dates: Vec<_> = commits.iter().map(|commit| commit.date).collect();
+        assert_eq!(
+            dates,
+            vec![
+                datetime!(2023-06-30 18:00 UTC),
+                datetime!(2023-01-01 0:00 UTC),
+                datetime!(2022-12-31 23:00 -2)
+            ]
+        );
+    }
+
+    #[test]
+    fn retain_aliased_authors() {
+        let mut commits = vec![
+            commit().author("Jonathan Neufeld", "jon@email.ca").build(),
+            commit().author("jd", "jd@old-laptop.local").build(),
+            commit().author("Sam", "sam@email.ca").build(),
+        ];
+
+        // Aliased before filtering like every report does
+        let aliases = vec!["jd=Jonathan Neufeld".parse::<Alias>().unwrap()];
+        super::alias(&mut commits, &aliases);
+        super::retain_authors(&mut commits, &["jonathan".to_string()]);
+
+        assert_eq!(commits.len(), 2);
+        assert!(commits
+            .iter()
+            .all(|commit| commit.author.email == "jon@email.ca"));
+    }
+
+    #[test]
+    fn dir() {
+        assert_eq!(super::dir("src/report/size.rs", 1), "src");
+        assert_eq!(super::dir("src/report/size.rs", 2), "src/report");
+        assert_eq!(super::dir("src/main.rs", 2), "src");
+        assert_eq!(super::dir("README.md", 1), "(root)");
+    }
+
+    #[test]
+    fn sort() {
+        let mut commits = vec![
+            commit()
+                .hash("b")
+                .on(datetime!(2022-11-28 12:00 UTC))
+                .build(),
+            commit()
+                .hash("c")
+                .on(datetime!(2022-11-29 12:00 UTC))
+                .build(),
+            commit()
+                .hash("a")
+                .on(datetime!(2022-11-28 12:00 UTC))
+                .build(),
+        ];
+        let hashes = |commits: &[Commit]| -> Vec<String> {
+            commits.iter().map(|commit| commit.hash.clone()).collect()
+        };
+
+        super::sort(&mut commits, Order::Newest);
+        assert_eq!(hashes(&commits), vec!["c", "a", "b"]);
+
+        super::sort(&mut commits, Order::Oldest);
+        assert_eq!(hashes(&commits), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn into_teams() {
+        let mut commits = vec![
+            commit().author("Jon", "Jon@email.ca").build(),
+            commit().author("Jon", "other@email.ca").build(),
+        ];
+
+        let teams = HashMap::from([("jon@email.ca".to_string(), "Platform".to_string())]);
+        super::into_teams(&mut commits, &teams);
+
+        assert_eq!(commits[0].author.name, "Platform");
+        assert_eq!(commits[0].author.email, "Platform");
+        assert_eq!(commits[1].author.name, super::NO_TEAM);
+    }
+
+    #[test]
+    fn into_timezones() {
+        let mut commits = vec![
+            commit()
+                .author("Jon", "Jon@email.ca")
+                .on(datetime!(2022-11-28 17:30 UTC))
+                .build(),
+            commit()
+                .author("Jon", "jon@email.ca")
+                .on(datetime!(2022-11-28 17:30 +1))
+                .build(),
+            commit()
+                .author("Jon", "other@email.ca")
+                .on(datetime!(2022-11-28 17:30 UTC))
+                .build(),
+        ];
+
+        let timezones = HashMap::from([("jon@email.ca".to_string(), time::macros::offset!(-8))]);
+        super::into_timezones(&mut commits, &timezones);
+
+        // The same moment, in the author's own hours
+        assert_eq!(commits[0].date, datetime!(2022-11-28 9:30 -8));
+        assert_eq!(commits[0].date.hour(), 9);
+        // Made somewhere that already said where it was
+        assert_eq!(commits[1].date.hour(), 17);
+        assert_eq!(commits[2].date.hour(), 17);
+    }
+
+    #[test]
+    fn alias() {
+        let mut commits = vec![
+            commit().author("Jon Doe", "jon@home.ca").build(),
+            commit().author("Jonathan Neufeld", "jon@email.ca").build(),
+            commit().author("jd", "jd@laptop").build(),
+            commit().author("Other", "other@email.ca").build(),
+        ];
+
+        let aliases: Vec<Alias> = ["jon doe=Jonathan Neufeld", "jd@laptop = Jon D"]
+            .iter()
+            .map(|alias| alias.parse().unwrap())
+            .collect();
+        super::alias(&mut commits, &aliases);
+
+        assert_eq!(commits[0].author.email, "jon@email.ca");
+        assert_eq!(commits[0].author.name, "Jonathan Neufeld");
+        assert_eq!(commits[2].author.name, "Jon D");
+        assert_eq!(commits[2].author.email, "Jon D");
+        assert_eq!(commits[3].author.name, "Other");
+
+        assert!("Jon Doe".parse::<Alias>().is_err());
+        assert!("=Jon".parse::<Alias>().is_err());
+    }
+
+    #[test]
+    fn by_dir() {
+        let commits = vec![commit()
+            .lines(32, 8)
+            .changes(&[("api/main.rs", 30), ("web/index.js", 5), ("web/app.js", 5)])
+            .build()];
+
+        let dirs = super::by_dir(&commits, 1);
+
+        assert_eq!(dirs.len(), 2);
+        assert_eq!(dirs[0].0, "api");
+        assert_eq!(dirs[0].1[0].files, 1);
+        assert_eq!(dirs[0].1[0].inserts, 24);
+        assert_eq!(dirs[0].1[0].deletes, 6);
+        assert_eq!(dirs[1].0, "web");
+        assert_eq!(dirs[1].1[0].files, 2);
+        assert_eq!(dirs[1].1[0].churn(), 10);
+    }
+
+    #[test]
+    fn by_month() {
+        let commits = vec![
+            commit()
+                .hash("a")
+                .on(datetime!(2023-01-02 9:00 UTC))
+                .build(),
+            commit()
+                .hash("b")
+                .on(datetime!(2022-12-31 23:00 -8))
+                .build(),
+            commit()
+                .hash("c")
+                .on(datetime!(2022-11-28 16:00 -8))
+                .build(),
+            commit().hash("d").on(datetime!(2022-12-01 0:30 +1)).build(),
+        ];
+
+        let months = super::by_month(&commits);
+        let hashes = |i: usize| -> Vec<&str> {
+            months[i]
+                .1
+                .iter()
+                .map(|commit| commit.hash.as_str())
+                .collect()
+        };
+
+        assert_eq!(months.len(), 3);
+        assert_eq!(months[0].0, "2022-11");
+        assert_eq!(hashes(0), vec!["c"]);
+        assert_eq!(months[1].0, "2022-12");
+        assert_eq!(hashes(1), vec!["b", "d"]);
+        assert_eq!(months[2].0, "2023-01");
+        assert_eq!(hashes(2), vec!["a"]);
+    }
 }
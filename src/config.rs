@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use regex::Regex;
+use time::UtcOffset;
+
+use crate::report::timezone;
+
+/// Settings from a config file of `[section]`s holding `key = value` lines,
+/// e.g.
+///
+/// ```text
+/// # Squads, by email
+/// [teams]
+/// jon@email.ca = Platform
+///
+/// [issues]
+/// pattern = \bYEESH-\d+\b
+///
+/// # Machines set to UTC
+/// [timezones]
+/// jon@email.ca = -08:00
+///
+/// [tests]
+/// pattern = ^checks/
+/// pattern = _check\.rs$
+/// ```
+///
+/// Lines starting with `#` or `;` are comments. Keys may repeat.
+#[derive(Debug, Default)]
+pub struct Config {
+    sections: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl Config {
+    pub fn parse(text: &str) -> anyhow::Result<Config> {
+        let mut config = Config::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(section) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                config
+                    .sections
+                    .push((section.trim().to_string(), Vec::new()));
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some(pair) => pair,
+                None => bail!("Expected `key = value` on line {}: {:?}", i + 1, line),
+            };
+
+            match config.sections.last_mut() {
+                Some((_, values)) => {
+                    values.push((key.trim().to_string(), value.trim().to_string()))
+                }
+                None => bail!("Expected a [section] before line {}: {:?}", i + 1, line),
+            }
+        }
+
+        // Checked up front rather than once a history's been read
+        for (email, offset) in config.section("timezones") {
+            if let Err(why) = timezone::parse_offset(offset) {
+                bail!("{} for {} in [timezones]", why, email);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Every `key = value` in every section with the name, in order
+    pub fn section<'a>(&'a self, name: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.sections
+            .iter()
+            .filter(move |(section, _)| section == name)
+            .flat_map(|(_, values)| values)
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// The last value for the key in every section with the name
+    pub fn value<'a>(&'a self, section: &'a str, key: &str) -> Option<&'a str> {
+        self.section(section)
+            .filter(|(name, _)| *name == key)
+            .map(|(_, value)| value)
+            .last()
+    }
+
+    /// How to find issue references in messages, from `pattern` in `[issues]`
+    pub fn issue_pattern(&self) -> anyhow::Result<Option<Regex>> {
+        self.value("issues", "pattern")
+            .map(|pattern| {
+                Regex::new(pattern).with_context(|| format!("Invalid issue pattern {}", pattern))
+            })
+            .transpose()
+    }
+
+    /// Which files are tests, from every `pattern` in `[tests]`
+    pub fn test_patterns(&self) -> anyhow::Result<Vec<Regex>> {
+        self.section("tests")
+            .filter(|(key, _)| *key == "pattern")
+            .map(|(_, pattern)| {
+                Regex::new(pattern).with_context(|| format!("Invalid test pattern {}", pattern))
+            })
+            .collect()
+    }
+
+    /// Team names by lowercase email from `[teams]`
+    pub fn teams(&self) -> HashMap<String, String> {
+        self.section("teams")
+            .map(|(email, team)| (email.to_lowercase(), team.to_string()))
+            .collect()
+    }
+
+    /// Offsets by lowercase email from `[timezones]`, e.g. `-08:00`, which
+    /// `parse()` made sure are valid
+    pub fn timezones(&self) -> HashMap<String, UtcOffset> {
+        self.section("timezones")
+            .filter_map(|(email, offset)| {
+                let offset = timezone::parse_offset(offset).ok()?;
+                Some((email.to_lowercase(), offset))
+            })
+            .collect()
+    }
+}
+
+/// The config at `path` or, without one, the user's config if they have one,
+/// e.g. `~/.config/yeesh/config`
+pub fn load(path: Option<&Path>) -> anyhow::Result<Config> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => match default_path().filter(|path| path.exists()) {
+            Some(path) => path,
+            None => return Ok(Config::default()),
+        },
+    };
+
+    // Both for the user to fix, so said the same way
+    let context = || format!("Can't read config {}", path.display());
+    let text = fs::read_to_string(&path).with_context(context)?;
+
+    Config::parse(&text).with_context(context)
+}
+
+/// Following the XDG base directory spec like the cache
+fn default_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+    Some(base.join("yeesh").join("config"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Config;
+
+    #[test]
+    fn parse() {
+        let config = Config::parse(
+            "# Squads\n\
+             [teams]\n\
+             Jon@Email.ca = Platform\n\
+             \n\
+             [other]\n\
+             key = a = b\n\
+             [teams]\n\
+             ; More squads\n\
+             not.jon@email.ca = Web Team\n",
+        )
+        .unwrap();
+
+        let teams = config.teams();
+        assert_eq!(teams["jon@email.ca"], "Platform");
+        assert_eq!(teams["not.jon@email.ca"], "Web Team");
+
+        let other: Vec<(&str, &str)> = config.section("other").collect();
+        assert_eq!(other, vec![("key", "a = b")]);
+        assert_eq!(config.value("other", "key"), Some("a = b"));
+        assert_eq!(config.value("other", "missing"), None);
+
+        let issues = Config::parse("[issues]\npattern = \\bYEESH-\\d+").unwrap();
+        let pattern = issues.issue_pattern().unwrap().unwrap();
+        assert!(pattern.is_match("Fix YEESH-12"));
+
+        let invalid = Config::parse("[issues]\npattern = (").unwrap();
+        assert!(invalid.issue_pattern().is_err());
+
+        let tests = Config::parse("[tests]\npattern = ^checks/\npattern = _check\\.rs$").unwrap();
+        let patterns = tests.test_patterns().unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert!(patterns[1].is_match("src/parser_check.rs"));
+        assert!(Config::default().test_patterns().unwrap().is_empty());
+
+        let timezones = Config::parse("[timezones]\nJon@Email.ca = -08:00").unwrap();
+        let offsets = timezones.timezones();
+        assert_eq!(offsets["jon@email.ca"].whole_hours(), -8);
+        assert_eq!(
+            Config::parse("[timezones]\njon@email.ca = PST")
+                .unwrap_err()
+                .to_string(),
+            "Invalid UTC offset PST for jon@email.ca in [timezones]"
+        );
+
+        let missing = super::load(Some(Path::new("/nowhere/yeesh/config"))).unwrap_err();
+        assert!(format!("{:#}", missing)
+            .starts_with("Can't read config /nowhere/yeesh/config: No such file"));
+
+        assert!(Config::parse("key = value").is_err());
+        assert!(Config::parse("[teams]\njon").is_err());
+    }
+}
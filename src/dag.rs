@@ -0,0 +1,134 @@
+//! The history as a graph of commits joined to their parents, for walking it
+//! the way git does rather than in the order it was read
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::commit::Commit;
+
+/// Commits by hash, to find each one's parents among them
+pub struct Dag<'a> {
+    commits: &'a [Commit],
+    by_hash: BTreeMap<&'a str, usize>,
+}
+
+impl<'a> Dag<'a> {
+    pub fn new(commits: &'a [Commit]) -> Dag<'a> {
+        let by_hash = commits
+            .iter()
+            .enumerate()
+            .map(|(i, commit)| (commit.hash.as_str(), i))
+            .collect();
+
+        Dag { commits, by_hash }
+    }
+
+    /// The commit with the hash, or the only one whose hash starts with it
+    /// since merges read from saved logs have abbreviated parents
+    pub fn find(&self, hash: &str) -> Option<&'a Commit> {
+        if let Some(&i) = self.by_hash.get(hash) {
+            return Some(&self.commits[i]);
+        }
+
+        let mut matches = self
+            .by_hash
+            .range(hash..)
+            .take_while(|(full, _)| full.starts_with(hash));
+
+        match (matches.next(), matches.next()) {
+            (Some((_, &i)), None) if !hash.is_empty() => Some(&self.commits[i]),
+            _ => None,
+        }
+    }
+
+    /// The parent the commit was made on, or merged into, when it's in the
+    /// history
+    pub fn first_parent(&self, commit: &Commit) -> Option<&'a Commit> {
+        commit.parents.first().and_then(|parent| self.find(parent))
+    }
+
+    /// The first commit and its first parents back to a root or the oldest
+    /// commit read, like `git log --first-parent`. Commits merged in from
+    /// other branches are left out but the merges themselves kept.
+    pub fn first_parents(&self) -> Vec<&'a Commit> {
+        let mut chain = Vec::new();
+        let mut seen = HashSet::new();
+        let mut next = self.commits.first();
+
+        // Parents are always older so there are no cycles, unless hashes were
+        // made up, e.g. by an import
+        while let Some(commit) = next {
+            if !seen.insert(commit.hash.as_str()) {
+                break;
+            }
+
+            chain.push(commit);
+            next = self.first_parent(commit);
+        }
+
+        chain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::commit;
+
+    use super::Dag;
+
+    #[test]
+    fn first_parents() {
+        // main: a1 - b2 ------ d4 (merge)
+        //              \      /
+        // feature:      c3 --
+        let commits = vec![
+            commit().hash("d4d4").parents(&["b2b2", "c3c3"]).build(),
+            commit().hash("c3c3").parents(&["b2b2"]).build(),
+            commit().hash("b2b2").parents(&["a1a1"]).build(),
+            commit().hash("a1a1").parents(&[]).build(),
+        ];
+
+        let dag = Dag::new(&commits);
+        let hashes: Vec<&str> = dag
+            .first_parents()
+            .iter()
+            .map(|commit| commit.hash.as_str())
+            .collect();
+
+        assert_eq!(hashes, vec!["d4d4", "b2b2", "a1a1"]);
+        assert_eq!(dag.first_parent(&commits[1]).unwrap().hash, "b2b2");
+        assert!(dag.first_parent(&commits[3]).is_none());
+    }
+
+    #[test]
+    fn find() {
+        let commits = vec![
+            commit().hash("abc123").parents(&["abd"]).build(),
+            commit().hash("abd999").parents(&[]).build(),
+        ];
+
+        let dag = Dag::new(&commits);
+
+        assert_eq!(dag.find("abd999").unwrap().hash, "abd999");
+        assert_eq!(dag.find("abd").unwrap().hash, "abd999");
+        // Abbreviated too far to tell which it is
+        assert!(dag.find("ab").is_none());
+        assert!(dag.find("").is_none());
+        assert!(dag.find("fff").is_none());
+
+        // Merges' parents are abbreviated in saved logs
+        assert_eq!(dag.first_parents().len(), 2);
+
+        // A parent outside the history, e.g. before --since, ends the walk
+        let commits = vec![commit().hash("abd999").parents(&["0ld"]).build()];
+        assert_eq!(Dag::new(&commits).first_parents().len(), 1);
+    }
+
+    #[test]
+    fn cycle() {
+        let commits = vec![
+            commit().hash("a").parents(&["b"]).build(),
+            commit().hash("b").parents(&["a"]).build(),
+        ];
+        assert_eq!(Dag::new(&commits).first_parents().len(), 2);
+    }
+}
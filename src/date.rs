@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use time::format_description;
+use time::macros::date;
+
+use crate::report::{Report, Value};
+
+/// Where the components a format can use are described
+const REFERENCE: &str = "https://time-rs.github.io/book/api/format-description.html";
+
+/// How dates are written for people to read, e.g. `[day].[month].[year]`.
+/// Machine formats like JSON always keep them as `2022-11-28`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateFormat(String);
+
+impl FromStr for DateFormat {
+    type Err = String;
+
+    /// Checks the pattern can write a date so a typo fails at startup rather
+    /// than halfway through printing
+    fn from_str(pattern: &str) -> Result<DateFormat, String> {
+        let items = format_description::parse(pattern).map_err(|error| {
+            format!(
+                "{}. Formats are like [year]-[month]-[day] or \
+                [month repr:short] [day padding:none], see {}",
+                error, REFERENCE
+            )
+        })?;
+
+        date!(2022 - 11 - 28).format(&items).map_err(|_| {
+            "Dates have no time of day or offset, only components like [year], \
+            [month], [day], and [weekday]"
+                .to_string()
+        })?;
+
+        Ok(DateFormat(pattern.to_string()))
+    }
+}
+
+impl Default for DateFormat {
+    fn default() -> DateFormat {
+        DateFormat("[year]-[month]-[day]".to_string())
+    }
+}
+
+impl DateFormat {
+    /// The reports with every date written in this format, or as they are
+    /// when it's the default
+    pub fn apply<'a>(&self, reports: &'a [Report]) -> Cow<'a, [Report]> {
+        if *self == DateFormat::default() {
+            return Cow::Borrowed(reports);
+        }
+
+        // Checked when parsed
+        let items = format_description::parse(&self.0).unwrap();
+
+        let mut reports = reports.to_vec();
+
+        for value in reports
+            .iter_mut()
+            .flat_map(|report| report.table.rows.iter_mut())
+            .flatten()
+        {
+            if let Value::Date(date) = value {
+                *value = Value::Text(date.format(&items).unwrap());
+            }
+        }
+
+        Cow::Owned(reports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use crate::report::{Report, Table, Value};
+
+    use super::DateFormat;
+
+    #[test]
+    fn apply() {
+        let mut table = Table::new(&["release", "date"]);
+        table.push(vec![
+            Value::Text("v1.0".to_string()),
+            Value::Date(date!(2022 - 11 - 28)),
+        ]);
+
+        let reports = vec![Report::new("Releases:", table)];
+
+        let format: DateFormat = "[day].[month].[year]".parse().unwrap();
+        assert_eq!(
+            format.apply(&reports)[0].table.rows[0][1],
+            Value::Text("28.11.2022".to_string())
+        );
+
+        let format: DateFormat = "[weekday repr:short] [month repr:short] [day padding:none]"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            format.apply(&reports)[0].table.rows[0][1],
+            Value::Text("Mon Nov 28".to_string())
+        );
+
+        let unchanged = DateFormat::default().apply(&reports);
+        assert_eq!(unchanged[0].table.rows[0][1].to_string(), "2022-11-28");
+    }
+
+    #[test]
+    fn invalid() {
+        let error = "[year".parse::<DateFormat>().unwrap_err();
+        assert!(error.starts_with("unclosed opening bracket"));
+
+        let error = "[year] [nonsense]".parse::<DateFormat>().unwrap_err();
+        assert!(error.contains("Formats are like"));
+
+        let error = "[year] [hour]".parse::<DateFormat>().unwrap_err();
+        assert!(error.starts_with("Dates have no time of day"));
+    }
+}
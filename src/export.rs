@@ -0,0 +1,4 @@
+pub mod cube;
+pub mod json;
+pub mod parquet;
+pub mod sqlite;
@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::commit::{self, Author, Commit};
+use crate::histogram::{self, Weight, WEEKDAYS};
+use crate::json::{self, SCHEMA_VERSION};
+
+/// Commits by every author for every hour of every weekday, the outer index
+/// being the weekday from Monday like `histogram::by_weekday_and_hour()`.
+/// Authors with the most commits come first, then by name.
+pub fn cube(commits: &[Commit]) -> Vec<(&Author, [[u64; 24]; 7])> {
+    let mut cube: Vec<(&Author, [[u64; 24]; 7])> = commit::by_author(commits)
+        .into_iter()
+        .map(|(author, commits)| {
+            (
+                author,
+                histogram::by_weekday_and_hour(commits, Weight::Count),
+            )
+        })
+        .collect();
+
+    let total = |counts: &[[u64; 24]; 7]| -> u64 { counts.iter().flatten().sum() };
+
+    cube.sort_by(|a, b| total(&b.1).cmp(&total(&a.1)).then(a.0.name.cmp(&b.0.name)));
+
+    cube
+}
+
+/// Write the cube as CSV with a row for every author, weekday, and hour with
+/// commits. Empty cells are left out since most are for most authors.
+pub fn write_csv(path: &Path, commits: &[Commit]) -> anyhow::Result<()> {
+    fs::write(path, csv(&cube(commits))).with_context(|| format!("Can't write {}", path.display()))
+}
+
+/// Write the cube as JSON with every author's counts as 7 lists of 24, from
+/// Monday and midnight
+pub fn write_json(path: &Path, commits: &[Commit]) -> anyhow::Result<()> {
+    fs::write(path, json(&cube(commits))).with_context(|| format!("Can't write {}", path.display()))
+}
+
+fn csv(cube: &[(&Author, [[u64; 24]; 7])]) -> String {
+    let mut out = String::from("author,email,weekday,hour,commits\n");
+
+    for (author, counts) in cube {
+        for (weekday, hours) in WEEKDAYS.iter().zip(counts) {
+            for (hour, count) in hours.iter().enumerate().filter(|(_, count)| **count > 0) {
+                out.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    field(&author.name),
+                    field(&author.email),
+                    weekday,
+                    hour,
+                    count
+                ));
+            }
+        }
+    }
+
+    out
+}
+
+/// The cube as JSON, each author's commits indexed by weekday then hour
+#[derive(Serialize)]
+struct CubeJson<'a> {
+    schema_version: u32,
+    weekdays: [&'static str; 7],
+    hours: u32,
+    authors: Vec<AuthorJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct AuthorJson<'a> {
+    name: &'a str,
+    email: &'a str,
+    commits: &'a [[u64; 24]; 7],
+}
+
+fn json(cube: &[(&Author, [[u64; 24]; 7])]) -> String {
+    let mut json = json::to_string(&CubeJson {
+        schema_version: SCHEMA_VERSION,
+        weekdays: WEEKDAYS,
+        hours: 24,
+        authors: cube
+            .iter()
+            .map(|(author, commits)| AuthorJson {
+                name: &author.name,
+                email: &author.email,
+                commits,
+            })
+            .collect(),
+    });
+
+    json.push('\n');
+    json
+}
+
+/// A CSV field, quoted when it has to be
+fn field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::test_support::commit;
+
+    #[test]
+    fn cube() {
+        let commits = vec![
+            // A Monday at 9 and a Sunday at 23
+            commit().by("Jon").on(datetime!(2022-11-28 9:15 -8)).build(),
+            commit()
+                .by("Jon, Jr.")
+                .on(datetime!(2022-11-27 23:00 -8))
+                .build(),
+            commit()
+                .by("Jon, Jr.")
+                .on(datetime!(2022-11-27 23:30 -8))
+                .build(),
+        ];
+
+        let cube = super::cube(&commits);
+
+        assert_eq!(cube[0].0.name, "Jon, Jr.");
+        assert_eq!(cube[0].1[6][23], 2);
+        assert_eq!(cube[1].1[0][9], 1);
+
+        assert_eq!(
+            super::csv(&cube),
+            "author,email,weekday,hour,commits\n\
+             \"Jon, Jr.\",\"jon, jr.@email.ca\",Sun,23,2\n\
+             Jon,jon@email.ca,Mon,9,1\n"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&super::json(&cube)).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["authors"][0]["commits"][6][23], 2);
+        assert_eq!(json["authors"][1]["commits"].as_array().unwrap().len(), 7);
+    }
+}
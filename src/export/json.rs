@@ -0,0 +1,289 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde::Serialize;
+use serde_json::Value;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::commit::{Author, Commit, FileChange, Ref, RefKind};
+use crate::json::{self, SCHEMA_VERSION};
+
+/// Write commits to a file as a JSON array or, with `lines`, as one JSON object
+/// per line
+pub fn write(path: &Path, commits: &[Commit], lines: bool) -> anyhow::Result<()> {
+    let text = if lines {
+        self::lines(commits)
+    } else {
+        let commits: Vec<String> = commits.iter().map(commit).collect();
+        format!("[{}]\n", commits.join(","))
+    };
+
+    fs::write(path, text).with_context(|| format!("Can't write {}", path.display()))
+}
+
+/// Commits as JSON lines, without writing them anywhere
+pub fn lines(commits: &[Commit]) -> String {
+    commits.iter().map(|c| format!("{}\n", commit(c))).collect()
+}
+
+/// Read commits from a file written by `write`, either as a JSON array or JSON
+/// lines
+pub fn read(path: &Path) -> anyhow::Result<Vec<Commit>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+
+    parse(&text).with_context(|| format!("Can't import {}", path.display()))
+}
+
+fn parse(text: &str) -> anyhow::Result<Vec<Commit>> {
+    let values = if text.trim_start().starts_with('[') {
+        match serde_json::from_str(text)? {
+            Value::Array(values) => values,
+            _ => bail!("Expected a list of commits"),
+        }
+    } else {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Value>, _>>()?
+    };
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            parse_commit(value).with_context(|| format!("Commit {} is invalid", i + 1))
+        })
+        .collect()
+}
+
+/// A commit as JSON, which `parse_commit()` reads back
+#[derive(Serialize)]
+struct CommitJson<'a> {
+    schema_version: u32,
+    hash: &'a str,
+    parents: &'a [String],
+    refs: Vec<RefJson<'a>>,
+    author: AuthorJson<'a>,
+    date: String,
+    committed: Option<String>,
+    message: &'a str,
+    files: u32,
+    inserts: u32,
+    deletes: u32,
+    file_changes: Vec<FileChangeJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct RefJson<'a> {
+    name: &'a str,
+    kind: &'static str,
+}
+
+#[derive(Serialize)]
+struct AuthorJson<'a> {
+    name: &'a str,
+    email: &'a str,
+}
+
+#[derive(Serialize)]
+struct FileChangeJson<'a> {
+    path: &'a str,
+    changes: u32,
+}
+
+fn commit(commit: &Commit) -> String {
+    json::to_string(&CommitJson {
+        schema_version: SCHEMA_VERSION,
+        hash: &commit.hash,
+        parents: &commit.parents,
+        refs: commit
+            .refs
+            .iter()
+            .map(|r| RefJson {
+                name: &r.name,
+                kind: r.kind.name(),
+            })
+            .collect(),
+        author: AuthorJson {
+            name: &commit.author.name,
+            email: &commit.author.email,
+        },
+        date: commit.date.format(&Rfc3339).unwrap_or_default(),
+        committed: commit.committed.and_then(|date| date.format(&Rfc3339).ok()),
+        message: &commit.message,
+        files: commit.files,
+        inserts: commit.inserts,
+        deletes: commit.deletes,
+        file_changes: commit
+            .file_changes
+            .iter()
+            .map(|change| FileChangeJson {
+                path: &change.path,
+                changes: change.changes,
+            })
+            .collect(),
+    })
+}
+
+fn parse_commit(value: &Value) -> anyhow::Result<Commit> {
+    // Files written before schema versions were recorded are version 1
+    if let Some(version) = value["schema_version"].as_u64() {
+        if version > SCHEMA_VERSION as u64 {
+            bail!("Schema version {} is newer than this yeesh reads", version);
+        }
+    }
+
+    let text = |value: &Value, field: &str| -> anyhow::Result<String> {
+        value[field]
+            .as_str()
+            .map(String::from)
+            .with_context(|| format!("Missing {}", field))
+    };
+    let count = |value: &Value, field: &str| -> anyhow::Result<u32> {
+        value[field]
+            .as_u64()
+            .map(|n| n as u32)
+            .with_context(|| format!("Missing {}", field))
+    };
+
+    let file_changes = value["file_changes"]
+        .as_array()
+        .context("Missing file_changes")?
+        .iter()
+        .map(|change| {
+            Ok(FileChange::new(
+                text(change, "path")?,
+                count(change, "changes")?,
+            ))
+        })
+        .collect::<anyhow::Result<Vec<FileChange>>>()?;
+
+    // Files written before parents and refs were recorded have none
+    let parents = value["parents"]
+        .as_array()
+        .map(|parents| {
+            parents
+                .iter()
+                .filter_map(|parent| parent.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let refs = value["refs"]
+        .as_array()
+        .map(|refs| {
+            refs.iter()
+                .filter_map(|r| {
+                    let kind = RefKind::from_name(r["kind"].as_str()?)?;
+                    Some(Ref::new(r["name"].as_str()?.to_string(), kind))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Commit {
+        hash: text(value, "hash")?,
+        parents,
+        refs,
+        author: Author::new(
+            text(&value["author"], "name")?,
+            text(&value["author"], "email")?,
+        ),
+        date: OffsetDateTime::parse(&text(value, "date")?, &Rfc3339)?,
+        // Files written before commit dates were recorded have none
+        committed: value["committed"]
+            .as_str()
+            .map(|date| OffsetDateTime::parse(date, &Rfc3339))
+            .transpose()?,
+        message: text(value, "message")?,
+        files: count(value, "files")?,
+        inserts: count(value, "inserts")?,
+        deletes: count(value, "deletes")?,
+        file_changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::commit::Commit;
+    use crate::test_support::commit;
+
+    fn commits() -> Vec<Commit> {
+        vec![
+            commit()
+                .hash("abc")
+                .parents(&["a1", "b2"])
+                .tag("v1")
+                .author("Jon \"J\"", "jon@email.ca")
+                .on(datetime!(2022-11-28 16:00 -8))
+                .committed(datetime!(2022-11-29 9:00 -8))
+                .message("Add things\n\nFor reasons")
+                .files(1)
+                .lines(2, 3)
+                .changes(&[("src/main.rs", 5)])
+                .build(),
+            commit()
+                .hash("def")
+                .on(datetime!(2022-11-27 9:30 UTC))
+                .build(),
+        ]
+    }
+
+    fn assert_same(actual: &[Commit], expected: &[Commit]) {
+        assert_eq!(actual.len(), expected.len());
+
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert_eq!(actual.hash, expected.hash);
+            assert_eq!(actual.parents, expected.parents);
+            assert_eq!(actual.refs, expected.refs);
+            assert_eq!(actual.author.name, expected.author.name);
+            assert_eq!(actual.date, expected.date);
+            // Dates are equal when they're the same instant in any offset
+            assert_eq!(actual.date.offset(), expected.date.offset());
+            assert_eq!(actual.committed, expected.committed);
+            assert_eq!(actual.message, expected.message);
+            assert_eq!(actual.churn(), expected.churn());
+            assert_eq!(actual.file_changes.len(), expected.file_changes.len());
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let commits = commits();
+        let json: Vec<String> = commits.iter().map(super::commit).collect();
+
+        let array = format!("[{}]", json.join(","));
+        assert_same(&super::parse(&array).unwrap(), &commits);
+
+        let lines = format!("{}\n\n{}\n", json[0], json[1]);
+        assert_same(&super::parse(&lines).unwrap(), &commits);
+    }
+
+    #[test]
+    fn invalid() {
+        let error = super::parse("[{\"hash\":\"abc\"}]").unwrap_err();
+
+        assert_eq!(error.to_string(), "Commit 1 is invalid");
+    }
+
+    #[test]
+    fn schema_version() {
+        let json = super::commit(&commits()[0]);
+        assert!(json.starts_with("{\"schema_version\":1,\"hash\":\"abc\""));
+
+        // Written before schema versions
+        let unversioned = json.replace("\"schema_version\":1,", "");
+        assert_same(&super::parse(&unversioned).unwrap(), &commits()[..1]);
+
+        let newer = json.replace("\"schema_version\":1", "\"schema_version\":2");
+        let error = super::parse(&newer).unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "Commit 1 is invalid: Schema version 2 is newer than this yeesh reads"
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use parquet::basic::Compression;
+use parquet::data_type::{ByteArray, ByteArrayType, DataType, Int32Type, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+use parquet::schema::parser::parse_message_type;
+
+use crate::commit::{Commit, Ref, RefKind};
+
+/// One row per commit. Changed files are a list of paths alongside a list of
+/// lines changed in each so the two can be zipped or exploded together.
+const SCHEMA: &str = "
+message commit {
+    required binary hash (STRING);
+    required binary author_name (STRING);
+    required binary author_email (STRING);
+    required int64 date (TIMESTAMP(MILLIS, true));
+    required int32 utc_offset_seconds;
+    required binary subject (STRING);
+    required binary message (STRING);
+    required int64 files;
+    required int64 inserts;
+    required int64 deletes;
+    required group paths (LIST) {
+        repeated group list {
+            required binary element (STRING);
+        }
+    }
+    required group changes (LIST) {
+        repeated group list {
+            required int64 element;
+        }
+    }
+    required group branches (LIST) {
+        repeated group list {
+            required binary element (STRING);
+        }
+    }
+    required group tags (LIST) {
+        repeated group list {
+            required binary element (STRING);
+        }
+    }
+}
+";
+
+/// Write commits to a Parquet file as a single row group, replacing the file
+/// if it exists
+pub fn write(path: &Path, commits: &[Commit]) -> anyhow::Result<()> {
+    let schema = Arc::new(parse_message_type(SCHEMA)?);
+    let properties = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build(),
+    );
+
+    let file = File::create(path).with_context(|| format!("Can't create {}", path.display()))?;
+    let mut writer = SerializedFileWriter::new(file, schema, properties)?;
+    let mut row_group = writer.next_row_group()?;
+
+    let text = |f: fn(&Commit) -> &str| -> Vec<ByteArray> {
+        commits.iter().map(|commit| f(commit).into()).collect()
+    };
+    let count = |f: fn(&Commit) -> u32| -> Vec<i64> {
+        commits.iter().map(|commit| f(commit) as i64).collect()
+    };
+
+    let dates: Vec<i64> = commits
+        .iter()
+        .map(|commit| (commit.date.unix_timestamp_nanos() / 1_000_000) as i64)
+        .collect();
+    let offsets: Vec<i32> = commits
+        .iter()
+        .map(|commit| commit.date.offset().whole_seconds())
+        .collect();
+
+    let (paths, changes, definitions, repetitions) = file_changes(commits);
+    let lists = Some((definitions.as_slice(), repetitions.as_slice()));
+
+    column::<ByteArrayType>(&mut row_group, &text(|c| &c.hash), None)?;
+    column::<ByteArrayType>(&mut row_group, &text(|c| &c.author.name), None)?;
+    column::<ByteArrayType>(&mut row_group, &text(|c| &c.author.email), None)?;
+    column::<Int64Type>(&mut row_group, &dates, None)?;
+    column::<Int32Type>(&mut row_group, &offsets, None)?;
+    column::<ByteArrayType>(&mut row_group, &text(|c| c.subject()), None)?;
+    column::<ByteArrayType>(&mut row_group, &text(|c| &c.message), None)?;
+    column::<Int64Type>(&mut row_group, &count(|c| c.files), None)?;
+    column::<Int64Type>(&mut row_group, &count(|c| c.inserts), None)?;
+    column::<Int64Type>(&mut row_group, &count(|c| c.deletes), None)?;
+    column::<ByteArrayType>(&mut row_group, &paths, lists)?;
+    column::<Int64Type>(&mut row_group, &changes, lists)?;
+
+    for kind in [RefKind::Branch, RefKind::Tag] {
+        let (names, definitions, repetitions) = refs(commits, kind);
+        let lists = Some((definitions.as_slice(), repetitions.as_slice()));
+
+        column::<ByteArrayType>(&mut row_group, &names, lists)?;
+    }
+
+    row_group.close()?;
+    writer.close()?;
+
+    Ok(())
+}
+
+/// Write the next column in the schema, with definition and repetition levels
+/// for list columns
+fn column<T: DataType>(
+    row_group: &mut SerializedRowGroupWriter<File>,
+    values: &[T::T],
+    levels: Option<(&[i16], &[i16])>,
+) -> anyhow::Result<()> {
+    let mut column = row_group
+        .next_column()?
+        .context("More columns written than the schema has")?;
+
+    column
+        .typed::<T>()
+        .write_batch(values, levels.map(|l| l.0), levels.map(|l| l.1))?;
+    column.close()?;
+
+    Ok(())
+}
+
+/// Flattened paths and changes with the levels Parquet uses to rebuild each
+/// commit's list. A definition level of 0 is an empty list, 1 is an element. A
+/// repetition level of 0 starts a new commit's list, 1 continues it.
+fn file_changes(commits: &[Commit]) -> (Vec<ByteArray>, Vec<i64>, Vec<i16>, Vec<i16>) {
+    let mut paths = Vec::new();
+    let mut changes = Vec::new();
+    let mut definitions = Vec::new();
+    let mut repetitions = Vec::new();
+
+    for commit in commits {
+        if commit.file_changes.is_empty() {
+            definitions.push(0);
+            repetitions.push(0);
+        }
+
+        for (i, change) in commit.file_changes.iter().enumerate() {
+            paths.push(change.path.as_str().into());
+            changes.push(change.changes as i64);
+            definitions.push(1);
+            repetitions.push(if i == 0 { 0 } else { 1 });
+        }
+    }
+
+    (paths, changes, definitions, repetitions)
+}
+
+/// Flattened names of each commit's refs of a kind with their levels, like
+/// `file_changes()`
+fn refs(commits: &[Commit], kind: RefKind) -> (Vec<ByteArray>, Vec<i16>, Vec<i16>) {
+    let mut names = Vec::new();
+    let mut definitions = Vec::new();
+    let mut repetitions = Vec::new();
+
+    for commit in commits {
+        let refs: Vec<&Ref> = commit.refs.iter().filter(|r| r.kind == kind).collect();
+
+        if refs.is_empty() {
+            definitions.push(0);
+            repetitions.push(0);
+        }
+
+        for (i, r) in refs.iter().enumerate() {
+            names.push(r.name.as_str().into());
+            definitions.push(1);
+            repetitions.push(if i == 0 { 0 } else { 1 });
+        }
+    }
+
+    (names, definitions, repetitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use time::macros::datetime;
+
+    use crate::test_support::commit;
+
+    #[test]
+    fn levels() {
+        let commits = vec![
+            commit().changes(&[("a", 1), ("b", 2)]).build(),
+            commit().build(),
+            commit().changes(&[("c", 3)]).build(),
+        ];
+
+        let (paths, changes, definitions, repetitions) = super::file_changes(&commits);
+
+        assert_eq!(paths.len(), 3);
+        assert_eq!(changes, vec![1, 2, 3]);
+        assert_eq!(definitions, vec![1, 1, 0, 1]);
+        assert_eq!(repetitions, vec![0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let commits = vec![
+            commit()
+                .hash("abc")
+                .branch("main")
+                .tag("v1")
+                .by("Jon")
+                .on(datetime!(2022-11-28 16:00 -8))
+                .message("Add things")
+                .files(2)
+                .lines(3, 1)
+                .changes(&[("src/main.rs", 3), ("README.md", 1)])
+                .build(),
+            commit().hash("def").build(),
+        ];
+
+        let path = env::temp_dir().join(format!("yeesh-{}.parquet", std::process::id()));
+        super::write(&path, &commits).unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let rows: Vec<String> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().to_string())
+            .collect();
+
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        assert!(rows[0].contains("hash: \"abc\""));
+        assert!(rows[0].contains("paths: [\"src/main.rs\", \"README.md\"]"));
+        assert!(rows[0].contains("changes: [3, 1]"));
+        assert!(rows[0].contains("branches: [\"main\"], tags: [\"v1\"]"));
+        assert!(rows[1].contains("paths: []"));
+        assert!(rows[1].contains("tags: []"));
+    }
+}
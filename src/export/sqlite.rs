@@ -0,0 +1,179 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context};
+use time::format_description::well_known::Rfc3339;
+
+use crate::commit::{self, Commit};
+
+const SCHEMA: &str = "\
+DROP TABLE IF EXISTS refs;
+DROP TABLE IF EXISTS file_changes;
+DROP TABLE IF EXISTS commits;
+DROP TABLE IF EXISTS authors;
+
+CREATE TABLE authors (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    email TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE commits (
+    hash TEXT PRIMARY KEY,
+    author_id INTEGER NOT NULL REFERENCES authors (id),
+    date TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    utc_offset_seconds INTEGER NOT NULL,
+    subject TEXT NOT NULL,
+    message TEXT NOT NULL,
+    files INTEGER NOT NULL,
+    inserts INTEGER NOT NULL,
+    deletes INTEGER NOT NULL
+);
+
+CREATE TABLE file_changes (
+    commit_hash TEXT NOT NULL REFERENCES commits (hash),
+    path TEXT NOT NULL,
+    changes INTEGER NOT NULL
+);
+
+CREATE TABLE refs (
+    commit_hash TEXT NOT NULL REFERENCES commits (hash),
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('branch', 'tag'))
+);
+
+CREATE INDEX commits_author_id ON commits (author_id);
+CREATE INDEX file_changes_commit_hash ON file_changes (commit_hash);
+CREATE INDEX file_changes_path ON file_changes (path);
+CREATE INDEX refs_commit_hash ON refs (commit_hash);
+";
+
+/// Write commits, their file changes, and their authors to a SQLite database,
+/// replacing any tables a previous export left. The sqlite3 command does the
+/// writing in the same way git does the heavy lifting for history.
+pub fn write(path: &Path, commits: &[Commit]) -> anyhow::Result<()> {
+    let mut sqlite = Command::new("sqlite3")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Can't run sqlite3, is it installed?")?;
+
+    let mut stdin = sqlite.stdin.take().context("Can't write to sqlite3")?;
+    // A sqlite3 that can't open the file stops reading, so why it failed is
+    // only known once it exits
+    let written = stdin.write_all(script(commits).as_bytes());
+    drop(stdin);
+
+    let output = sqlite.wait_with_output()?;
+
+    if !output.status.success() {
+        bail!(
+            "Can't export to {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    written.context("Can't write to sqlite3")
+}
+
+/// SQL creating and filling every table in one transaction. Authors are
+/// identified by email and named by their most recent commit.
+fn script(commits: &[Commit]) -> String {
+    let mut sql = String::from("BEGIN;\n");
+    sql.push_str(SCHEMA);
+
+    for (id, (author, commits)) in commit::by_author(commits).into_iter().enumerate() {
+        let id = id + 1;
+
+        sql.push_str(&format!(
+            "INSERT INTO authors VALUES ({}, {}, {});\n",
+            id,
+            quote(&author.name),
+            quote(&author.email)
+        ));
+
+        for commit in commits {
+            sql.push_str(&format!(
+                "INSERT INTO commits VALUES ({}, {}, {}, {}, {}, {}, {}, {}, {}, {});\n",
+                quote(&commit.hash),
+                id,
+                quote(&commit.date.format(&Rfc3339).unwrap_or_default()),
+                commit.date.unix_timestamp(),
+                commit.date.offset().whole_seconds(),
+                quote(commit.subject()),
+                quote(&commit.message),
+                commit.files,
+                commit.inserts,
+                commit.deletes
+            ));
+
+            for change in &commit.file_changes {
+                sql.push_str(&format!(
+                    "INSERT INTO file_changes VALUES ({}, {}, {});\n",
+                    quote(&commit.hash),
+                    quote(&change.path),
+                    change.changes
+                ));
+            }
+
+            for r in &commit.refs {
+                sql.push_str(&format!(
+                    "INSERT INTO refs VALUES ({}, {}, {});\n",
+                    quote(&commit.hash),
+                    quote(&r.name),
+                    quote(r.kind.name())
+                ));
+            }
+        }
+    }
+
+    sql.push_str("COMMIT;\n");
+    sql
+}
+
+/// A SQL string literal
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::test_support::commit;
+
+    #[test]
+    fn quote() {
+        assert_eq!(super::quote("O'Neil"), "'O''Neil'");
+    }
+
+    #[test]
+    fn script() {
+        let commits = vec![commit()
+            .hash("abc")
+            .tag("v1")
+            .by("Jon")
+            .on(datetime!(2022-11-28 16:00 -8))
+            .message("Add things\n\nIt's good")
+            .files(1)
+            .lines(2, 3)
+            .changes(&[("src/main.rs", 5)])
+            .build()];
+
+        let sql = super::script(&commits);
+
+        assert!(sql.starts_with("BEGIN;\n"));
+        assert!(sql.ends_with("COMMIT;\n"));
+        assert!(sql.contains("INSERT INTO authors VALUES (1, 'Jon', 'jon@email.ca');"));
+        assert!(sql.contains(
+            "INSERT INTO commits VALUES ('abc', 1, '2022-11-28T16:00:00-08:00', 1669680000, -28800, 'Add things', 'Add things\n\nIt''s good', 1, 2, 3);"
+        ));
+        assert!(sql.contains("INSERT INTO file_changes VALUES ('abc', 'src/main.rs', 5);"));
+        assert!(sql.contains("INSERT INTO refs VALUES ('abc', 'v1', 'tag');"));
+    }
+}
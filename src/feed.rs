@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use time::format_description::well_known::Rfc3339;
+use time::Date;
+
+use crate::commit::{self, Commit};
+use crate::report::anomaly::monday_of;
+
+/// Most recent weeks in the feed. Readers only show the newest entries anyway
+/// and a long history would otherwise make for a huge file.
+const WEEKS: usize = 52;
+
+/// An Atom feed with an entry summarizing each week with commits, newest
+/// first, so activity can be followed from a feed reader.
+///
+/// Entries are only ever added or, for the current week, updated so readers
+/// don't show old weeks as new each time the feed is written.
+pub fn atom(repo: &str, commits: &[Commit]) -> String {
+    let mut weeks: BTreeMap<Date, Vec<Commit>> = BTreeMap::new();
+
+    for commit in commits {
+        weeks
+            .entry(monday_of(commit))
+            .or_default()
+            .push(commit.clone());
+    }
+
+    let entries: Vec<String> = weeks
+        .iter()
+        .rev()
+        .take(WEEKS)
+        .map(|(week, commits)| entry(repo, *week, commits))
+        .collect();
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<feed xmlns=\"http://www.w3.org/2005/Atom\">
+  <title>{} activity</title>
+  <id>{}</id>
+  <updated>{}</updated>
+  <generator>yeesh</generator>
+{}</feed>
+",
+        escape(repo),
+        id(repo, None),
+        updated(commits),
+        entries.concat()
+    )
+}
+
+fn entry(repo: &str, week: Date, commits: &[Commit]) -> String {
+    let authors = commit::by_author(commits);
+
+    let inserts: u64 = commits.iter().map(|commit| commit.inserts as u64).sum();
+    let deletes: u64 = commits.iter().map(|commit| commit.deletes as u64).sum();
+
+    let contributors: Vec<String> = authors
+        .iter()
+        .map(|(author, commits)| format!("{} ({})", author.name, commits.len()))
+        .collect();
+
+    let names: Vec<String> = authors
+        .iter()
+        .map(|(author, _)| {
+            format!(
+                "    <author><name>{}</name></author>\n",
+                escape(&author.name)
+            )
+        })
+        .collect();
+
+    format!(
+        "  <entry>
+    <title>Week of {}</title>
+    <id>{}</id>
+    <updated>{}</updated>
+{}    <summary>{} commits by {} contributors, +{} -{} lines. {}</summary>
+  </entry>
+",
+        week,
+        id(repo, Some(week)),
+        updated(commits),
+        names.concat(),
+        commits.len(),
+        authors.len(),
+        inserts,
+        deletes,
+        escape(&contributors.join(", "))
+    )
+}
+
+/// A stable identifier for the feed, or a week's entry in it. The repository
+/// name is kept to characters that can't upset a URN.
+fn id(repo: &str, week: Option<Date>) -> String {
+    let repo: String = repo
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+
+    match week {
+        Some(week) => format!("urn:yeesh:{}:week:{}", repo, week),
+        None => format!("urn:yeesh:{}", repo),
+    }
+}
+
+/// When the newest commit was made rather than when the feed was written so
+/// rewriting an unchanged feed doesn't change it
+fn updated(commits: &[Commit]) -> String {
+    commits
+        .iter()
+        .map(|commit| commit.date)
+        .max()
+        .and_then(|date| date.format(&Rfc3339).ok())
+        .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::test_support::commit;
+
+    #[test]
+    fn weekly_entries() {
+        let commits = vec![
+            commit()
+                .by("Jon")
+                .on(datetime!(2022-11-30 12:00 -8))
+                .lines(2, 1)
+                .build(),
+            commit()
+                .by("A&B")
+                .on(datetime!(2022-11-28 9:00 -8))
+                .lines(2, 1)
+                .build(),
+            commit()
+                .by("Jon")
+                .on(datetime!(2022-11-27 23:00 -8))
+                .lines(2, 1)
+                .build(),
+        ];
+
+        let feed = super::atom("yeesh", &commits);
+
+        assert!(feed.contains("<updated>2022-11-30T12:00:00-08:00</updated>"));
+
+        let weeks: Vec<&str> = feed
+            .lines()
+            .filter(|line| line.contains("<title>Week of"))
+            .collect();
+
+        assert_eq!(
+            weeks,
+            vec![
+                "    <title>Week of 2022-11-28</title>",
+                "    <title>Week of 2022-11-21</title>"
+            ]
+        );
+
+        assert!(feed.contains("<id>urn:yeesh:yeesh:week:2022-11-28</id>"));
+        assert!(feed.contains("<author><name>A&amp;B</name></author>"));
+        assert!(feed.contains(
+            "<summary>2 commits by 2 contributors, +4 -2 lines. Jon (1), A&amp;B (1)</summary>"
+        ));
+    }
+
+    #[test]
+    fn id() {
+        assert_eq!(super::id("my repo", None), "urn:yeesh:my-repo");
+    }
+}
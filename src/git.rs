@@ -0,0 +1,818 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::str;
+use std::thread::{self, JoinHandle};
+
+use anyhow::Context;
+
+use crate::commit::{Author, Ref, RefKind};
+use crate::subprocess::{self, Watch};
+
+/// A branch, local or remote, compared with the branch others are merged into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    pub name: String,
+    /// When its latest commit was made, as a Unix timestamp
+    pub time: i64,
+    /// Who made its latest commit
+    pub author: String,
+    /// Commits it has that the base doesn't
+    pub ahead: u64,
+    /// Commits the base has that it doesn't
+    pub behind: u64,
+}
+
+/// A tag and the commit it points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub hash: String,
+}
+
+impl Tag {
+    pub fn new(name: String, hash: String) -> Tag {
+        Tag { name, hash }
+    }
+}
+
+/// Configuration that would change the output yeesh parses, overridden with
+/// git's defaults. Aliases needn't be since they can't shadow git's commands.
+const CONFIG: [&str; 7] = [
+    "color.ui=never",
+    "core.quotePath=false",
+    "format.pretty=medium",
+    "i18n.logOutputEncoding=UTF-8",
+    "log.abbrevCommit=false",
+    "log.showSignature=false",
+    "diff.relative=false",
+];
+
+/// A git command run against the repository at the given path rather than
+/// wherever yeesh happens to be running, in the same environment on every
+/// machine. Otherwise a locale translates e.g. "1 file changed", a pager
+/// waits on a keypress, or someone's config changes the log's format. Only the
+/// repository's own config is read, with `CONFIG` on top of it.
+fn git(repo: &Path) -> Command {
+    let mut command = Command::new("git");
+
+    command
+        .env("LC_ALL", "C")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        // Hooks set these for the repository they run in, which would
+        // override `-C` for any other
+        .env_remove("GIT_DIR")
+        .env_remove("GIT_WORK_TREE")
+        .arg("--no-pager")
+        .arg("-C")
+        .arg(repo);
+
+    for config in CONFIG {
+        command.arg("-c").arg(config);
+    }
+
+    command
+}
+
+/// How much of each commit's diff to ask git for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Diffstat {
+    /// Lines changed in each file as well as the totals
+    Files,
+    /// Only the files changed, inserts, and deletes of the whole commit, which
+    /// is far less for git to write and yeesh to parse
+    Totals,
+}
+
+/// The repository's history in the format `parser::parse()` expects. Bytes
+/// that aren't UTF-8, e.g. a latin-1 author name, are replaced unless
+/// `strict`, in which case they're an error.
+pub fn logs(repo: &Path, diffstat: Diffstat, strict: bool) -> anyhow::Result<String> {
+    log(repo, &[], diffstat, strict)
+}
+
+/// Like `logs()` but only the commits since the given one, newest first
+pub fn logs_since(
+    repo: &Path,
+    hash: &str,
+    diffstat: Diffstat,
+    strict: bool,
+) -> anyhow::Result<String> {
+    log(repo, &[format!("{}..HEAD", hash)], diffstat, strict)
+}
+
+/// Like `logs()` but only commits made between the given Unix timestamps,
+/// inclusive, by the time they were committed. Either end may be left open.
+pub fn logs_between(
+    repo: &Path,
+    since: Option<i64>,
+    until: Option<i64>,
+    diffstat: Diffstat,
+    strict: bool,
+) -> anyhow::Result<String> {
+    let mut range = Vec::new();
+
+    if let Some(since) = since {
+        range.push(format!("--since=@{}", since));
+    }
+
+    if let Some(until) = until {
+        range.push(format!("--until=@{}", until));
+    }
+
+    log(repo, &range, diffstat, strict)
+}
+
+fn log(repo: &Path, range: &[String], diffstat: Diffstat, strict: bool) -> anyhow::Result<String> {
+    let output = subprocess::output(&mut log_command(repo, range, diffstat), "git log")
+        .context("Can't run git to read logs")?;
+
+    decode(output.stdout, strict)
+}
+
+fn log_command(repo: &Path, range: &[String], diffstat: Diffstat) -> Command {
+    // The date format below yields the committer's local date. Regardless when
+    // (or where) this program is run, the local time of the commit is what gets
+    // captured. This is more meaningful than coverting dates and times into the
+    // local timezone of the person running the tool.
+    //
+    // The following StackOverflow discussion has more details:
+    // https://stackoverflow.com/questions/7853332/how-to-change-git-log-date-formats
+    //
+    // The fuller format has when each commit was committed as well as authored.
+    //
+    // The stat block is widened so long paths aren't abbreviated and its graph
+    // narrowed since only the numbers are used.
+    //
+    // Every commit's parents follow its hash, where otherwise only merges list
+    // theirs, abbreviated, so the history can be walked as a graph.
+    let mut command = git(repo);
+    command.arg("log");
+
+    match diffstat {
+        Diffstat::Files => command.arg("--stat=1000").arg("--stat-graph-width=1"),
+        Diffstat::Totals => command.arg("--shortstat"),
+    };
+
+    command
+        .arg("--pretty=fuller")
+        .arg("--date=rfc")
+        .arg("--decorate=short")
+        .arg("--no-notes")
+        .arg("--parents");
+
+    command.args(range);
+
+    command
+}
+
+/// Each commit's hash and date, e.g. `abc123 2022-11-28T16:52:42-08:00`,
+/// newest first. Much less for git to write than `logs()` when only the dates
+/// are needed.
+pub fn dates(repo: &Path) -> anyhow::Result<String> {
+    let mut command = git(repo);
+    command
+        .arg("log")
+        .arg("--pretty=format:%H %ad")
+        .arg("--date=iso-strict");
+
+    let output =
+        subprocess::output(&mut command, "git log").context("Can't run git to read logs")?;
+
+    // Only hashes and dates so always ASCII
+    decode(output.stdout, false)
+}
+
+/// Like `logs()` but read a line at a time as git writes them rather than all
+/// at once. Call `finish()` after reading every line to hear of any problems.
+pub fn log_lines(repo: &Path, diffstat: Diffstat, strict: bool) -> anyhow::Result<LogLines> {
+    let (mut child, watch) = Watch::spawn(
+        log_command(repo, &[], diffstat)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped()),
+        "git log",
+    )
+    .context("Can't run git to read logs")?;
+
+    let stdout = child.stdout.take().context("Can't read from git")?;
+    let mut stderr = child.stderr.take().context("Can't read from git")?;
+
+    // Read alongside stdout so git can't fill the pipe and wait on yeesh
+    let stderr = thread::spawn(move || {
+        let mut errors = Vec::new();
+        let _ = stderr.read_to_end(&mut errors);
+        errors
+    });
+
+    Ok(LogLines {
+        child,
+        watch,
+        stdout: BufReader::new(stdout),
+        stderr,
+        strict,
+        error: None,
+    })
+}
+
+pub struct LogLines {
+    child: Child,
+    watch: Watch,
+    stdout: BufReader<ChildStdout>,
+    stderr: JoinHandle<Vec<u8>>,
+    strict: bool,
+    /// Stops iteration, and is returned by `finish()`
+    error: Option<anyhow::Error>,
+}
+
+impl LogLines {
+    /// An error when reading failed or git did, with what it wrote to stderr
+    pub fn finish(mut self) -> anyhow::Result<()> {
+        if let Some(why) = self.error.take() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+            let _ = self.watch.finish();
+            return Err(why);
+        }
+
+        let status = self.child.wait()?;
+        self.watch.finish()?;
+
+        let stderr = self.stderr.join().unwrap_or_default();
+        subprocess::check("git log", status, &stderr).context("Can't run git to read logs")
+    }
+}
+
+impl Iterator for LogLines {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.error.is_some() {
+            return None;
+        }
+
+        let mut line = Vec::new();
+
+        match self.stdout.read_until(b'\n', &mut line) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(why) => {
+                self.error = Some(why.into());
+                return None;
+            }
+        }
+
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+
+        match decode(line, self.strict) {
+            Ok(line) => Some(line),
+            Err(why) => {
+                self.error = Some(why);
+                None
+            }
+        }
+    }
+}
+
+/// Text from git's output, replacing invalid UTF-8 with `�` unless `strict`
+fn decode(bytes: Vec<u8>, strict: bool) -> anyhow::Result<String> {
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(text),
+        Err(why) if strict => Err(why).context("git's output isn't valid UTF-8"),
+        Err(why) => Ok(String::from_utf8_lossy(why.as_bytes()).into_owned()),
+    }
+}
+
+/// The hash of the commit currently checked out
+pub fn head(repo: &Path) -> anyhow::Result<String> {
+    let output = git(repo)
+        .arg("rev-parse")
+        .arg("HEAD")
+        .output()
+        .context("Can't run git to find HEAD")?;
+
+    if !output.status.success() {
+        anyhow::bail!("The repository has no commits");
+    }
+
+    Ok(str::from_utf8(&output.stdout)?.trim().to_string())
+}
+
+/// The branch checked out or, when HEAD is detached, e.g. `detached at
+/// abc1234`
+pub fn branch(repo: &Path) -> anyhow::Result<String> {
+    let output = git(repo)
+        .arg("symbolic-ref")
+        .arg("--short")
+        .arg("-q")
+        .arg("HEAD")
+        .output()
+        .context("Can't run git to find the branch")?;
+
+    if output.status.success() {
+        return Ok(str::from_utf8(&output.stdout)?.trim().to_string());
+    }
+
+    let head = head(repo)?;
+
+    Ok(format!(
+        "detached at {}",
+        head.get(..7).unwrap_or(head.as_str())
+    ))
+}
+
+/// When the commit currently checked out was made, as a Unix timestamp
+pub fn head_time(repo: &Path) -> anyhow::Result<i64> {
+    let output = git(repo)
+        .arg("log")
+        .arg("-1")
+        .arg("--format=%ct")
+        .output()
+        .context("Can't run git to find HEAD")?;
+
+    str::from_utf8(&output.stdout)?
+        .trim()
+        .parse()
+        .context("The repository has no commits")
+}
+
+/// When the oldest root commit was made, as a Unix timestamp
+pub fn first_time(repo: &Path) -> anyhow::Result<i64> {
+    let output = git(repo)
+        .arg("log")
+        .arg("--max-parents=0")
+        .arg("--format=%ct")
+        .output()
+        .context("Can't run git to find the first commit")?;
+
+    str::from_utf8(&output.stdout)?
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .min()
+        .context("The repository has no commits")
+}
+
+/// When each commit `merged` brings in that `base` doesn't have was authored,
+/// as Unix timestamps, e.g. the commits of a branch merged into `base`
+pub fn branch_times(repo: &Path, base: &str, merged: &str) -> anyhow::Result<Vec<i64>> {
+    let mut command = git(repo);
+    command
+        .arg("log")
+        .arg("--format=%at")
+        .arg(format!("{}..{}", base, merged));
+
+    let output = subprocess::output(&mut command, "git log")
+        .context("Can't run git to list a branch's commits")?;
+
+    Ok(str::from_utf8(&output.stdout)?
+        .lines()
+        .filter_map(|time| time.trim().parse().ok())
+        .collect())
+}
+
+/// Whether `ancestor` is in the history of `descendant`, which isn't the case
+/// after the ancestor is amended or rebased away
+pub fn is_ancestor(repo: &Path, ancestor: &str, descendant: &str) -> anyhow::Result<bool> {
+    let status = git(repo)
+        .arg("merge-base")
+        .arg("--is-ancestor")
+        .arg(ancestor)
+        .arg(descendant)
+        .status()
+        .context("Can't run git to compare commits")?;
+
+    Ok(status.success())
+}
+
+/// Where the repository's hooks live, respecting `core.hooksPath`
+pub fn hooks_dir(repo: &Path) -> anyhow::Result<PathBuf> {
+    let output = git(repo)
+        .arg("rev-parse")
+        .arg("--git-path")
+        .arg("hooks")
+        .output()
+        .context("Can't run git to find the hooks directory")?;
+
+    if !output.status.success() {
+        anyhow::bail!("{} isn't a git repository", repo.display());
+    }
+
+    // The path is relative to the repository unless configured otherwise
+    Ok(repo.join(str::from_utf8(&output.stdout)?.trim()))
+}
+
+/// Every branch and tag in the repository by the hash of the commit it points
+/// to. Annotated tags are peeled so the hash is always a commit's rather than
+/// the tag object's.
+pub fn refs(repo: &Path) -> anyhow::Result<Vec<(String, Ref)>> {
+    let mut command = git(repo);
+    command
+        .arg("for-each-ref")
+        .arg("--format=%(refname)%09%(refname:short)%09%(objectname)%09%(*objectname)")
+        .arg("refs/heads")
+        .arg("refs/remotes")
+        .arg("refs/tags");
+
+    let output = subprocess::output(&mut command, "git for-each-ref")
+        .context("Can't run git to list branches and tags")?;
+
+    let output = str::from_utf8(&output.stdout)?;
+
+    Ok(parse_refs(output))
+}
+
+fn parse_refs(output: &str) -> Vec<(String, Ref)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+
+            let full = fields.next()?;
+            let name = fields.next()?;
+            let object = fields.next()?;
+
+            // Only annotated tags have a peeled object, i.e. `*objectname`
+            let hash = match fields.next() {
+                Some(peeled) if !peeled.is_empty() => peeled,
+                _ => object,
+            };
+
+            // Like decorations, leave out where remotes' HEADs point
+            if full.ends_with("/HEAD") {
+                return None;
+            }
+
+            let kind = if full.starts_with("refs/tags/") {
+                RefKind::Tag
+            } else {
+                RefKind::Branch
+            };
+
+            Some((hash.to_string(), Ref::new(name.to_string(), kind)))
+        })
+        .collect()
+}
+
+/// The branch others are merged into: where origin's HEAD points, or else main
+/// or master, or else the one checked out
+pub fn default_branch(repo: &Path) -> anyhow::Result<String> {
+    let output = git(repo)
+        .arg("symbolic-ref")
+        .arg("--short")
+        .arg("-q")
+        .arg("refs/remotes/origin/HEAD")
+        .output()
+        .context("Can't run git to find the default branch")?;
+
+    if output.status.success() {
+        return Ok(str::from_utf8(&output.stdout)?.trim().to_string());
+    }
+
+    for name in ["main", "master"] {
+        let status = git(repo)
+            .arg("rev-parse")
+            .arg("--verify")
+            .arg("-q")
+            .arg(format!("refs/heads/{}", name))
+            .stdout(Stdio::null())
+            .status()
+            .context("Can't run git to find the default branch")?;
+
+        if status.success() {
+            return Ok(name.to_string());
+        }
+    }
+
+    branch(repo)
+}
+
+/// Every local and remote branch but `base` itself, with how far each has
+/// diverged from it. That's a `git rev-list` per branch, so slow for
+/// repositories with thousands.
+pub fn branches(repo: &Path, base: &str) -> anyhow::Result<Vec<Branch>> {
+    let mut command = git(repo);
+    command
+        .arg("for-each-ref")
+        .arg("--format=%(refname)%09%(refname:short)%09%(committerdate:unix)%09%(authorname)")
+        .arg("refs/heads")
+        .arg("refs/remotes");
+
+    let output = subprocess::output(&mut command, "git for-each-ref")
+        .context("Can't run git to list branches")?;
+
+    let mut branches = parse_branches(str::from_utf8(&output.stdout)?);
+    branches.retain(|branch| branch.name != base);
+
+    for branch in &mut branches {
+        let output = git(repo)
+            .arg("rev-list")
+            .arg("--left-right")
+            .arg("--count")
+            .arg(format!("{}...{}", base, branch.name))
+            .output()
+            .context("Can't run git to compare branches")?;
+
+        if !output.status.success() {
+            anyhow::bail!("Can't compare {} with {}", branch.name, base);
+        }
+
+        let counts = str::from_utf8(&output.stdout)?;
+        let (behind, ahead) = parse_left_right(counts)
+            .with_context(|| format!("Can't read git's counts {:?}", counts))?;
+
+        branch.ahead = ahead;
+        branch.behind = behind;
+    }
+
+    Ok(branches)
+}
+
+/// Branches without their counts, which come from elsewhere
+fn parse_branches(output: &str) -> Vec<Branch> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+
+            let full = fields.next()?;
+            let name = fields.next()?;
+            let time = fields.next()?.parse().ok()?;
+            let author = fields.next()?;
+
+            // Where remotes' HEADs point is another branch already listed
+            if full.ends_with("/HEAD") {
+                return None;
+            }
+
+            Some(Branch {
+                name: name.to_string(),
+                time,
+                author: author.to_string(),
+                ahead: 0,
+                behind: 0,
+            })
+        })
+        .collect()
+}
+
+/// `rev-list --left-right --count`'s commits only on the left and only on
+/// the right, e.g. `3\t12`
+fn parse_left_right(output: &str) -> Option<(u64, u64)> {
+    let (left, right) = output.trim().split_once('\t')?;
+    Some((left.parse().ok()?, right.parse().ok()?))
+}
+
+/// Keep a bare copy of the repository at `url` in `dir`, cloning it the first
+/// time and fetching every branch and tag every time after
+pub fn mirror(url: &str, dir: &Path) -> anyhow::Result<()> {
+    // Nothing's parsed, and the user's config may have the credentials or
+    // proxy needed to reach the URL
+    let user_git = |repo: &Path| {
+        let mut command = git(repo);
+        command
+            .env_remove("GIT_CONFIG_NOSYSTEM")
+            .env_remove("GIT_CONFIG_GLOBAL");
+        command
+    };
+
+    let status = if dir.exists() {
+        user_git(dir)
+            .arg("fetch")
+            .arg("--quiet")
+            .arg("--prune")
+            .status()
+    } else {
+        let parent = dir.parent().unwrap_or_else(|| Path::new("."));
+        fs::create_dir_all(parent).with_context(|| format!("Can't create {}", parent.display()))?;
+
+        user_git(parent)
+            .arg("clone")
+            .arg("--mirror")
+            .arg("--quiet")
+            .arg(url)
+            .arg(dir)
+            .status()
+    }
+    .context("Can't run git to clone or fetch")?;
+
+    if !status.success() {
+        anyhow::bail!("Can't clone or fetch {}", url);
+    }
+
+    Ok(())
+}
+
+/// Where the `origin` remote points, e.g. `git@github.com:jneufeld/yeesh.git`
+pub fn origin_url(repo: &Path) -> anyhow::Result<String> {
+    let output = git(repo)
+        .arg("remote")
+        .arg("get-url")
+        .arg("origin")
+        .output()
+        .context("Can't run git to find the origin remote")?;
+
+    let url = str::from_utf8(&output.stdout)?.trim();
+
+    if url.is_empty() {
+        anyhow::bail!("The repository has no origin remote");
+    }
+
+    Ok(url.to_string())
+}
+
+/// Paths of every file git tracks in the current commit
+pub fn tracked_files(repo: &Path) -> anyhow::Result<Vec<String>> {
+    let output = subprocess::output(git(repo).arg("ls-files"), "git ls-files")
+        .context("Can't run git to list files")?;
+
+    let output = str::from_utf8(&output.stdout)?;
+
+    Ok(output.lines().map(String::from).collect())
+}
+
+/// When each surviving line of the file was authored, as Unix timestamps. This
+/// is expensive! Files git can't blame, e.g. binaries, have no lines.
+pub fn blame_times(repo: &Path, path: &str) -> anyhow::Result<Vec<i64>> {
+    Ok(parse_blame_times(&blame(repo, path)?))
+}
+
+/// Who authored each surviving line of the file. This is expensive too!
+pub fn blame_authors(repo: &Path, path: &str) -> anyhow::Result<Vec<Author>> {
+    Ok(parse_blame_authors(&blame(repo, path)?))
+}
+
+/// Every line of the file with its commit's details, or nothing for files git
+/// can't blame
+fn blame(repo: &Path, path: &str) -> anyhow::Result<String> {
+    let mut command = git(repo);
+    command
+        .arg("blame")
+        .arg("--line-porcelain")
+        .arg("--")
+        .arg(path);
+
+    let output = subprocess::output_with_status(&mut command, "git blame")
+        .context("Can't run git to blame a file")?;
+
+    if !output.status.success() {
+        return Ok(String::new());
+    }
+
+    // Binary files and the odd latin-1 source file are still worth reading
+    // for their timestamps and authors
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn parse_blame_times(output: &str) -> Vec<i64> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("author-time "))
+        .filter_map(|time| time.parse().ok())
+        .collect()
+}
+
+/// Each line's author is named on one line and their email, in angle
+/// brackets, on a later one
+fn parse_blame_authors(output: &str) -> Vec<Author> {
+    let mut authors = Vec::new();
+    let mut name = "";
+
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("author ") {
+            name = rest;
+        } else if let Some(mail) = line.strip_prefix("author-mail ") {
+            let email = mail.trim_start_matches('<').trim_end_matches('>');
+            authors.push(Author::identified(name, email));
+        }
+    }
+
+    authors
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    use crate::commit::{Ref, RefKind};
+
+    #[test]
+    fn branches_and_tags() {
+        let output = "\
+refs/heads/main\tmain\tabc123\t
+refs/remotes/origin/HEAD\torigin\tabc123\t
+refs/tags/v0.1.0\tv0.1.0\tabc123\t
+refs/tags/v0.2.0\tv0.2.0\ttag456\tdef789
+";
+
+        assert_eq!(
+            super::parse_refs(output),
+            vec![
+                (
+                    "abc123".to_string(),
+                    Ref::new("main".to_string(), RefKind::Branch)
+                ),
+                (
+                    "abc123".to_string(),
+                    Ref::new("v0.1.0".to_string(), RefKind::Tag)
+                ),
+                (
+                    "def789".to_string(),
+                    Ref::new("v0.2.0".to_string(), RefKind::Tag)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn branches() {
+        let output = "\
+refs/heads/main\tmain\t1669680000\tJon
+refs/heads/fix/parser\tfix/parser\t1669000000\tNot Jon\tagain
+refs/remotes/origin/HEAD\torigin\t1669680000\tJon
+refs/remotes/origin/old\torigin/old\t1600000000\tJon
+";
+
+        let branches = super::parse_branches(output);
+        let names: Vec<&str> = branches.iter().map(|branch| branch.name.as_str()).collect();
+
+        assert_eq!(names, vec!["main", "fix/parser", "origin/old"]);
+        assert_eq!(branches[1].time, 1669000000);
+        assert_eq!(branches[1].author, "Not Jon\tagain");
+
+        assert_eq!(super::parse_left_right("3\t12\n"), Some((3, 12)));
+        assert_eq!(super::parse_left_right("fatal"), None);
+    }
+
+    #[test]
+    fn stable_environment() {
+        let command = super::git(Path::new("repo"));
+
+        let args: Vec<&OsStr> = command.get_args().collect();
+        let envs: Vec<(&OsStr, Option<&OsStr>)> = command.get_envs().collect();
+
+        assert_eq!(args[..3], ["--no-pager", "-C", "repo"]);
+        assert!(args.contains(&OsStr::new("log.abbrevCommit=false")));
+        assert!(envs.contains(&(OsStr::new("LC_ALL"), Some(OsStr::new("C")))));
+        assert!(envs.contains(&(OsStr::new("GIT_DIR"), None)));
+    }
+
+    #[test]
+    fn decode() {
+        let latin1 = b"Author: Ren\xe9 <rene@email.ca>".to_vec();
+
+        assert_eq!(
+            super::decode(latin1.clone(), false).unwrap(),
+            "Author: Ren\u{fffd} <rene@email.ca>"
+        );
+        assert!(super::decode(latin1, true).is_err());
+        assert_eq!(super::decode(b"plain".to_vec(), true).unwrap(), "plain");
+    }
+
+    #[test]
+    fn blame_times() {
+        let output = "\
+abc123 1 1 1
+author Jon
+author-time 1669683162
+author-tz -0800
+\tfn main() {
+def456 2 2 1
+author Not Jon
+author-time 1669000000
+author-tz -0800
+\t}
+";
+
+        assert_eq!(
+            super::parse_blame_times(output),
+            vec![1669683162, 1669000000]
+        );
+    }
+
+    #[test]
+    fn blame_authors() {
+        let output = "\
+abc123 1 1 1
+author Jon
+author-mail <jon@email.ca>
+author-time 1669683162
+\tfn main() {
+def456 2 2 1
+author Not Committed Yet
+author-mail <not.committed.yet>
+author-time 1669000000
+\t}
+";
+
+        let authors = super::parse_blame_authors(output);
+
+        assert_eq!(authors.len(), 2);
+        assert_eq!(authors[0].name, "Jon");
+        assert_eq!(authors[0].email, "jon@email.ca");
+        assert_eq!(authors[1].email, "not.committed.yet");
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::commit::{self, Commit};
+
+lazy_static! {
+    // Trailers crediting others, e.g. `Co-authored-by: Jon <jon@email.ca>`
+    static ref CO_AUTHOR_REGEX: Regex =
+        Regex::new(r"(?mi)^co-authored-by:\s*(.+?)\s*<([^>]+)>").unwrap();
+}
+
+/// Who works with whom. Authors are nodes and edges join authors who changed
+/// the same files or shared a commit.
+#[derive(Debug, Default)]
+pub struct Graph {
+    /// Keyed by email, like `commit::by_author()`
+    pub authors: BTreeMap<String, Node>,
+    /// Keyed by the pair of emails, in order
+    pub edges: BTreeMap<(String, String), Edge>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Node {
+    pub name: String,
+    pub commits: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Edge {
+    /// Files both authors changed
+    pub files: u64,
+    /// Commits one authored and the other was credited as a co-author on
+    pub commits: u64,
+}
+
+impl Edge {
+    pub fn weight(&self) -> u64 {
+        self.files + self.commits
+    }
+}
+
+impl Graph {
+    fn edge(&mut self, a: &str, b: &str) -> &mut Edge {
+        let key = if a < b { (a, b) } else { (b, a) };
+
+        self.edges
+            .entry((key.0.to_string(), key.1.to_string()))
+            .or_default()
+    }
+}
+
+/// Build the collaboration graph for the history
+pub fn collaboration(commits: &[Commit]) -> Graph {
+    let mut graph = Graph::default();
+
+    for (author, commits) in commit::by_author(commits) {
+        graph.authors.insert(
+            author.email.clone(),
+            Node {
+                name: author.name.clone(),
+                commits: commits.len() as u64,
+            },
+        );
+    }
+
+    let mut authors_by_file: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+
+    for commit in commits {
+        for change in &commit.file_changes {
+            authors_by_file
+                .entry(&change.path)
+                .or_default()
+                .insert(&commit.author.email);
+        }
+
+        for co_author in CO_AUTHOR_REGEX.captures_iter(&commit.message) {
+            let (name, email) = (&co_author[1], &co_author[2]);
+
+            if email == commit.author.email {
+                continue;
+            }
+
+            // Co-authors who never committed themselves still get a node
+            graph
+                .authors
+                .entry(email.to_string())
+                .or_insert_with(|| Node {
+                    name: name.to_string(),
+                    commits: 0,
+                });
+
+            graph.edge(&commit.author.email, email).commits += 1;
+        }
+    }
+
+    for authors in authors_by_file.values() {
+        let authors: Vec<&str> = authors.iter().copied().collect();
+
+        for (i, a) in authors.iter().enumerate() {
+            for b in &authors[i + 1..] {
+                graph.edge(a, b).files += 1;
+            }
+        }
+    }
+
+    graph
+}
+
+/// The graph in Graphviz's DOT language, e.g. for `dot -Tsvg`. Edges are
+/// thicker the more two authors collaborate.
+pub fn dot(graph: &Graph) -> String {
+    let max = graph
+        .edges
+        .values()
+        .map(Edge::weight)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+
+    let ids: HashMap<&str, usize> = graph
+        .authors
+        .keys()
+        .enumerate()
+        .map(|(i, email)| (email.as_str(), i))
+        .collect();
+
+    let mut dot = String::from("graph collaboration {\n    node [shape=box];\n");
+
+    for (email, node) in &graph.authors {
+        dot.push_str(&format!(
+            "    a{} [label={}];\n",
+            ids[email.as_str()],
+            quote(&format!("{}\n{} commits", node.name, node.commits))
+        ));
+    }
+
+    for ((a, b), edge) in &graph.edges {
+        let width = 1.0 + 4.0 * edge.weight() as f64 / max as f64;
+
+        dot.push_str(&format!(
+            "    a{} -- a{} [weight={}, penwidth={:.1}, label={}];\n",
+            ids[a.as_str()],
+            ids[b.as_str()],
+            edge.weight(),
+            width,
+            quote(&format!("{} files, {} commits", edge.files, edge.commits))
+        ));
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// A quoted DOT string
+fn quote(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::commit;
+
+    use super::Edge;
+
+    #[test]
+    fn collaboration() {
+        let commits = vec![
+            commit()
+                .by("jon")
+                .message("Add things")
+                .paths(&["a.rs", "b.rs"])
+                .build(),
+            commit()
+                .by("amy")
+                .message("Fix things")
+                .paths(&["a.rs", "b.rs"])
+                .build(),
+            commit()
+                .by("amy")
+                .message("More\n\nCo-authored-by: Zed <zed@email.ca>")
+                .paths(&["c.rs"])
+                .build(),
+            commit()
+                .by("zed")
+                .message("Nothing shared")
+                .paths(&["d.rs"])
+                .build(),
+        ];
+
+        let graph = super::collaboration(&commits);
+
+        assert_eq!(graph.authors.len(), 3);
+        assert_eq!(graph.authors["amy@email.ca"].commits, 2);
+        assert_eq!(
+            graph.edges[&("amy@email.ca".to_string(), "jon@email.ca".to_string())],
+            Edge {
+                files: 2,
+                commits: 0
+            }
+        );
+        assert_eq!(
+            graph.edges[&("amy@email.ca".to_string(), "zed@email.ca".to_string())],
+            Edge {
+                files: 0,
+                commits: 1
+            }
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let commits = vec![
+            commit()
+                .by("jon")
+                .message("Add things")
+                .paths(&["a.rs"])
+                .build(),
+            commit()
+                .by("O\"Neil")
+                .message("Fix things")
+                .paths(&["a.rs"])
+                .build(),
+        ];
+
+        let dot = super::dot(&super::collaboration(&commits));
+
+        assert!(dot.starts_with("graph collaboration {\n"));
+        assert!(dot.contains("a1 [label=\"O\\\"Neil\\n1 commits\"];"));
+        assert!(dot.contains("a0 -- a1 [weight=1, penwidth=5.0, label=\"1 files, 0 commits\"];"));
+    }
+}
@@ -0,0 +1,203 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::commit::{Author, Commit, FileChange, Ref, RefKind};
+use crate::git::Diffstat;
+use crate::subprocess;
+
+/// Each changeset's fields separated by unit separators, with the record
+/// separator before each so the description can span lines. The description
+/// comes last but for `--stat`'s block, which hg writes after the template.
+const TEMPLATE: &str = "\x1e{node}\x1f{p1node}\x1f{p2node}\x1f{tags}\x1f\
+    {author|person}\x1f{author|email}\x1f{date|hgdate}\x1f{diffstat}\x1f{desc}\x1f\n";
+
+/// What hg calls the parent of a root, or the second parent of anything but a
+/// merge
+const NULL_PARENT: &str = "0000000000000000000000000000000000000000";
+
+/// An hg command run against the repository at the given path. `HGPLAIN`
+/// keeps someone's config from changing the output, like git's `-c`
+/// overrides.
+fn hg(repo: &Path) -> Command {
+    let mut command = Command::new("hg");
+
+    command
+        .env("HGPLAIN", "1")
+        .env("HGENCODING", "UTF-8")
+        .arg("--repository")
+        .arg(repo);
+
+    command
+}
+
+/// The named branch of the working directory, e.g. `default`
+pub fn branch(repo: &Path) -> anyhow::Result<String> {
+    let output = hg(repo)
+        .arg("branch")
+        .output()
+        .context("Can't run hg to find the branch")?;
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The repository's changesets as commits, newest first. Lines changed in
+/// each file are only asked for with `Diffstat::Files`.
+pub fn commits(repo: &Path, diffstat: Diffstat) -> anyhow::Result<Vec<Commit>> {
+    let mut command = hg(repo);
+    command.arg("log").arg("--template").arg(TEMPLATE);
+
+    if diffstat == Diffstat::Files {
+        command.arg("--stat");
+    }
+
+    let output = subprocess::output(&mut command, "hg log").context("Can't run hg to read logs")?;
+
+    parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse(output: &str) -> anyhow::Result<Vec<Commit>> {
+    output
+        .split('\x1e')
+        .filter(|record| !record.trim().is_empty())
+        .enumerate()
+        .map(|(i, record)| {
+            parse_changeset(record).with_context(|| format!("Changeset {} is invalid", i + 1))
+        })
+        .collect()
+}
+
+fn parse_changeset(record: &str) -> anyhow::Result<Commit> {
+    let fields: Vec<&str> = record.splitn(10, '\x1f').collect();
+
+    let (node, p1, p2, tags, name, email, date, diffstat, desc, stat) = match fields[..] {
+        [node, p1, p2, tags, name, email, date, diffstat, desc, stat] => {
+            (node, p1, p2, tags, name, email, date, diffstat, desc, stat)
+        }
+        _ => bail!("Expected 10 fields but there are {}", fields.len()),
+    };
+
+    let parents = [p1, p2]
+        .into_iter()
+        .filter(|parent| *parent != NULL_PARENT)
+        .map(String::from)
+        .collect();
+
+    // Every repository has a tip, which git has no equivalent of
+    let refs = tags
+        .split_whitespace()
+        .filter(|tag| *tag != "tip")
+        .map(|tag| Ref::new(tag.to_string(), RefKind::Tag))
+        .collect();
+
+    let (files, inserts, deletes) = parse_diffstat(diffstat)
+        .with_context(|| format!("Can't read the diffstat {:?}", diffstat))?;
+
+    Ok(Commit {
+        hash: node.to_string(),
+        parents,
+        refs,
+        author: Author::identified(name, email),
+        date: parse_date(date).with_context(|| format!("Can't read the date {:?}", date))?,
+        committed: None,
+        message: desc.to_string(),
+        files,
+        inserts,
+        deletes,
+        file_changes: stat.lines().filter_map(parse_file_change).collect(),
+    })
+}
+
+/// `hgdate`'s seconds since the epoch and offset in seconds west of UTC, e.g.
+/// `1669680000 28800` for 2022-11-28 16:00 -08:00
+fn parse_date(date: &str) -> Option<OffsetDateTime> {
+    let (seconds, west) = date.split_once(' ')?;
+    let offset = UtcOffset::from_whole_seconds(-west.parse::<i32>().ok()?).ok()?;
+
+    OffsetDateTime::from_unix_timestamp(seconds.parse().ok()?)
+        .ok()
+        .map(|date| date.to_offset(offset))
+}
+
+/// Files changed, inserts, and deletes from `diffstat`, e.g. `2: +10/-3`
+fn parse_diffstat(diffstat: &str) -> Option<(u32, u32, u32)> {
+    let (files, lines) = diffstat.split_once(": +")?;
+    let (inserts, deletes) = lines.split_once("/-")?;
+
+    Some((
+        files.trim().parse().ok()?,
+        inserts.parse().ok()?,
+        deletes.trim().parse().ok()?,
+    ))
+}
+
+/// A line of `--stat`'s block, e.g. ` src/main.rs |  5 +++--`. Binary files
+/// changed no lines, and the summary line isn't a file.
+fn parse_file_change(line: &str) -> Option<FileChange> {
+    let (path, graph) = line.rsplit_once(" | ")?;
+    let changes = graph.split_whitespace().next()?.parse().unwrap_or(0);
+
+    Some(FileChange::new(path.trim().to_string(), changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::commit::RefKind;
+
+    #[test]
+    fn parse() {
+        let output = format!(
+            "\
+\x1eaaa\x1f0c1\x1f{null}\x1ftip v1.0\x1fJon\x1fjon@email.ca\x1f1669680000 28800\x1f3: +10/-3\x1fAdd the parser
+
+With a body\x1f
+ README.md   |  3 ++-
+ src/main.rs |  10 ++++++++--
+ logo.png    |  Bin 0 -> 1024 bytes
+ 3 files changed, 10 insertions(+), 3 deletions(-)
+
+\x1ebbb\x1f0a1\x1f0b2\x1f\x1fNot Jon\x1f\x1f1669593600 0\x1f0: +0/-0\x1fMerge\x1f
+",
+            null = super::NULL_PARENT
+        );
+
+        let commits = super::parse(&output).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "aaa");
+        assert_eq!(commits[0].parents, vec!["0c1"]);
+        assert_eq!(commits[0].refs.len(), 1);
+        assert_eq!(commits[0].refs[0].name, "v1.0");
+        assert_eq!(commits[0].refs[0].kind, RefKind::Tag);
+        assert_eq!(commits[0].author.email, "jon@email.ca");
+        assert_eq!(commits[0].date, datetime!(2022-11-28 16:00 -8));
+        assert_eq!(commits[0].date.offset().whole_hours(), -8);
+        assert_eq!(commits[0].message, "Add the parser\n\nWith a body");
+        assert_eq!(
+            (commits[0].files, commits[0].inserts, commits[0].deletes),
+            (3, 10, 3)
+        );
+        assert_eq!(commits[0].file_changes.len(), 3);
+        assert_eq!(commits[0].file_changes[1].path, "src/main.rs");
+        assert_eq!(commits[0].file_changes[1].changes, 10);
+        assert_eq!(commits[0].file_changes[2].changes, 0);
+
+        assert_eq!(commits[1].parents, vec!["0a1", "0b2"]);
+        assert_eq!(commits[1].author.email, "Not Jon");
+        assert!(commits[1].file_changes.is_empty());
+    }
+
+    #[test]
+    fn invalid() {
+        let error = super::parse("\x1eaaa\x1fonly").unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Changeset 1 is invalid: Expected 10 fields but there are 2"
+        );
+    }
+}
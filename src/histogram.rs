@@ -1,53 +1,251 @@
-use hdrhistogram::Histogram;
+use std::str::FromStr;
 
 use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
 
-pub enum Kind {
-    ByHour,
-    ByWeekday,
+/// Abbreviated weekday names in the order `by_weekday_and_hour()` uses
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// What each commit adds to the bucket it falls in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Weight {
+    /// One, i.e. counting commits
+    #[default]
+    Count,
+    /// Lines inserted plus lines deleted
+    Lines,
+    /// Files changed
+    Files,
 }
 
-pub fn of_kind(kind: Kind, commits: &Vec<Commit>) -> Histogram<u8> {
-    match kind {
-        Kind::ByHour => by_hour(commits),
-        Kind::ByWeekday => by_weekday(commits),
+impl FromStr for Weight {
+    type Err = String;
+
+    fn from_str(weight: &str) -> Result<Weight, String> {
+        match weight {
+            "count" => Ok(Weight::Count),
+            "lines" => Ok(Weight::Lines),
+            "files" => Ok(Weight::Files),
+            _ => Err(format!("Unknown weight {}", weight)),
+        }
     }
 }
 
-fn by_hour(commits: &Vec<Commit>) -> Histogram<u8> {
-    let mut histogram = Histogram::new_with_bounds(1, 24, 1).unwrap();
+impl Weight {
+    pub fn of(self, commit: &Commit) -> u64 {
+        self.of_totals(commit.files, commit.churn())
+    }
 
-    for commit in commits {
-        let hour = get_hour(commit);
-        histogram.record(hour).unwrap();
+    /// Like `of()`, from a commit's files changed and lines inserted plus
+    /// deleted
+    pub fn of_totals(self, files: u32, churn: u64) -> u64 {
+        match self {
+            Weight::Count => 1,
+            Weight::Lines => churn,
+            Weight::Files => files as u64,
+        }
+    }
+
+    /// What the totals are of, for a report's column
+    pub fn name(self) -> &'static str {
+        match self {
+            Weight::Count => "commits",
+            Weight::Lines => "lines changed",
+            Weight::Files => "files changed",
+        }
+    }
+}
+
+/// Totals in a fixed number of buckets, e.g. one for each hour of the day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Histogram<const N: usize> {
+    totals: [u64; N],
+}
+
+impl<const N: usize> Default for Histogram<N> {
+    fn default() -> Self {
+        Histogram { totals: [0; N] }
+    }
+}
+
+impl<const N: usize> Histogram<N> {
+    /// Count one more in the bucket
+    pub fn record(&mut self, bucket: usize) {
+        self.record_weighted(bucket, 1);
+    }
+
+    pub fn record_weighted(&mut self, bucket: usize, weight: u64) {
+        self.totals[bucket] += weight;
+    }
+
+    pub fn totals(&self) -> [u64; N] {
+        self.totals
+    }
+}
+
+/// Commits, or whatever they're weighted by, for each hour of the day, from
+/// midnight
+pub fn by_hour(commits: &[Commit], weight: Weight) -> [u64; 24] {
+    hours(&by_weekday_and_hour(commits, weight))
+}
+
+/// Commits, or whatever they're weighted by, for each day of the week, from
+/// Monday
+pub fn by_weekday(commits: &[Commit], weight: Weight) -> [u64; 7] {
+    weekdays(&by_weekday_and_hour(commits, weight))
+}
+
+/// Totals for each hour of the day, across every weekday
+pub fn hours(weekday_hours: &[[u64; 24]; 7]) -> [u64; 24] {
+    let mut totals = [0; 24];
+
+    for hours in weekday_hours {
+        for (hour, total) in hours.iter().enumerate() {
+            totals[hour] += total;
+        }
+    }
+
+    totals
+}
+
+/// Totals for each day of the week, across every hour
+pub fn weekdays(weekday_hours: &[[u64; 24]; 7]) -> [u64; 7] {
+    let mut totals = [0; 7];
+
+    for (weekday, hours) in weekday_hours.iter().enumerate() {
+        totals[weekday] = hours.iter().sum();
     }
 
-    histogram
+    totals
 }
 
-fn by_weekday(commits: &Vec<Commit>) -> Histogram<u8> {
-    let mut histogram = Histogram::new_with_bounds(1, 7, 1).unwrap();
+/// Total commits, or whatever they're weighted by, for every hour of every
+/// weekday. The outer index is the weekday starting from Monday and the inner
+/// index is the hour of day.
+pub fn by_weekday_and_hour<'a>(
+    commits: impl IntoIterator<Item = &'a Commit>,
+    weight: Weight,
+) -> [[u64; 24]; 7] {
+    let mut histogram = Histogram::<{ 7 * 24 }>::default();
 
     for commit in commits {
-        let weekday = get_weekday(commit);
-        histogram.record(weekday).unwrap();
+        let weekday = commit.date.weekday().number_days_from_monday() as usize;
+        let hour = commit.date.hour() as usize;
+
+        histogram.record_weighted(weekday * 24 + hour, weight.of(commit));
+    }
+
+    let totals = histogram.totals();
+    let mut by_weekday = [[0; 24]; 7];
+
+    for (weekday, hours) in by_weekday.iter_mut().enumerate() {
+        hours.copy_from_slice(&totals[weekday * 24..(weekday + 1) * 24]);
     }
 
-    histogram
+    by_weekday
 }
 
-fn get_hour(commit: &Commit) -> u64 {
-    commit.date.hour() as u64
+/// Upper bounds of buckets of durations in hours, and their labels. The last
+/// bucket has no upper bound.
+pub type Durations = [(f64, &'static str)];
+
+/// How many of the durations, in hours, fall in each bucket, under columns
+/// naming the buckets and what's counted, e.g. how long commits took to land
+pub fn of_durations(title: &str, columns: [&str; 2], hours: &[f64], buckets: &Durations) -> Report {
+    let mut counts = vec![0u64; buckets.len()];
+
+    for hours in hours {
+        let bucket = buckets
+            .iter()
+            .position(|(bound, _)| hours < bound)
+            .unwrap_or(buckets.len() - 1);
+
+        counts[bucket] += 1;
+    }
+
+    let mut table = Table::new(&columns);
+
+    for ((_, label), count) in buckets.iter().zip(counts) {
+        table.push(vec![Value::Text(label.to_string()), Value::Count(count)]);
+    }
+
+    Report::histogram(title, table)
 }
 
-fn get_weekday(commit: &Commit) -> u64 {
-    match commit.date.weekday() {
-        time::Weekday::Monday => 1,
-        time::Weekday::Tuesday => 2,
-        time::Weekday::Wednesday => 3,
-        time::Weekday::Thursday => 4,
-        time::Weekday::Friday => 5,
-        time::Weekday::Saturday => 6,
-        time::Weekday::Sunday => 7,
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+    use time::Duration;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    use super::{Histogram, Weight};
+
+    #[test]
+    fn midnight_and_sunday() {
+        // A Sunday at midnight and a Monday just before midnight
+        let commits = vec![
+            commit().on(datetime!(2022-11-27 00:00 -8)).build(),
+            commit().on(datetime!(2022-11-28 23:59 -8)).build(),
+        ];
+
+        let by_hour = super::by_hour(&commits, Weight::Count);
+
+        assert_eq!(by_hour[0], 1);
+        assert_eq!(by_hour[23], 1);
+        assert_eq!(by_hour.iter().sum::<u64>(), 2);
+        assert_eq!(
+            super::by_weekday(&commits, Weight::Count),
+            [1, 0, 0, 0, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn weighted() {
+        let commits = vec![
+            commit()
+                .on(datetime!(2022-11-28 00:30 -8) + Duration::hours(9))
+                .files(2)
+                .lines(10, 5)
+                .build(),
+            commit()
+                .on(datetime!(2022-11-28 00:30 -8) + Duration::hours(9))
+                .files(1)
+                .lines(0, 3)
+                .build(),
+            commit()
+                .on(datetime!(2022-11-28 00:30 -8) + Duration::hours(14))
+                .files(4)
+                .lines(1, 0)
+                .build(),
+        ];
+
+        let lines = super::by_hour(&commits, Weight::Lines);
+        assert_eq!((lines[9], lines[14]), (18, 1));
+
+        let files = super::by_hour(&commits, Weight::Files);
+        assert_eq!((files[9], files[14]), (3, 4));
+
+        let mut histogram = Histogram::<3>::default();
+        histogram.record(0);
+        histogram.record_weighted(2, 7);
+        histogram.record_weighted(2, 1);
+        assert_eq!(histogram.totals(), [1, 0, 8]);
+
+        assert_eq!("lines".parse(), Ok(Weight::Lines));
+        assert!("churn".parse::<Weight>().is_err());
+    }
+
+    #[test]
+    fn durations() {
+        let buckets = [(1.0, "under an hour"), (f64::INFINITY, "an hour or more")];
+
+        let report = super::of_durations("Lag:", ["lag", "commits"], &[0.5, 1.0, 30.0], &buckets);
+
+        assert!(report.histogram);
+        assert_eq!(report.table.columns, vec!["lag", "commits"]);
+        assert_eq!(report.table.rows[0][1], Value::Count(1));
+        assert_eq!(report.table.rows[1][1], Value::Count(2));
     }
 }
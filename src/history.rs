@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::commit::{Commit, CommitRef, Ref};
+use crate::export::json;
+use crate::git::Diffstat;
+use crate::{cache, git, parser};
+
+/// The repository's commits, newest first. Repositories with a hook installed
+/// are read from the cache, which is brought up to date first in case a
+/// commit slipped past the hook, e.g. one made with `--no-verify`.
+///
+/// With `strict`, log output that isn't UTF-8 is an error rather than having
+/// its invalid bytes replaced. Commits already cached aren't read again.
+///
+/// Reports needing only each commit's totals can ask for `Diffstat::Totals`
+/// so uncached commits are read faster. Cached commits always have files.
+pub fn commits(repo: &Path, diffstat: Diffstat, strict: bool) -> anyhow::Result<Vec<Commit>> {
+    let path = cache_file(repo)?;
+
+    if path.exists() {
+        let mut commits = update(repo, strict)?;
+        decorate(repo, &mut commits)?;
+        Ok(commits)
+    } else {
+        parser::parse(&git::logs(repo, diffstat, strict)?)
+    }
+}
+
+/// Like `commits()` but read from git `days` at a time from the newest, so
+/// `progress` can be told of the commits read so far and whether there are more
+/// to read, e.g. to show partial results of a huge history or one on a slow
+/// disk. The cache isn't used.
+pub fn commits_in_windows(
+    repo: &Path,
+    days: u64,
+    diffstat: Diffstat,
+    strict: bool,
+    mut progress: impl FnMut(&[Commit], bool),
+) -> anyhow::Result<Vec<Commit>> {
+    let oldest = git::first_time(repo)?;
+    let newest = git::head_time(repo)?;
+
+    let mut commits = Vec::new();
+
+    let windows = windows(oldest, newest, days);
+    let last = windows.len() - 1;
+
+    for (i, (since, until)) in windows.into_iter().enumerate() {
+        commits.extend(parser::parse(&git::logs_between(
+            repo, since, until, diffstat, strict,
+        )?)?);
+
+        progress(&commits, i < last);
+    }
+
+    Ok(commits)
+}
+
+/// Windows of `days` between two Unix timestamps, newest first, as the first
+/// and last second of each. The newest window is open ended so commits made
+/// after HEAD, e.g. with a skewed clock, are still read, and likewise the
+/// oldest.
+fn windows(oldest: i64, newest: i64, days: u64) -> Vec<(Option<i64>, Option<i64>)> {
+    let span = days.max(1) as i64 * 24 * 60 * 60;
+
+    let mut windows = Vec::new();
+    let mut end = newest + 1;
+
+    loop {
+        let start = end - span;
+
+        let since = (start > oldest).then_some(start);
+        let until = (!windows.is_empty()).then_some(end - 1);
+
+        windows.push((since, until));
+
+        if since.is_none() {
+            return windows;
+        }
+
+        end = start;
+    }
+}
+
+/// The repository's commits, newest first, with nothing but their hashes and
+/// dates, e.g. for reports on when commits are made
+pub fn dates(repo: &Path) -> anyhow::Result<Vec<Commit>> {
+    parser::parse_dates(&git::dates(repo)?)
+}
+
+/// Call `each` with the repository's commits, newest first, with only their
+/// subjects and totals. Each is parsed as git writes it, borrowing from a
+/// buffer holding just that commit, so the whole log is never held in memory.
+/// The cache is bypassed since it's read whole.
+pub fn each_slim(
+    repo: &Path,
+    strict: bool,
+    mut each: impl FnMut(&CommitRef),
+) -> anyhow::Result<()> {
+    // Files aren't kept so git needn't write them
+    let mut lines = git::log_lines(repo, Diffstat::Totals, strict)?;
+    let mut block = String::new();
+
+    let mut flush = |block: &mut String| -> anyhow::Result<()> {
+        parser::parse_borrowed(block)?.iter().for_each(&mut each);
+        block.clear();
+        Ok(())
+    };
+
+    for line in &mut lines {
+        if parser::is_hash(&line) {
+            flush(&mut block)?;
+        }
+
+        block.push_str(&line);
+        block.push('\n');
+    }
+
+    flush(&mut block)?;
+    lines.finish()
+}
+
+/// Point commits at the branches and tags as they are now. Branches move and
+/// tags are added to old commits, neither of which adds commits to the cache,
+/// so its decorations are only as fresh as each commit.
+fn decorate(repo: &Path, commits: &mut [Commit]) -> anyhow::Result<()> {
+    let mut refs: HashMap<String, Vec<Ref>> = HashMap::new();
+
+    for (hash, r) in git::refs(repo)? {
+        refs.entry(hash).or_default().push(r);
+    }
+
+    for commit in commits {
+        commit.refs = refs.remove(&commit.hash).unwrap_or_default();
+    }
+
+    Ok(())
+}
+
+/// Bring the cache up to date with HEAD, parsing only the commits it doesn't
+/// have yet. When the newest cached commit is no longer in the history, e.g.
+/// after amending or rebasing, the whole history is parsed again.
+pub fn update(repo: &Path, strict: bool) -> anyhow::Result<Vec<Commit>> {
+    let path = cache_file(repo)?;
+    let head = git::head(repo)?;
+
+    let cached = if path.exists() {
+        json::read(&path)?
+    } else {
+        Vec::new()
+    };
+
+    let newest = cached.first().map(|commit| commit.hash.clone());
+
+    let commits = match newest {
+        Some(newest) if newest == head => return Ok(cached),
+        Some(newest) if git::is_ancestor(repo, &newest, &head)? => {
+            let mut commits =
+                parser::parse(&git::logs_since(repo, &newest, Diffstat::Files, strict)?)?;
+            commits.extend(cached);
+            commits
+        }
+        _ => parser::parse(&git::logs(repo, Diffstat::Files, strict)?)?,
+    };
+
+    // Written aside then moved into place so a report running at the same
+    // time never reads half a cache
+    let partial = path.with_extension("jsonl.partial");
+    fs::write(&partial, json::lines(&commits))
+        .with_context(|| format!("Can't write {}", partial.display()))?;
+    fs::rename(&partial, &path)?;
+
+    Ok(commits)
+}
+
+fn cache_file(repo: &Path) -> anyhow::Result<PathBuf> {
+    cache::file(repo, "commits.jsonl")
+}
+
+#[cfg(test)]
+mod tests {
+    const DAY: i64 = 24 * 60 * 60;
+
+    #[test]
+    fn windows() {
+        let windows = super::windows(0, 10 * DAY, 4);
+
+        assert_eq!(
+            windows,
+            vec![
+                (Some(6 * DAY + 1), None),
+                (Some(2 * DAY + 1), Some(6 * DAY)),
+                (None, Some(2 * DAY)),
+            ]
+        );
+
+        assert_eq!(super::windows(5, 5, 7), vec![(None, None)]);
+    }
+}
@@ -0,0 +1,72 @@
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+
+use crate::{git, history};
+
+/// Hooks run after history changes locally. Amends also run `post-commit`.
+const HOOKS: [&str; 2] = ["post-commit", "post-merge"];
+
+/// Identifies hooks yeesh wrote so they can be replaced but others aren't
+const MARKER: &str = "# Installed by yeesh";
+
+/// Install hooks that keep the cache of the repository's history current so
+/// reports don't have to parse the whole history every time. Returns the
+/// hooks installed.
+pub fn install(repo: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let dir = git::hooks_dir(repo)?;
+    fs::create_dir_all(&dir).with_context(|| format!("Can't create {}", dir.display()))?;
+
+    let yeesh = env::current_exe().context("Can't find yeesh's own path")?;
+    let script = script(&yeesh.to_string_lossy());
+
+    let paths: Vec<PathBuf> = HOOKS.iter().map(|hook| dir.join(hook)).collect();
+
+    // Check every hook before writing any so a failure doesn't leave half of
+    // them installed
+    for path in &paths {
+        if let Ok(existing) = fs::read_to_string(path) {
+            if !existing.contains(MARKER) {
+                bail!(
+                    "{} already exists, add `{} hook update` to it instead",
+                    path.display(),
+                    yeesh.display()
+                );
+            }
+        }
+    }
+
+    for path in &paths {
+        fs::write(path, &script).with_context(|| format!("Can't write {}", path.display()))?;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    // Fill the cache now rather than leaving it to the first commit
+    history::update(repo, false)?;
+
+    Ok(paths)
+}
+
+/// A shell script updating the cache in the background so committing isn't
+/// slowed down
+fn script(yeesh: &str) -> String {
+    format!(
+        "#!/bin/sh\n{}\n'{}' hook update >/dev/null 2>&1 &\n",
+        MARKER,
+        yeesh.replace('\'', "'\\''")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn script() {
+        assert_eq!(
+            super::script("/opt/it's/yeesh"),
+            "#!/bin/sh\n# Installed by yeesh\n'/opt/it'\\''s/yeesh' hook update >/dev/null 2>&1 &\n"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Pages are self-contained, with their assets built into yeesh and inlined
+//! rather than linked, so they work offline or saved to a file with nothing
+//! else fetched.
+
+use crate::report::{Report, Value};
+
+const STYLE: &str = include_str!("assets/style.css");
+
+const FAVICON: &[u8] = include_bytes!("assets/favicon.svg");
+
+/// A page listing each repository with a link to its dashboard
+pub fn index(repos: &[&str]) -> String {
+    let links: Vec<String> = repos
+        .iter()
+        .map(|repo| {
+            format!(
+                "<li><a href=\"/repos/{}\">{}</a></li>",
+                escape(repo),
+                escape(repo)
+            )
+        })
+        .collect();
+
+    page("yeesh", &format!("<ul>{}</ul>", links.join("")))
+}
+
+/// A page of every report for a single repository
+pub fn dashboard(repo: &str, analyzed_at: &str, reports: &[Report]) -> String {
+    let mut body = format!(
+        "<p class=\"muted\">Analyzed {} &middot; <a href=\"/api/repos/{}\">JSON</a></p>\n",
+        escape(analyzed_at),
+        escape(repo)
+    );
+
+    for report in reports {
+        body.push_str(&self::report(report));
+    }
+
+    page(repo, &body)
+}
+
+/// The reports as a page to save, e.g. to attach to an email
+pub fn reports(repo: &str, reports: &[Report]) -> String {
+    let body: String = reports.iter().map(report).collect();
+    page(repo, &body)
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<link rel=\"icon\" href=\"data:image/svg+xml;base64,{icon}\">\n<style>\n{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n{body}\n</body>\n</html>\n",
+        title = escape(title),
+        icon = base64(FAVICON),
+        STYLE = STYLE,
+        body = body,
+    )
+}
+
+/// Standard base64 with padding, for data URIs
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+fn report(report: &Report) -> String {
+    let mut html = format!("<h2>{}</h2>\n<table>\n", escape(&report.title));
+
+    html.push_str("<tr>");
+    for column in &report.table.columns {
+        html.push_str(&format!("<th>{}</th>", escape(column)));
+    }
+    html.push_str("</tr>\n");
+
+    // Histogram bars are scaled against the largest bucket
+    let max = report
+        .table
+        .rows
+        .iter()
+        .filter_map(|row| match row.get(1) {
+            Some(Value::Count(count)) => Some(*count),
+            _ => None,
+        })
+        .max()
+        .unwrap_or_default()
+        .max(1);
+
+    for row in &report.table.rows {
+        html.push_str("<tr>");
+
+        for value in row {
+            html.push_str(&format!("<td>{}</td>", escape(&value.to_string())));
+        }
+
+        if let (true, Some(Value::Count(count))) = (report.histogram, row.get(1)) {
+            let width = *count as f64 / max as f64 * 30.0;
+            html.push_str(&format!(
+                "<td><div class=\"bar\" style=\"width: {:.2}em\"></div></td>",
+                width
+            ));
+        }
+
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>\n");
+    html
+}
+
+/// Escape text for use in both element content and quoted attributes
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::{Report, Table, Value};
+
+    #[test]
+    fn escape() {
+        assert_eq!(
+            super::escape("<a href=\"x\">O'Neil & co</a>"),
+            "&lt;a href=&quot;x&quot;&gt;O&#39;Neil &amp; co&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(super::base64(b""), "");
+        assert_eq!(super::base64(b"f"), "Zg==");
+        assert_eq!(super::base64(b"fo"), "Zm8=");
+        assert_eq!(super::base64(b"foo"), "Zm9v");
+        assert_eq!(super::base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn self_contained() {
+        let html = super::reports("yeesh", &[Report::new("Empty:", Table::new(&["x"]))]);
+
+        assert!(html.contains("<style>\nbody {"));
+        assert!(html.contains("href=\"data:image/svg+xml;base64,PHN2Zy"));
+        assert!(!html.contains("src="));
+        assert!(!html.contains("href=\"http"));
+    }
+
+    #[test]
+    fn histogram_bars() {
+        let mut table = Table::new(&["hour", "commits"]);
+        table.push(vec![Value::Text("00".to_string()), Value::Count(2)]);
+        table.push(vec![Value::Text("01".to_string()), Value::Count(1)]);
+
+        let html = super::report(&Report::histogram("By hour:", table));
+
+        assert!(html.contains("<h2>By hour:</h2>"));
+        assert!(
+            html.contains("<td>00</td><td>2</td><td><div class=\"bar\" style=\"width: 30.00em\">")
+        );
+        assert!(
+            html.contains("<td>01</td><td>1</td><td><div class=\"bar\" style=\"width: 15.00em\">")
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context};
+
+/// GET a URL with curl and parse the response as JSON.
+///
+/// Headers are given to curl on stdin rather than as arguments so tokens don't
+/// show up in process listings.
+pub fn get_json(url: &str, headers: &[String]) -> anyhow::Result<serde_json::Value> {
+    let output = curl(&["--header", "@-", url], headers.join("\n").as_bytes(), url)?;
+
+    let json = serde_json::from_slice(&output)
+        .with_context(|| format!("Response from {} isn't JSON", url))?;
+
+    Ok(json)
+}
+
+/// POST a JSON body to a URL, ignoring whatever it responds with
+pub fn post_json(url: &str, body: &str) -> anyhow::Result<()> {
+    curl(
+        &[
+            "--header",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+            url,
+        ],
+        body.as_bytes(),
+        url,
+    )?;
+
+    Ok(())
+}
+
+/// Run curl with `input` on stdin, returning the response body
+fn curl(args: &[&str], input: &[u8], url: &str) -> anyhow::Result<Vec<u8>> {
+    let mut curl = Command::new("curl")
+        .arg("--silent")
+        .arg("--show-error")
+        .arg("--fail")
+        .arg("--location")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Can't run curl, is it installed?")?;
+
+    let mut stdin = curl.stdin.take().context("Can't write to curl")?;
+    stdin.write_all(input)?;
+    drop(stdin);
+
+    let output = curl.wait_with_output()?;
+
+    if !output.status.success() {
+        bail!(
+            "Request to {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(output.stdout)
+}
@@ -0,0 +1,76 @@
+pub mod csv;
+pub mod tsv;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::Context;
+
+use crate::commit::{self, Commit, Order};
+use crate::export::json;
+use crate::parser;
+
+/// Kinds of file commits can be imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// yeesh's own JSON or JSON lines exports
+    Json,
+    /// Activity from other systems, see `csv` for the columns
+    Csv,
+    /// Tab-separated commits from any system, see `tsv` for the columns
+    Tsv,
+    /// Saved output of `git log --stat` with any of the dates git can show
+    Git,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Format, String> {
+        match format {
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "tsv" => Ok(Format::Tsv),
+            "git" => Ok(Format::Git),
+            _ => Err(format!("Unknown input format {}", format)),
+        }
+    }
+}
+
+/// Commits from every file, newest first like git's own history and then by
+/// hash so the order doesn't depend on the order of the files. Commits in more
+/// than one file are only counted once.
+pub fn read(paths: &[PathBuf], format: Format) -> anyhow::Result<Vec<Commit>> {
+    let mut commits = Vec::new();
+    let mut seen = HashSet::new();
+
+    for path in paths {
+        let read = match format {
+            Format::Json => json::read(path)?,
+            Format::Csv => csv::read(path)?,
+            Format::Tsv => tsv::read(path)?,
+            Format::Git => read_log(path)?,
+        };
+
+        for commit in read {
+            if seen.insert(commit.hash.clone()) {
+                commits.push(commit);
+            }
+        }
+    }
+
+    commit::sort(&mut commits, Order::Newest);
+
+    Ok(commits)
+}
+
+/// Commits from a saved git log. Invalid UTF-8 is replaced like it is when
+/// reading git directly.
+fn read_log(path: &Path) -> anyhow::Result<Vec<Commit>> {
+    let bytes = fs::read(path).with_context(|| format!("Can't read {}", path.display()))?;
+
+    parser::parse(&String::from_utf8_lossy(&bytes))
+        .with_context(|| format!("Can't import {}", path.display()))
+}
@@ -0,0 +1,242 @@
+//! Commits from CSV so activity recorded elsewhere, e.g. ticketing exports or
+//! other version control systems, can be reported on like a git history.
+//!
+//! The first row names the columns, in any order. Unknown columns are ignored.
+//!
+//! | column    | required | meaning                                            |
+//! |-----------|----------|----------------------------------------------------|
+//! | `date`    | yes      | RFC 3339 or RFC 2822, e.g. `2022-11-28T16:00:00-08:00` |
+//! | `author`  | yes      | name of whoever made the change                    |
+//! | `committed` | no     | when the change landed, in the same formats as `date` |
+//! | `email`   | no       | groups authors, defaults to `author`               |
+//! | `hash`    | no       | unique ID, defaults to the file and row number     |
+//! | `message` | no       | first line is the subject                          |
+//! | `inserts` | no       | lines added, defaults to 0                         |
+//! | `deletes` | no       | lines removed, defaults to 0                       |
+//! | `files`   | no       | paths changed separated by `;`                     |
+//!
+//! Fields are quoted as in RFC 4180 when they contain commas, quotes, or
+//! newlines.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+use time::OffsetDateTime;
+
+use crate::commit::{Author, Commit, FileChange};
+
+/// Read commits from a CSV file with the columns described above
+pub fn read(path: &Path) -> anyhow::Result<Vec<Commit>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+
+    parse(&text, &path.display().to_string())
+        .with_context(|| format!("Can't import {}", path.display()))
+}
+
+fn parse(text: &str, source: &str) -> anyhow::Result<Vec<Commit>> {
+    let mut rows = records(text)?.into_iter();
+
+    let header = rows.next().context("There's no header row")?;
+    let columns: HashMap<&str, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.trim(), i))
+        .collect();
+
+    for required in ["date", "author"] {
+        if !columns.contains_key(required) {
+            bail!("There's no {} column", required);
+        }
+    }
+
+    rows.enumerate()
+        .filter(|(_, fields)| fields.iter().any(|field| !field.is_empty()))
+        .map(|(i, fields)| {
+            let row = Row {
+                columns: &columns,
+                fields: &fields,
+            };
+
+            // Rows are numbered as a spreadsheet would, counting the header
+            let number = i + 2;
+
+            commit(&row, source, number).with_context(|| format!("Row {} is invalid", number))
+        })
+        .collect()
+}
+
+/// A row's fields looked up by column name
+struct Row<'a> {
+    columns: &'a HashMap<&'a str, usize>,
+    fields: &'a [String],
+}
+
+impl<'a> Row<'a> {
+    /// The trimmed field, or empty when the column or field is missing
+    fn get(&self, column: &str) -> &'a str {
+        self.columns
+            .get(column)
+            .and_then(|&i| self.fields.get(i))
+            .map(|field| field.trim())
+            .unwrap_or_default()
+    }
+}
+
+fn commit(row: &Row, source: &str, number: usize) -> anyhow::Result<Commit> {
+    let parse_date = |date: &str| {
+        OffsetDateTime::parse(date, &Rfc3339)
+            .or_else(|_| OffsetDateTime::parse(date, &Rfc2822))
+            .with_context(|| format!("Can't read the date {:?}", date))
+    };
+
+    let date = parse_date(row.get("date"))?;
+
+    let committed = match row.get("committed") {
+        "" => None,
+        committed => Some(parse_date(committed)?),
+    };
+
+    let author = row.get("author");
+    if author.is_empty() {
+        bail!("There's no author");
+    }
+
+    let email = match row.get("email") {
+        "" => author,
+        email => email,
+    };
+
+    let hash = match row.get("hash") {
+        "" => format!("{}:{}", source, number),
+        hash => hash.to_string(),
+    };
+
+    let count = |name: &str| -> anyhow::Result<u32> {
+        match row.get(name) {
+            "" => Ok(0),
+            count => count
+                .parse()
+                .with_context(|| format!("Can't read {} {:?}", name, count)),
+        }
+    };
+
+    let inserts = count("inserts")?;
+    let deletes = count("deletes")?;
+
+    // Lines changed per file aren't known so they're left at zero
+    let file_changes: Vec<FileChange> = row
+        .get("files")
+        .split(';')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(|path| FileChange::new(path.to_string(), 0))
+        .collect();
+
+    Ok(Commit {
+        hash,
+        parents: Vec::new(),
+        refs: Vec::new(),
+        author: Author::new(author.to_string(), email.to_string()),
+        date,
+        committed,
+        message: row.get("message").to_string(),
+        files: file_changes.len() as u32,
+        inserts,
+        deletes,
+        file_changes,
+    })
+}
+
+/// Split CSV into rows of fields, handling quoted fields per RFC 4180
+fn records(text: &str) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+
+    if quoted {
+        bail!("A quoted field is never closed");
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    #[test]
+    fn records() {
+        let rows = super::records("a,b\r\n\"x, \"\"y\"\"\",\"multi\nline\"\nlast,").unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                vec!["a", "b"],
+                vec!["x, \"y\"", "multi\nline"],
+                vec!["last", ""],
+            ]
+        );
+        assert!(super::records("\"open").is_err());
+    }
+
+    #[test]
+    fn parse() {
+        let text = "\
+author,date,inserts,files,ticket
+Jon,2022-11-28T16:00:00-08:00,3,src/main.rs; README.md,ABC-1
+Not Jon,\"Mon, 28 Nov 2022 09:00:00 +0000\",,,ABC-2
+";
+
+        let commits = super::parse(text, "tickets.csv").unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "tickets.csv:2");
+        assert_eq!(commits[0].author.email, "Jon");
+        assert_eq!(commits[0].date, datetime!(2022-11-28 16:00 -8));
+        assert_eq!(commits[0].inserts, 3);
+        assert_eq!(commits[0].files, 2);
+        assert_eq!(commits[0].file_changes[1].path, "README.md");
+        assert_eq!(commits[1].date, datetime!(2022-11-28 09:00 UTC));
+        assert_eq!(commits[1].inserts, 0);
+    }
+
+    #[test]
+    fn invalid() {
+        let missing = super::parse("author\nJon\n", "x.csv").unwrap_err();
+        assert_eq!(missing.to_string(), "There's no date column");
+
+        let bad = super::parse("author,date\nJon,yesterday\n", "x.csv").unwrap_err();
+        assert_eq!(bad.to_string(), "Row 2 is invalid");
+    }
+}
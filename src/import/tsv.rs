@@ -0,0 +1,167 @@
+//! Commits from tab-separated values, the simplest thing any system can write,
+//! e.g. `hg log --template` or a database query, so its history can be
+//! reported on without a parser of its own.
+//!
+//! Each line is a commit with these columns in this order. A first line naming
+//! the columns is skipped, as are blank lines.
+//!
+//! | column      | meaning                                                   |
+//! |-------------|-----------------------------------------------------------|
+//! | `hash`      | unique ID, defaults to the file and line number           |
+//! | `author`    | name of whoever made the change                           |
+//! | `email`     | groups authors, defaults to `author`                      |
+//! | `timestamp` | seconds since the epoch in UTC, or RFC 3339 to keep the offset |
+//! | `files`     | number of files changed, defaults to 0                    |
+//! | `inserts`   | lines added, defaults to 0                                |
+//! | `deletes`   | lines removed, defaults to 0                              |
+//!
+//! Fields can't contain tabs or newlines since there's no quoting.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::commit::{Author, Commit};
+
+const COLUMNS: [&str; 7] = [
+    "hash",
+    "author",
+    "email",
+    "timestamp",
+    "files",
+    "inserts",
+    "deletes",
+];
+
+/// Read commits from a TSV file with the columns described above
+pub fn read(path: &Path) -> anyhow::Result<Vec<Commit>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+
+    parse(&text, &path.display().to_string())
+        .with_context(|| format!("Can't import {}", path.display()))
+}
+
+fn parse(text: &str, source: &str) -> anyhow::Result<Vec<Commit>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter(|(i, line)| !(*i == 0 && is_header(line)))
+        .map(|(i, line)| {
+            let number = i + 1;
+
+            commit(line, source, number).with_context(|| format!("Line {} is invalid", number))
+        })
+        .collect()
+}
+
+fn is_header(line: &str) -> bool {
+    line.split('\t').map(str::trim).eq(COLUMNS)
+}
+
+fn commit(line: &str, source: &str, number: usize) -> anyhow::Result<Commit> {
+    let fields: Vec<&str> = line.trim_end_matches('\r').split('\t').collect();
+
+    if fields.len() != COLUMNS.len() {
+        bail!(
+            "Expected {} columns but there are {}",
+            COLUMNS.len(),
+            fields.len()
+        );
+    }
+
+    let field = |name: &str| fields[COLUMNS.iter().position(|c| *c == name).unwrap()].trim();
+
+    let author = field("author");
+    if author.is_empty() {
+        bail!("There's no author");
+    }
+
+    let email = match field("email") {
+        "" => author,
+        email => email,
+    };
+
+    let hash = match field("hash") {
+        "" => format!("{}:{}", source, number),
+        hash => hash.to_string(),
+    };
+
+    let timestamp = field("timestamp");
+    let date = match timestamp.parse::<i64>() {
+        Ok(seconds) => OffsetDateTime::from_unix_timestamp(seconds).ok(),
+        Err(_) => OffsetDateTime::parse(timestamp, &Rfc3339).ok(),
+    }
+    .with_context(|| format!("Can't read the timestamp {:?}", timestamp))?;
+
+    let count = |name: &str| -> anyhow::Result<u32> {
+        match field(name) {
+            "" => Ok(0),
+            count => count
+                .parse()
+                .with_context(|| format!("Can't read {} {:?}", name, count)),
+        }
+    };
+
+    // Which files changed isn't known, only how many
+    Ok(Commit {
+        hash,
+        parents: Vec::new(),
+        refs: Vec::new(),
+        author: Author::new(author.to_string(), email.to_string()),
+        date,
+        committed: None,
+        message: String::new(),
+        files: count("files")?,
+        inserts: count("inserts")?,
+        deletes: count("deletes")?,
+        file_changes: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    #[test]
+    fn parse() {
+        let text = "\
+hash\tauthor\temail\ttimestamp\tfiles\tinserts\tdeletes
+abc\tJon\tjon@email.ca\t1669680000\t2\t10\t3
+
+\tNot Jon\t\t2022-11-28T16:00:00-08:00\t\t\t
+";
+
+        let commits = super::parse(text, "changes.tsv").unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "abc");
+        assert_eq!(commits[0].author.email, "jon@email.ca");
+        assert_eq!(commits[0].date, datetime!(2022-11-29 0:00 UTC));
+        assert_eq!(commits[0].files, 2);
+        assert_eq!(commits[0].inserts, 10);
+        assert_eq!(commits[0].deletes, 3);
+        assert_eq!(commits[1].hash, "changes.tsv:4");
+        assert_eq!(commits[1].author.email, "Not Jon");
+        assert_eq!(commits[1].date, datetime!(2022-11-28 16:00 -8));
+        assert_eq!(commits[1].inserts, 0);
+    }
+
+    #[test]
+    fn invalid() {
+        let short = super::parse("abc\tJon\t\t1669680000\n", "x.tsv").unwrap_err();
+        assert_eq!(
+            format!("{:#}", short),
+            "Line 1 is invalid: Expected 7 columns but there are 4"
+        );
+
+        let bad = super::parse("abc\tJon\t\tyesterday\t\t\t\n", "x.tsv").unwrap_err();
+        assert_eq!(
+            format!("{:#}", bad),
+            "Line 1 is invalid: Can't read the timestamp \"yesterday\""
+        );
+    }
+}
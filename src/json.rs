@@ -0,0 +1,187 @@
+use serde::Serialize;
+
+use crate::report::{Report, Value};
+
+/// The version of the shape of yeesh's JSON output, in every document as
+/// `schema_version`. Within a version fields are only ever added, so readers
+/// should ignore fields they don't know. Removing, renaming, or changing the
+/// type or meaning of a field bumps the version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Reports on their own, e.g. from `yeesh q ... | json`
+#[derive(Serialize)]
+struct Reports<'a> {
+    schema_version: u32,
+    reports: Vec<ReportJson<'a>>,
+}
+
+/// Every report for a repository along with when it was analyzed
+#[derive(Serialize)]
+struct Analysis<'a> {
+    schema_version: u32,
+    repo: &'a str,
+    analyzed_at: &'a str,
+    reports: Vec<ReportJson<'a>>,
+}
+
+/// The repositories a dashboard serves
+#[derive(Serialize)]
+struct Repos<'a> {
+    schema_version: u32,
+    repos: &'a [&'a str],
+}
+
+/// A report's title, columns, and rows. Numbers are kept exact, e.g.
+/// percentages are fractions rather than rounded text.
+#[derive(Serialize)]
+struct ReportJson<'a> {
+    title: &'a str,
+    histogram: bool,
+    shares: bool,
+    columns: &'a [String],
+    rows: Vec<Vec<Cell<'a>>>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Cell<'a> {
+    /// Empty, or a number JSON can't represent like infinity or NaN
+    Null,
+    Text(&'a str),
+    Count(u64),
+    Number(f64),
+    /// Always like `2022-11-28`, whatever `--date-format` says
+    Date(String),
+}
+
+impl<'a> From<&'a Report> for ReportJson<'a> {
+    fn from(report: &'a Report) -> Self {
+        ReportJson {
+            title: &report.title,
+            histogram: report.histogram,
+            shares: report.shares,
+            columns: &report.table.columns,
+            rows: report
+                .table
+                .rows
+                .iter()
+                .map(|row| row.iter().map(Cell::from).collect())
+                .collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a Value> for Cell<'a> {
+    fn from(value: &'a Value) -> Self {
+        match value {
+            Value::Empty => Cell::Null,
+            Value::Text(text) => Cell::Text(text),
+            Value::Count(count) => Cell::Count(*count),
+            Value::Decimal(number) | Value::Ratio(number) | Value::Percent(number)
+                if number.is_finite() =>
+            {
+                Cell::Number(*number)
+            }
+            Value::Decimal(_) | Value::Ratio(_) | Value::Percent(_) => Cell::Null,
+            Value::Date(date) => Cell::Date(date.to_string()),
+        }
+    }
+}
+
+/// Reports as a JSON object with the schema version
+pub fn reports(reports: &[Report]) -> String {
+    to_string(&Reports {
+        schema_version: SCHEMA_VERSION,
+        reports: reports.iter().map(ReportJson::from).collect(),
+    })
+}
+
+/// Every report for a repository along with when it was analyzed
+pub fn analysis(repo: &str, analyzed_at: &str, reports: &[Report]) -> String {
+    to_string(&Analysis {
+        schema_version: SCHEMA_VERSION,
+        repo,
+        analyzed_at,
+        reports: reports.iter().map(ReportJson::from).collect(),
+    })
+}
+
+/// The names of the repositories a dashboard serves
+pub fn repos(names: &[&str]) -> String {
+    to_string(&Repos {
+        schema_version: SCHEMA_VERSION,
+        repos: names,
+    })
+}
+
+/// Serializing these can't fail since every map key is a string
+pub fn to_string(value: &impl Serialize) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+/// A quoted and escaped JSON string
+pub fn string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::{Report, Table, Value};
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(super::string("plain"), r#""plain""#);
+        assert_eq!(super::string("say \"hi\"\n"), r#""say \"hi\"\n""#);
+        assert_eq!(super::string("back\\slash"), r#""back\\slash""#);
+        assert_eq!(super::string("bell\u{7}"), r#""bell\u0007""#);
+    }
+
+    #[test]
+    fn report() {
+        let mut table = Table::new(&["author", "commits", "share", "days"]);
+        table.push(vec![
+            Value::Text("Jon".to_string()),
+            Value::Count(3),
+            Value::Percent(0.25),
+            Value::Empty,
+        ]);
+
+        table.push(vec![
+            Value::Text("Not Jon".to_string()),
+            Value::Count(0),
+            Value::Percent(f64::NAN),
+            Value::Decimal(1.0),
+        ]);
+
+        let json = super::reports(&[Report::new("Authors:", table)]);
+
+        assert_eq!(
+            json,
+            r#"{"schema_version":1,"reports":[{"title":"Authors:","histogram":false,"shares":false,"columns":["author","commits","share","days"],"rows":[["Jon",3,0.25,null],["Not Jon",0,null,1.0]]}]}"#
+        );
+    }
+
+    #[test]
+    fn analysis() {
+        assert_eq!(
+            super::analysis("yeesh", "2022-11-28T16:00:00Z", &[]),
+            r#"{"schema_version":1,"repo":"yeesh","analyzed_at":"2022-11-28T16:00:00Z","reports":[]}"#
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! What yeesh reads histories with, for other crates, e.g. to walk a history
+//! as a graph with `dag` or test against its parser with `test_support`'s logs.
+//! The reports and everything else live in the binary.
+
+pub mod commit;
+pub mod dag;
+pub mod git;
+pub mod parser;
+pub mod range;
+pub mod subprocess;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
@@ -1,53 +1,1528 @@
-mod commit;
+mod anonymize;
+mod ascii;
+mod bar;
+mod cache;
+mod capability;
+mod config;
+mod date;
+mod export;
+mod feed;
+mod graph;
+mod hg;
 mod histogram;
-mod parser;
+mod history;
+mod hook;
+mod html;
+mod http;
+mod import;
+mod json;
+mod markdown;
+mod memo;
+mod mermaid;
+mod number;
+mod org;
+mod plugin;
+mod provider;
+mod query;
+mod report;
+mod serve;
+mod slack;
+#[cfg(test)]
+mod snapshot;
+mod stats;
+mod svn;
+mod table;
+mod tally;
+mod terminal;
+mod vcs;
+mod workspace;
 
-use std::process::{self, Command};
-use std::str;
+// The library's modules, at the same paths as the binary's own
+#[cfg(test)]
+use yeesh::test_support;
+use yeesh::{commit, dag, git, parser, range, subprocess};
 
-use crate::commit::Commit;
-use crate::histogram::Kind;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::slice;
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::anonymize::Pseudonyms;
+use crate::bar::Bars;
+use crate::capability::Capabilities;
+use crate::commit::{Alias, Author, Commit, CommitRef, Order};
+use crate::config::Config;
+use crate::dag::Dag;
+use crate::date::DateFormat;
+use crate::git::{Diffstat, Tag};
+use crate::histogram::{Weight, WEEKDAYS};
+use crate::number::Numbers;
+use crate::provider::github::GitHub;
+use crate::provider::gitlab::GitLab;
+use crate::provider::Provider;
+use crate::query::Query;
+use crate::range::Period;
+use crate::report::changes::Snapshot;
+use crate::report::group::{Bucket, GroupBy};
+use crate::report::Report;
+use crate::report::{
+    activity, anomaly, author, biggest, branches, changes, churn_tree, compare, conventional,
+    extension_hours, file_age, gitmoji, group, half_life, handoff, header, hygiene, issue, lag,
+    list, merge_lead, newcomer, ownership, peak, pipeline, pull_request, release, repository,
+    retention, revert, rolling, signoff, size, test_ratio, timezone, words,
+};
+use crate::table::Layout;
+use crate::tally::Tally;
+use crate::vcs::Vcs;
+use crate::workspace::Workspace;
+
+use anyhow::Context;
+use regex::Regex;
 use termion::{color, style};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime, UtcOffset};
 
 const HELP: &str = "\
 yeesh: simple stats for git repositories
 
 USAGE:
-  yeesh [-h] [--hours] [--days]
+  yeesh [-h] [--group-by <group>]... [--show-bucket <group>=<bucket>]...
+        [--hours] [--days] [--offsets] [--team-offset <offset>]
+        [--weight <weight>] [--sizes]
+        [--anomalies [--sigma <n>]] [--peak] [--hygiene] [--reverts]
+        [--releases] [--authors] [--retention] [--new-contributors] [--handoffs]
+        [--lag] [--signoffs] [--scopes] [--extension-hours]
+        [--issues [--issue-pattern <regex>]] [--file-ages] [--merge-lead-time]
+        [--gitmoji] [--rolling [--weeks <n>]] [--churn-tree [--tree-threshold <percent>]]
+        [--test-ratio [--test-pattern <regex>]...] [--words [--top-words <n>]]
+        [--plugin <program>]...
+        [--github | --gitlab [--token <token>] [--pr-limit <n>]]
+        [--split-by-dir <depth> | --split-by <period>] [--vcs <vcs>] [--import <file>]... [--input-format <format>]
+        [--post <url>]
+        [--format <format>] [--numbers <style>] [--date-format <format>]
+        [--strict-encoding] [--first-parent] [--timeout <seconds>]
+        [--deterministic] [--header] [--low-memory] [--sort <order>]
+        [--cache-reports] [--chunk-days <n>] [--partial] [--since <date>]
+        [--until <date>] [--today | --this-week | --this-month] [--vs-previous]
+        [--config <path>] [--alias <from=to>]... [--by-team]
+        [--author <text>]... [--anonymize | --redact-emails]
+        [--workspace <path> [--label <label>] | --discover <dir>]
+        [--hide-empty | --show-empty] [--bar-scale <n>] [--max-width <cols>]
+        [--borders] [--ascii]
+  yeesh biggest [<n>]
+  yeesh branches [--base <branch>] [--deterministic]
+  yeesh blame-ownership [--sample <n>] [--format <format>]
+  yeesh changes
+  yeesh list [--author <text>]... [--hour <h>]... [--weekday <day>]...
+  yeesh q <query>
+  yeesh feed --out <path>
+  yeesh graph --dot
+  yeesh half-life [--sample <n>] [--deterministic]
+  yeesh hook install | update
+  yeesh compare --repo <path> --repo <path>... [--format <format>]
+                [--alias <from=to>]... [--author <text>]...
+  yeesh export [--sqlite <path>] [--parquet <path>] [--json <path>]
+               [--jsonl <path>] [--cube-csv <path>] [--cube-json <path>]
+               [--import <file>]...
+  yeesh serve [--repo <path>]... [--host <host>] [--port <n>] [--refresh <s>]
+              [--alias <from=to>]... [--author <text>]...
+
+COMMANDS:
+  biggest       Prints the n commits changing the most lines, defaults to 10,
+                e.g. to find vendored code bloating the history
+  branches      Prints each branch's latest commit, how many days ago it was,
+                who made it, and commits ahead of and behind the base, stalest
+                first, e.g. to find abandoned branches
+  blame-ownership
+                Prints whose lines make up the code now, overall and per
+                directory, using git blame, which is slow on large repositories
+  changes       Prints what changed since the last time it was run, e.g. from
+                cron, remembering each run in the cache directory
+  feed          Writes an Atom feed with an entry per week of commits and
+                contributors to follow from a feed reader
+  graph         Prints a graph of authors joined by the files they both changed
+                and commits they co-authored
+  half-life     Prints the median age of surviving lines per directory using
+                git blame, which is slow on large repositories
+  compare       Prints headline metrics and activity of repositories side by
+                side, per contributor so teams of different sizes compare
+  list          Prints each commit with its date, author, subject, and lines
+                changed, e.g. to see the ones behind a busy hour in --hours
+  export        Writes commits, file changes, and authors to a file for use
+                in other tools
+  hook install  Installs git hooks keeping a cache of the history up to date
+                so reports needn't read all of it every time
+  hook update   Adds new commits to the cache, which the hooks run
+  q             Prints reports composed from stages separated by |, e.g.
+                'since 2024 | author alice | group-by hour | top 5 | json'.
+                Stages are since <date>, until <date>, author <name or
+                email>, group-by <group>, top <n>, and a format, one of
+                terminal, slack, mermaid, markdown, org, or json
+  serve         Hosts an HTML dashboard and JSON API of every report
 
 ARGS:
   -h, --help    Prints this message
-  --hours       (Optional) prints commit stats by hour of day
-  --days        (Optional) prints commit stats by weekday
+  --group-by <group>
+                (Optional) prints commits by hour, weekday, month, author,
+                domain of their email, extension of the files changed, or tag
+                of the release they went out in, may be repeated
+  --show-bucket <group>=<bucket>
+                (Optional) prints the commits in a group of --group-by, e.g.
+                hour=23, weekday=sat, month=2022-11, or author=jon, may be
+                repeated
+  --hours       (Optional) prints commit stats by hour of day, like --group-by
+                hour
+  --days        (Optional) prints commit stats by weekday, like --group-by
+                weekday
+  --offsets     (Optional) prints commits by the UTC offset they were made in
+  --team-offset <offset>
+                (Optional) prints commits by hour in their own offsets and in
+                the team's, e.g. -08:00, with how far from it they're made and
+                how many are in working hours either way, to see how spread
+                out the team is
+  --weight <weight>
+                (Optional) what --hours, --days, --peak, and
+                --extension-hours total, one of count, lines, or files
+                changed, defaults to count
+  --sizes       (Optional) prints percentiles of lines changed per commit
+  --anomalies   (Optional) prints weeks with unusually many or few commits
+  --sigma <n>   (Optional) standard deviations from the trailing average for
+                a week to be unusual, defaults to 2
+  --peak        (Optional) prints the hours and weekdays with the most commits
+  --hygiene     (Optional) prints a scorecard of commit message conventions
+  --reverts     (Optional) prints how often commits are reverted or fixed up,
+                whose commits and which files are reverted most, and how long
+                commits last before they're reverted
+  --releases    (Optional) prints time, commits, and contributors between tags
+  --authors     (Optional) prints authors by commits and how evenly work is
+                spread across them
+  --retention   (Optional) prints how many authors whose first commit was in
+                each month still commit 3, 6, and 12 months later
+  --new-contributors
+                (Optional) prints authors whose first commit ever is since
+                --since, with their commits since, e.g. to thank in release
+                notes. Not with --workspace or --discover
+  --handoffs    (Optional) prints directories whose owner, the author changing
+                the most lines, is someone else since --since than in the
+                period as long just before, e.g. to check knowledge was passed
+                on. Not with --workspace or --discover
+  --lag         (Optional) prints how long commits took to land after they
+                were authored, e.g. waiting on review or a rebase
+  --signoffs    (Optional) prints how many commits are signed off by their
+                authors, as the Developer Certificate of Origin asks, and who
+                signs off the most
+  --issues      (Optional) prints how many commits reference an issue, e.g.
+                #12 or ABC-12, and which issues have the most commits
+  --issue-pattern <regex>
+                (Optional) finds issue references with the regex rather than
+                the default, or pattern in the config's [issues]. The first
+                group that matches is the issue, or else the whole match
+  --file-ages   (Optional) prints the files untouched the longest and the
+                deleted files that lived the shortest time. Not with
+                --import, --workspace, or --discover
+  --merge-lead-time
+                (Optional) prints how long merged branches took to land from
+                their first and last commits, roughly the time pull requests
+                take. Not with --import, --workspace, or --discover
+  --gitmoji     (Optional) prints how many commit subjects start with an emoji
+                or gitmoji code, e.g. :bug:, and which are used the most
+  --scopes      (Optional) prints commits by Conventional Commits type, e.g.
+                fix, and activity by scope, e.g. parser in fix(parser): ...
+  --words       (Optional) prints the words in the most commit subjects,
+                leaving out common ones like the and to
+  --top-words <n>
+                (Optional) words --words prints, defaults to 20
+  --rolling     (Optional) charts commits in the 7 days up to each day of the
+                weeks before the latest commit
+  --weeks <n>   (Optional) weeks --rolling charts, defaults to 12
+  --churn-tree  (Optional) prints lines changed per directory as a tree with
+                bars and each directory's share
+  --tree-threshold <percent>
+                (Optional) share of lines changed a directory needs to be
+                shown in the tree rather than counted in a (n more) row,
+                defaults to 2
+  --extension-hours
+                (Optional) prints when in the day files of each extension are
+                changed, by --weight, as shares of the night (00 to 06),
+                morning, afternoon, and evening (18 to 24), and the busiest
+                hour, e.g. docs in the morning and CI config at night
+  --test-ratio  (Optional) prints lines changed in tests against lines changed
+                in other files, overall and by author
+  --test-pattern <regex>
+                (Optional) files whose paths match are tests, may be repeated,
+                in place of the default or every pattern in the config's
+                [tests], e.g. ^checks/
+  --plugin <program>
+                (Optional) runs the program with every commit on its stdin as
+                JSON lines, like export --jsonl, and prints the reports it
+                writes to stdout as JSON, either the JSON --post sends or a
+                single report with a title, columns, and rows, may be
+                repeated
+  --split-by-dir <depth>
+                (Optional) prints reports for each directory that deep, e.g.
+                each project of a monorepo, followed by all of them together
+  --split-by <period>
+                (Optional) prints reports for each month, oldest first,
+                followed by all of them together, e.g. with --hours to see
+                whether late nights are new. Period is month
+  --github      (Optional) prints pull request merge and review times from
+                GitHub for the repository's origin remote
+  --gitlab      (Optional) prints merge request merge and review times, and
+                pipeline results per commit, from GitLab for the repository's
+                origin remote
+  --token <token>
+                (Optional) API token for --github or --gitlab, defaults to
+                $GITHUB_TOKEN or $GITLAB_TOKEN
+  --pr-limit <n>
+                (Optional) most recent pull requests, and pipelines, to fetch,
+                defaults to 100
+  --import <file>
+                (Optional) JSON or JSON lines file from export to read commits
+                from instead of git, may be repeated to combine several
+  --vcs <vcs>   (Optional) git, hg, or svn for a working copy, which the
+                repository is otherwise detected as. Only git's can be
+                blamed, hooked, cached, or read in chunks, and svn records
+                which files changed but not how many lines
+  --input-format <format>
+                (Optional) json (the default) for files from export, or csv
+                with a header naming the columns date, author, and optionally
+                email, hash, message, inserts, deletes, and files separated
+                by semicolons, tsv with the columns hash, author, email,
+                timestamp in seconds or RFC 3339, files, inserts, and
+                deletes in that order, or git for saved output of git log
+                --stat with any of its rfc, iso, iso-strict, unix, or
+                default dates
+  --post <url>  (Optional) POSTs every report as JSON to a URL, the same JSON
+                serve's API responds with
+  --format <format>
+                (Optional) how reports are printed, one of terminal (the
+                default), slack for a Block Kit message, mermaid for charts
+                to embed in markdown, markdown, org for org-mode tables, or
+                html for a page with its styles built in, to view offline
+  --out <path>  (Optional) file feed writes to
+  --numbers <style>
+                (Optional) how numbers are written by every format but
+                mermaid, one of grouped (the default) with the locale's
+                thousands separator, short for e.g. 1.3M, or plain
+  --date-format <format>
+                (Optional) how dates are written by every format but JSON,
+                e.g. '[day].[month].[year]' or '[month repr:short] [day]',
+                defaults to '[year]-[month]-[day]'. The components are
+                described at
+                https://time-rs.github.io/book/api/format-description.html
+  --strict-encoding
+                (Optional) fails on git output that isn't valid UTF-8 rather
+                than replacing the invalid bytes, e.g. of a latin-1 name
+  --deterministic
+                (Optional) measures time from the latest commit rather than
+                now, e.g. half-life's ages and --post's analyzed_at, so output
+                only changes when the history does. Rows are always in a
+                stable order, ties broken by name or hash
+  --header      (Optional) prints the repository, branch, dates, and number of
+                commits the reports cover before them
+  --low-memory  (Optional) tallies --hours, --days, and --sizes as git writes
+                the history, keeping only totals, for histories too large to
+                hold whole. Commits can be filtered with --since, --until, and
+                --author. Not with any other report or with options needing
+                every commit at once, e.g. --alias or --vs-previous
+  --first-parent
+                (Optional) counts only the first commit and its first parents,
+                like git log --first-parent, leaving out commits merged in
+                from other branches. Histories without parents, e.g. svn's or
+                CSV imports, end at the first commit. Not with --workspace or
+                --discover
+  --sort <order>
+                (Optional) sorts commits by date before any report, newest
+                or oldest first, e.g. for imports or hand-edited logs not in
+                git's order
+  --cache-reports
+                (Optional) remembers the output and prints it again straight
+                away for the same arguments until HEAD, the branch, or any
+                branch or tag changes, e.g. for a shell prompt. The outputs
+                of the 16 latest sets of arguments are kept. Not for
+                imports, --vcs hg or svn, --github, --gitlab, --post, or
+                subcommands
+  --timeout <seconds>
+                (Optional) stops git, hg, or svn reading the history if it
+                takes longer, e.g. hung on a network mount, rather than
+                waiting forever. Otherwise yeesh says what it's waiting on
+                after a few seconds, and Ctrl-C stops both
+  --chunk-days <n>
+                (Optional) reads the history from git n days at a time from
+                the newest, saying how far back it's read after each, e.g.
+                for huge histories or slow disks
+  --partial     (Optional) with --chunk-days, prints the reports for the
+                commits read so far after each chunk
+  --since <date>
+                (Optional) only reports on commits made since the date, e.g.
+                2022-11-28, yesterday, last friday, or 2 weeks ago. Days start
+                at midnight in the local offset. Commits are filtered by the
+                dates read, so imports are too
+  --until <date>
+                (Optional) only reports on commits made up to the date, in
+                the same formats as --since. A date without a time includes
+                that whole day, e.g. 2023-06-30 includes June 30th
+  --today       (Optional) only reports on commits made since midnight, in
+                place of --since and --until
+  --this-week   (Optional) likewise since Monday
+  --this-month  (Optional) likewise since the first of the month
+  --vs-previous (Optional) marks each histogram row on the terminal with the
+                change from the period as long just before --since, e.g.
+                last week given --this-week. Not with --split-by-dir or
+                --split-by
+  --config <path>
+                (Optional) config file to read, defaults to
+                $XDG_CONFIG_HOME/yeesh/config or ~/.config/yeesh/config.
+                Sections like [teams] hold lines like jon@email.ca = Platform.
+                Lines like jon@email.ca = -08:00 in [timezones] move that
+                author's commits recorded in UTC to their own offset, for
+                reports by time of day
+  --alias <from=to>
+                (Optional) counts the author named or emailed from as the one
+                named or emailed to, e.g. 'Jon Doe=Jonathan Neufeld', on top
+                of .mailmap, may be repeated
+  --by-team     (Optional) groups authors by their team from the config's
+                [teams] in every report and export by author, and everyone
+                else as (no team)
+  --anonymize   (Optional) replaces authors and co-authors with pseudonyms
+                like Author A in every report and export, and in messages
+                wherever they're named or emailed, lettered in the order they
+                first appear in the whole history so they're the same for any
+                --since or --author, so stats can be shared without naming
+                anyone
+  --redact-emails
+                (Optional) replaces authors' emails with hashes of them in
+                every report and export, keeping their names
+  --workspace <path>
+                (Optional) reports on every repository listed in a workspace
+                file, e.g. yeesh.workspace.toml, then on all of them together.
+                It's TOML with a [[repo]] table for each repository giving a
+                path or url, and optionally a name, labels, since, and until.
+                A [defaults] table gives any of them for every repository.
+                Only strings and arrays of them are read, each on one line
+  --label <label>
+                (Optional) only reports on the workspace's repositories with
+                the label
+  --discover <dir>
+                (Optional) finds every git repository in the directory or
+                below it and reports on all of them together, with a row for
+                each, e.g. --discover ~/src for everything you work on
+  --hide-empty  (Optional) leaves out histogram rows without commits, e.g.
+                hours nobody worked
+  --show-empty  (Optional) fills in histogram rows without commits, e.g. UTC
+                offsets between those seen
+  --bar-scale <n>
+                (Optional) draws histogram bars a character per n commits, in
+                place of one per commit on the terminal or fitting the longest
+                to 20 characters in Slack
+  --max-width <cols>
+                (Optional) shrinks histogram bars so no line with one is wider
+                than cols, e.g. 72 for a commit message, and cuts long text in
+                tables short to fit. Tables fit the terminal when not given
+  --borders     (Optional) draws lines around and between the columns of
+                tables on the terminal
+  --ascii       (Optional) prints only ASCII, without color, box drawing, or
+                unicode bars, and with other characters spelled in ASCII or
+                as ?, e.g. for screen readers, old terminals, or email.
+                Color is left out anyway when printing to a file or another
+                program or on a dumb terminal, or when NO_COLOR is set
+  --dot         (Optional) prints graph in Graphviz's DOT language
+  --base <branch>
+                (Optional) branch branches compares with, defaults to where
+                origin's HEAD points, or main or master
+  --sample <n>  (Optional) number of files half-life and blame-ownership
+                blame, defaults to 200
+  --author <text>
+                (Optional) only reports on commits by an author whose name or
+                email contains the text, ignoring case, may be repeated for
+                commits by any of them. Matched once --alias, --by-team, and
+                the config have renamed authors, so an aliased author's
+                commits under every identity are kept
+  --hour <h>    (Optional) commits list prints are made in the hour of the
+                day, from 0 to 23, like --hours counts, may be repeated
+  --weekday <day>
+                (Optional) commits list prints are made on the weekday, e.g.
+                sat or saturday, may be repeated
+  --sqlite <path>
+                (Optional) SQLite database export writes to, replacing any
+                tables a previous export left
+  --parquet <path>
+                (Optional) Parquet file export writes to, one row per commit
+  --json <path> (Optional) JSON file export writes to, a list of commits
+  --jsonl <path>
+                (Optional) JSON lines file export writes to, a commit per line
+  --cube-csv <path>
+                (Optional) CSV file export writes commits to by author,
+                weekday, and hour, a row for each with any commits
+  --cube-json <path>
+                (Optional) JSON file export writes the same to, each author
+                with a list of 24 hourly counts for each weekday from Monday
+  --repo <path> (Optional) repository to serve or compare, may be repeated,
+                defaults to the current directory
+  --host <host> (Optional) address to serve on, defaults to 127.0.0.1
+  --port <n>    (Optional) port to serve on, defaults to 7070
+  --refresh <s> (Optional) seconds between analyzing served repositories in
+                the background, otherwise they're analyzed on every request
 ";
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Subcommand {
+    Biggest,
+    BlameOwnership,
+    Branches,
+    Changes,
+    Compare,
+    HalfLife,
+    HookInstall,
+    HookUpdate,
+    List,
+    Query,
+    Export,
+    Feed,
+    Graph,
+    Serve,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Format {
+    Terminal,
+    Slack,
+    Mermaid,
+    Markdown,
+    Org,
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Format, String> {
+        match format {
+            "terminal" => Ok(Format::Terminal),
+            "slack" => Ok(Format::Slack),
+            "mermaid" => Ok(Format::Mermaid),
+            "markdown" => Ok(Format::Markdown),
+            "org" => Ok(Format::Org),
+            "html" => Ok(Format::Html),
+            _ => Err(format!("Unknown format {}", format)),
+        }
+    }
+}
+
+/// Periods `--split-by` prints reports for each of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SplitBy {
+    Month,
+}
+
+impl FromStr for SplitBy {
+    type Err = String;
+
+    fn from_str(period: &str) -> Result<SplitBy, String> {
+        match period {
+            "month" => Ok(SplitBy::Month),
+            _ => Err(format!("Unknown period {}", period)),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct CliArgs {
+    subcommand: Option<Subcommand>,
+    group_by: Vec<GroupBy>,
+    show_buckets: Vec<Bucket>,
     hours: bool,
     days: bool,
+    offsets: bool,
+    weight: Weight,
+    team_offset: Option<UtcOffset>,
+    sizes: bool,
+    anomalies: bool,
+    sigma: f64,
+    peak: bool,
+    hygiene: bool,
+    reverts: bool,
+    releases: bool,
+    lag: bool,
+    signoffs: bool,
+    issues: bool,
+    issue_pattern: Option<Regex>,
+    file_ages: bool,
+    merge_lead_time: bool,
+    gitmoji: bool,
+    scopes: bool,
+    words: bool,
+    top_words: usize,
+    rolling: bool,
+    weeks: usize,
+    churn_tree: bool,
+    extension_hours: bool,
+    tree_threshold: f64,
+    test_ratio: bool,
+    test_patterns: Vec<Regex>,
+    plugins: Vec<PathBuf>,
+    authors: bool,
+    retention: bool,
+    new_contributors: bool,
+    handoffs: bool,
+    split_by_dir: Option<usize>,
+    split_by: Option<SplitBy>,
+    github: bool,
+    gitlab: bool,
+    token: Option<String>,
+    pr_limit: usize,
+    out: Option<PathBuf>,
+    dot: bool,
+    sample: usize,
+    biggest: usize,
+    base: Option<String>,
+    only_authors: Vec<String>,
+    list: list::Filter,
+    query: Option<String>,
+    sqlite: Option<PathBuf>,
+    parquet: Option<PathBuf>,
+    json: Option<PathBuf>,
+    jsonl: Option<PathBuf>,
+    cube_csv: Option<PathBuf>,
+    cube_json: Option<PathBuf>,
+    vcs: Vcs,
+    imports: Vec<PathBuf>,
+    input_format: import::Format,
+    post: Option<String>,
+    format: Format,
+    numbers: Numbers,
+    date_format: DateFormat,
+    strict_encoding: bool,
+    deterministic: bool,
+    header: bool,
+    low_memory: bool,
+    first_parent: bool,
+    sort: Option<Order>,
+    cache_reports: bool,
+    timeout: Option<Duration>,
+    chunk_days: Option<u64>,
+    partial: bool,
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+    vs_previous: bool,
+    config: Option<PathBuf>,
+    aliases: Vec<Alias>,
+    by_team: bool,
+    anonymize: bool,
+    redact_emails: bool,
+    workspace: Option<PathBuf>,
+    label: Option<String>,
+    discover: Option<PathBuf>,
+    hide_empty: bool,
+    show_empty: bool,
+    bars: Bars,
+    layout: Layout,
+    capabilities: Capabilities,
+    repos: Vec<PathBuf>,
+    host: String,
+    port: u16,
+    refresh: Option<u64>,
+}
+
+impl CliArgs {
+    fn has_report(&self) -> bool {
+        self.has_date_report() || self.has_other_report()
+    }
+
+    /// Reports needing only when commits were made
+    fn has_date_report(&self) -> bool {
+        self.hours || self.days || self.offsets || self.team_offset.is_some()
+    }
+
+    fn has_other_report(&self) -> bool {
+        self.sizes || self.has_untallied_report()
+    }
+
+    /// Reports needing more than `Tally` keeps, so not for `--low-memory`
+    fn has_untallied_report(&self) -> bool {
+        self.subcommand.is_some()
+            || self.anomalies
+            || self.peak
+            || self.hygiene
+            || self.reverts
+            || self.releases
+            || self.lag
+            || self.signoffs
+            || self.issues
+            || self.file_ages
+            || self.merge_lead_time
+            || self.gitmoji
+            || self.scopes
+            || self.words
+            || self.rolling
+            || self.churn_tree
+            || self.extension_hours
+            || self.test_ratio
+            || !self.plugins.is_empty()
+            || !self.group_by.is_empty()
+            || !self.show_buckets.is_empty()
+            || self.authors
+            || self.retention
+            || self.new_contributors
+            || self.handoffs
+            || self.github
+            || self.gitlab
+            || self.post.is_some()
+    }
 }
 
 fn main() {
-    let args = args_or_quit();
+    let mut args = args_or_quit();
+
+    subprocess::set_timeout(args.timeout);
+    subprocess::stop_on_interrupt();
+
+    if args.subcommand == Some(Subcommand::HalfLife) {
+        let report = or_quit(half_life(&args));
+        print_output(&args, &terminal::render(&report, args.numbers, args.layout));
+        return;
+    }
+
+    if args.subcommand == Some(Subcommand::BlameOwnership) {
+        print_reports(&args, &or_quit(blame_ownership(args.sample)));
+        return;
+    }
+
+    if args.subcommand == Some(Subcommand::Branches) {
+        print_reports(&args, &[or_quit(branches(&args))]);
+        return;
+    }
+
+    if args.subcommand == Some(Subcommand::HookInstall) {
+        for path in or_quit(hook::install(Path::new("."))) {
+            println!("Installed {}", path.display());
+        }
+        return;
+    }
+
+    if args.subcommand == Some(Subcommand::HookUpdate) {
+        or_quit(history::update(Path::new("."), args.strict_encoding));
+        return;
+    }
+
+    if args.subcommand == Some(Subcommand::Compare) {
+        let reports = or_quit(compare(&args));
+        print_reports(&args, &reports);
+        return;
+    }
+
+    if args.subcommand == Some(Subcommand::Serve) {
+        serve(&args);
+        return;
+    }
+
+    let config = or_quit(config::load(args.config.as_deref()));
+
+    if args.issue_pattern.is_none() {
+        args.issue_pattern = or_quit(config.issue_pattern());
+    }
+
+    if args.test_patterns.is_empty() {
+        args.test_patterns = or_quit(config.test_patterns());
+    }
+
+    let workspace = match (&args.workspace, &args.discover) {
+        (Some(path), None) => Some(or_quit(workspace::load(path, range::now()))),
+        (None, Some(dir)) => Some(or_quit(workspace::discover(dir))),
+        (None, None) => None,
+        (Some(_), Some(_)) => print_help_and_quit(),
+    };
+
+    if let Some(workspace) = workspace {
+        if args.subcommand.is_some() {
+            print_help_and_quit();
+        }
+
+        print_output(&args, &render_workspace(&args, &config, &workspace));
+        return;
+    }
+
+    if args.low_memory {
+        print_output(&args, &or_quit(render_tally(&args, &config)));
+        return;
+    }
+
+    if let Some(key) = memo_key(&args, &config) {
+        // A cache that can't be read is only a miss
+        match memo::read(Path::new("."), &key) {
+            Ok(Some(output)) => {
+                print_output(&args, &output);
+                return;
+            }
+            Ok(None) => {}
+            Err(error) => eprintln!("yeesh: Can't read the cached reports: {:#}", error),
+        }
+    }
+
+    let read = if !args.imports.is_empty() {
+        import::read(&args.imports, args.input_format)
+    } else if args.vcs != Vcs::Git {
+        // Only git's history is cached or can be read in chunks or by dates
+        args.vcs
+            .commits(Path::new("."), diffstat(&args), args.strict_encoding)
+    } else if let Some(days) = args.chunk_days {
+        let progress = |commits: &[Commit], more: bool| {
+            if let Some(oldest) = commits.last() {
+                eprintln!(
+                    "Read {} commits back to {}",
+                    commits.len(),
+                    oldest.date.date()
+                );
+            }
+
+            if args.partial && more {
+                print_output(&args, &render(&args, commits, &[], &[]));
+            }
+        };
+
+        history::commits_in_windows(
+            Path::new("."),
+            days,
+            diffstat(&args),
+            args.strict_encoding,
+            progress,
+        )
+    } else if dates_only(&args, &config) {
+        history::dates(Path::new("."))
+    } else {
+        args.vcs
+            .commits(Path::new("."), diffstat(&args), args.strict_encoding)
+    };
+
+    let mut commits = or_quit(read);
+
+    // Walked from the newest commit in git's order, before any other
+    if args.first_parent {
+        commits = Dag::new(&commits)
+            .first_parents()
+            .into_iter()
+            .cloned()
+            .collect();
+    }
+
+    if let Some(order) = args.sort {
+        commit::sort(&mut commits, order);
+    }
+
+    // Before filtering so an author is renamed the same in every period
+    rename_authors(&args, &config, &mut commits);
+    let hidden = Hidden::of(&args, &commits);
+
+    commit::retain_authors(&mut commits, &args.only_authors);
+
+    let mut previous = previous_period(&args, &commits);
+    let mut earlier = earlier(&args, &commits);
+    commit::retain_between(&mut commits, args.since, args.until);
+
+    for commits in [&mut commits, &mut previous, &mut earlier] {
+        hidden.apply(commits);
+    }
+
+    if args.subcommand == Some(Subcommand::Query) {
+        let text = args
+            .query
+            .as_deref()
+            .unwrap_or_else(|| print_help_and_quit());
+        print_output(&args, &or_quit(query(&args, text, &commits)));
+        return;
+    }
+
+    if args.subcommand == Some(Subcommand::Export) {
+        export(&args, &commits);
+        return;
+    }
+
+    if args.subcommand == Some(Subcommand::Feed) {
+        let path = args.out.as_ref().unwrap_or_else(|| print_help_and_quit());
+        let atom = feed::atom(&repo_name(), &commits);
+        or_quit(fs::write(path, atom).with_context(|| format!("Can't write {}", path.display())));
+        return;
+    }
+
+    if args.subcommand == Some(Subcommand::Graph) {
+        if !args.dot {
+            print_help_and_quit();
+        }
+
+        print_output(&args, &graph::dot(&graph::collaboration(&commits)));
+        return;
+    }
+
+    let output = render(&args, &commits, &previous, &earlier);
+    print_output(&args, &output);
+
+    if let Some(key) = memo_key(&args, &config) {
+        if let Err(error) = memo::write(Path::new("."), &key, &output) {
+            eprintln!("yeesh: Can't cache the reports: {:#}", error);
+        }
+    }
+
+    if let Some(url) = &args.post {
+        or_quit(post(url, &commits, args.deterministic));
+    }
+}
+
+/// The commits in the period as long as `--since` to `--until`, or now, just
+/// before it when comparing with it or finding handoffs
+fn previous_period(args: &CliArgs, commits: &[Commit]) -> Vec<Commit> {
+    let since = match args.since {
+        Some(since) if args.vs_previous || args.handoffs => since,
+        _ => return Vec::new(),
+    };
+
+    let length = args.until.unwrap_or_else(range::now) - since;
+
+    let mut previous = commits.to_vec();
+    commit::retain_between(&mut previous, Some(since - length), Some(since));
+    previous
+}
+
+/// Every commit before `--since`, when new contributors are asked for, to tell
+/// whether an author committed before
+fn earlier(args: &CliArgs, commits: &[Commit]) -> Vec<Commit> {
+    match args.since {
+        Some(since) if args.new_contributors => commits
+            .iter()
+            .filter(|commit| commit.date < since)
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Commits moved to their authors' timezones while their emails are as
+/// committed, then aliased authors merged, then teams in place of authors, as
+/// asked for
+fn rename_authors(args: &CliArgs, config: &Config, commits: &mut [Commit]) {
+    commit::into_timezones(commits, &config.timezones());
+    commit::alias(commits, &args.aliases);
+
+    if args.by_team {
+        commit::into_teams(commits, &config.teams());
+    }
+}
+
+/// Who's hidden from reports and exports, as asked for
+enum Hidden {
+    Pseudonyms(Pseudonyms),
+    Emails,
+    None,
+}
+
+impl Hidden {
+    /// From every commit, before any are filtered out, so people are hidden
+    /// the same way whichever are shown
+    fn of(args: &CliArgs, commits: &[Commit]) -> Hidden {
+        if args.anonymize {
+            Hidden::Pseudonyms(Pseudonyms::of(commits))
+        } else if args.redact_emails {
+            Hidden::Emails
+        } else {
+            Hidden::None
+        }
+    }
+
+    fn apply(&self, commits: &mut [Commit]) {
+        match self {
+            Hidden::Pseudonyms(pseudonyms) => pseudonyms.apply(commits),
+            Hidden::Emails => anonymize::emails(commits),
+            Hidden::None => {}
+        }
+    }
+}
+
+/// A row for each repository in the workspace and the reports asked for on
+/// each of them, then on all of them together. Discovered repositories only
+/// get a row since there may be a great many. Each repository's own filters
+/// apply unless `--since` or `--until` are given.
+fn render_workspace(args: &CliArgs, config: &Config, workspace: &Workspace) -> String {
+    let repos: Vec<&workspace::Repo> = workspace
+        .repos
+        .iter()
+        .filter(|repo| {
+            args.label
+                .as_ref()
+                .is_none_or(|label| repo.labels.contains(label))
+        })
+        .collect();
 
-    let logs = get_git_logs();
-    let commits = parser::parse(&logs);
-    let commits = commits.unwrap();
+    let mut all = Vec::new();
+    let mut lengths = Vec::new();
+
+    for repo in &repos {
+        let path = or_quit(repo.local_path());
+        let mut history = or_quit(history::commits(
+            &path,
+            diffstat(args),
+            args.strict_encoding,
+        ));
+
+        if let Some(order) = args.sort {
+            commit::sort(&mut history, order);
+        }
+
+        lengths.push(history.len());
+        all.extend(history);
+    }
+
+    // Across every repository, before filtering, so the same author is renamed
+    // and hidden the same in each
+    rename_authors(args, config, &mut all);
+    let hidden = Hidden::of(args, &all);
+
+    let mut all = all.into_iter();
+    let mut commits = Vec::new();
+    let mut parts = Vec::new();
+
+    for (repo, length) in repos.iter().zip(lengths) {
+        let mut history: Vec<Commit> = all.by_ref().take(length).collect();
+        let since = args.since.or(repo.since);
+        let until = args.until.or(repo.until);
+        commit::retain_between(&mut history, since, until);
+        commit::retain_authors(&mut history, &args.only_authors);
+        hidden.apply(&mut history);
+
+        parts.push((
+            repo.name.as_str(),
+            commits.len()..commits.len() + history.len(),
+        ));
+        commits.extend(history);
+    }
+
+    let mut out = String::new();
+    let mut reports = Vec::new();
+
+    if args.header {
+        let names: Vec<&str> = repos.iter().map(|repo| repo.name.as_str()).collect();
+        let header = or_quit(workspace_header(args, &names, &commits));
+
+        if args.format == Format::Terminal {
+            out.push_str(&render_reports(args, &[header]));
+        } else {
+            reports.push(header);
+        }
+    }
+
+    let named: Vec<(&str, &[Commit])> = parts
+        .iter()
+        .map(|(name, part)| (*name, &commits[part.clone()]))
+        .collect();
+
+    reports.push(repository::report(&named));
+
+    if args.discover.is_none() {
+        for (name, commits) in named {
+            let tags = commit::tags(commits);
+            let split = requested_reports(args, commits, &tags, None);
+            reports.extend(split.into_iter().map(|report| retitle(report, name)));
+        }
+    }
+
+    commit::sort(&mut commits, args.sort.unwrap_or(Order::Newest));
+
+    let tags = commit::tags(&commits);
+    let combined = requested_reports(args, &commits, &tags, None);
+    reports.extend(combined.into_iter().map(|report| retitle(report, "all")));
+
+    out.push_str(&render_reports(args, &reports));
+    out
+}
+
+/// Everything printed for the reports asked for
+fn render(args: &CliArgs, commits: &[Commit], previous: &[Commit], earlier: &[Commit]) -> String {
+    let mut out = String::new();
+    let mut reports = Vec::new();
+
+    // Rendered straight away on the terminal so it comes before the original
+    // histograms, which are too
+    if args.header {
+        let header = or_quit(header(args, commits));
+
+        if args.format == Format::Terminal {
+            out.push_str(&render_reports(args, &[header]));
+        } else {
+            reports.push(header);
+        }
+    }
+
+    if args.subcommand == Some(Subcommand::Changes) {
+        reports.push(or_quit(changes(commits)));
+    }
+
+    if args.subcommand == Some(Subcommand::Biggest) {
+        reports.push(biggest::report(commits, args.biggest));
+    }
+
+    if args.subcommand == Some(Subcommand::List) {
+        reports.push(list::report(commits, &args.list));
+    }
+
+    let tags = commit::tags(commits);
+
+    for bucket in &args.show_buckets {
+        reports.push(group::bucket(commits, &tags, bucket));
+    }
+
+    let parts = match (args.split_by_dir, args.split_by) {
+        (Some(depth), _) => Some(commit::by_dir(commits, depth)),
+        (None, Some(SplitBy::Month)) => Some(commit::by_month(commits)),
+        (None, None) => None,
+    };
+
+    match parts {
+        Some(parts) => {
+            for (part, commits) in parts {
+                let split = requested_reports(args, &commits, &tags, None);
+                reports.extend(split.into_iter().map(|report| retitle(report, &part)));
+            }
+
+            let combined = requested_reports(args, commits, &tags, None);
+            reports.extend(combined.into_iter().map(|report| retitle(report, "all")));
+        }
+        None => {
+            // The original histograms can't show changes, and have a dash
+            // per commit
+            let legacy = args.format == Format::Terminal
+                && !args.vs_previous
+                && args.weight == Weight::Count;
+            let mut requested = requested_reports(args, commits, &tags, legacy.then_some(&mut out));
+
+            if args.vs_previous {
+                let before = requested_reports(args, previous, &commit::tags(previous), None);
+                report::annotate(&mut requested, &before);
+            }
+
+            reports.extend(requested);
+        }
+    }
+
+    if args.new_contributors {
+        reports.push(newcomer::report(commits, earlier));
+    }
+
+    if args.handoffs {
+        reports.push(handoff::report(commits, previous));
+    }
+
+    if args.file_ages {
+        let tracked = or_quit(git::tracked_files(Path::new(".")));
+        reports.extend(file_age::reports(commits, &tracked));
+    }
+
+    if args.merge_lead_time {
+        let merges = merge_lead::merges(commits, |base, merged| {
+            or_quit(git::branch_times(Path::new("."), base, merged))
+        });
+        reports.extend(merge_lead::reports(&merges));
+    }
+
+    if args.github || args.gitlab {
+        let provider = or_quit(provider(args));
+        let mut pulls = or_quit(provider.pull_requests(args.pr_limit));
+
+        if args.anonymize {
+            anonymize::pull_requests(&mut pulls);
+        }
+
+        reports.extend(pull_request::reports(commits, &pulls));
+
+        let pipelines = or_quit(provider.pipelines(args.pr_limit));
+
+        if !pipelines.is_empty() {
+            reports.extend(pipeline::reports(commits, &pipelines));
+        }
+    }
+
+    out.push_str(&render_reports(args, &reports));
+    out
+}
+
+/// The hour, weekday, and size reports tallied as git writes the history, for
+/// `--low-memory`. Commits are put in their author's timezone and filtered one
+/// at a time, like `rename_authors()` and the filters after it.
+fn render_tally(args: &CliArgs, config: &Config) -> anyhow::Result<String> {
+    let timezones = config.timezones();
+    let mut tally = Tally::new(args.weight);
+
+    history::each_slim(Path::new("."), args.strict_encoding, |commit| {
+        let date = commit::in_timezone(commit.date, commit.email, &timezones);
+        let author = || Author::identified(commit.name, commit.email);
+
+        let shown = commit::is_between(date, args.since, args.until)
+            && (args.only_authors.is_empty()
+                || args.only_authors.iter().any(|text| author().matches(text)));
+
+        if shown {
+            tally.add(&CommitRef { date, ..*commit });
+        }
+    })?;
+
+    let mut out = String::new();
+    let mut reports = Vec::new();
+    let legacy = args.format == Format::Terminal && args.weight == Weight::Count;
 
     if args.hours {
-        print_hours(&commits);
+        match legacy {
+            true => out.push_str(&render_hours(args, &tally.hours())),
+            false => reports.push(tally.hours_report()),
+        }
     }
 
     if args.days {
-        print_weekdays(&commits);
+        match legacy {
+            true => out.push_str(&render_weekdays(args, &tally.weekdays())),
+            false => reports.push(tally.weekdays_report()),
+        }
+    }
+
+    if args.sizes {
+        reports.extend(tally.size_reports());
     }
+
+    out.push_str(&render_reports(args, &reports));
+    Ok(out)
+}
+
+/// Arguments telling apart the outputs `memo` keeps, when `--cache-reports` is
+/// given and the output depends on nothing but the history
+fn memo_key(args: &CliArgs, config: &Config) -> Option<String> {
+    let cacheable = args.cache_reports
+        && args.vcs == Vcs::Git
+        && args.subcommand.is_none()
+        && args.imports.is_empty()
+        && args.workspace.is_none()
+        && args.discover.is_none()
+        && !args.github
+        && !args.gitlab
+        && args.post.is_none();
+
+    cacheable.then(|| format!("{:?} {:?}", args, config))
+}
+
+/// The reports asked for by flags. The original hour and weekday histograms
+/// are rendered into `legacy`, when given, rather than returned.
+fn requested_reports(
+    args: &CliArgs,
+    commits: &[Commit],
+    tags: &[Tag],
+    mut legacy: Option<&mut String>,
+) -> Vec<Report> {
+    let mut reports = Vec::new();
+
+    if args.hours {
+        match legacy.as_mut() {
+            Some(out) => out.push_str(&render_hours(
+                args,
+                &histogram::by_hour(commits, Weight::Count),
+            )),
+            None => reports.push(activity::hours(commits, args.weight)),
+        }
+    }
+
+    if args.days {
+        match legacy.as_mut() {
+            Some(out) => out.push_str(&render_weekdays(
+                args,
+                &histogram::by_weekday(commits, Weight::Count),
+            )),
+            None => reports.push(activity::weekdays(commits, args.weight)),
+        }
+    }
+
+    for group_by in &args.group_by {
+        reports.push(group::report(commits, tags, *group_by));
+    }
+
+    if args.offsets {
+        reports.push(activity::offsets(commits, args.show_empty));
+    }
+
+    if let Some(team) = args.team_offset {
+        reports.extend(timezone::reports(commits, team));
+    }
+
+    if args.sizes {
+        reports.push(size::overall(commits));
+        reports.push(size::by_author(commits));
+    }
+
+    if args.anomalies {
+        reports.push(anomaly::report(commits, args.sigma));
+    }
+
+    if args.peak {
+        reports.push(peak::report(commits, args.weight));
+    }
+
+    let issue_pattern = args
+        .issue_pattern
+        .as_ref()
+        .unwrap_or(&issue::DEFAULT_PATTERN);
+
+    if args.hygiene {
+        reports.push(hygiene::report(commits, issue_pattern));
+    }
+
+    if args.reverts {
+        reports.extend(revert::reports(commits));
+    }
+
+    if args.releases {
+        reports.extend(release::reports(commits, tags));
+    }
+
+    if args.authors {
+        reports.extend(author::reports(commits));
+    }
+
+    if args.retention {
+        reports.push(retention::report(commits));
+    }
+
+    if args.lag {
+        reports.extend(lag::reports(commits));
+    }
+
+    if args.signoffs {
+        reports.extend(signoff::reports(commits));
+    }
+
+    if args.issues {
+        reports.extend(issue::reports(commits, issue_pattern));
+    }
+
+    if args.gitmoji {
+        reports.extend(gitmoji::reports(commits));
+    }
+
+    if args.scopes {
+        reports.extend(conventional::reports(commits));
+    }
+
+    if args.words {
+        reports.push(words::report(commits, args.top_words));
+    }
+
+    if args.rolling {
+        reports.push(rolling::report(commits, args.weeks));
+    }
+
+    if args.test_ratio {
+        let patterns = match args.test_patterns.as_slice() {
+            [] => slice::from_ref(&*test_ratio::DEFAULT_PATTERN),
+            patterns => patterns,
+        };
+        reports.extend(test_ratio::reports(commits, patterns));
+    }
+
+    if args.churn_tree {
+        reports.push(churn_tree::report(commits, args.tree_threshold, args.bars));
+    }
+
+    if args.extension_hours {
+        reports.push(extension_hours::report(commits, args.weight));
+    }
+
+    for program in &args.plugins {
+        reports.extend(or_quit(plugin::run(program, commits)));
+    }
+
+    if args.hide_empty {
+        reports.iter_mut().for_each(Report::hide_empty);
+    }
+
+    reports
+}
+
+/// Name the part of the history a report covers, e.g. `By hour in api:`
+fn retitle(mut report: Report, part: &str) -> Report {
+    let title = report.title.trim_end_matches(':');
+    report.title = format!("{} in {}:", title, part);
+    report
+}
+
+fn print_reports(args: &CliArgs, reports: &[Report]) {
+    print_output(args, &render_reports(args, reports));
+}
+
+/// Print the output, in only ASCII for `--ascii`, and without color where it
+/// wouldn't show
+fn print_output(args: &CliArgs, output: &str) {
+    if args.layout.ascii {
+        print!("{}", ascii::text(output));
+    } else if !args.capabilities.color {
+        print!("{}", ascii::plain(output));
+    } else {
+        print!("{}", output);
+    }
+}
+
+fn render_reports(args: &CliArgs, reports: &[Report]) -> String {
+    render_reports_as(args, &args.format, reports)
+}
+
+fn render_reports_as(args: &CliArgs, format: &Format, reports: &[Report]) -> String {
+    let reports = &args.date_format.apply(reports)[..];
+
+    match format {
+        Format::Terminal => reports
+            .iter()
+            .map(|report| terminal::render(report, args.numbers, args.layout))
+            .collect(),
+        Format::Slack => format!(
+            "{}\n",
+            slack::payload(&repo_name(), reports, args.numbers, args.bars)
+        ),
+        Format::Mermaid => mermaid::charts(reports),
+        Format::Markdown => markdown::reports(reports, args.numbers),
+        Format::Org => org::reports(reports, args.numbers),
+        Format::Html => html::reports(&repo_name(), reports),
+    }
+}
+
+/// Whether git needn't be asked for anything but each commit's date, which is
+/// much faster than reading its author, message, and diffstat
+fn dates_only(args: &CliArgs, config: &Config) -> bool {
+    args.has_date_report()
+        && !args.has_other_report()
+        && args.split_by_dir.is_none()
+        && args.weight == Weight::Count
+        && !args.first_parent
+        && args.only_authors.is_empty()
+        // Timezones are by author
+        && config.section("timezones").next().is_none()
+}
+
+/// Files changed by each commit are only read from git when a report uses them
+fn diffstat(args: &CliArgs) -> Diffstat {
+    let needs_files = args.reverts
+        || args.file_ages
+        || args.churn_tree
+        || args.extension_hours
+        || args.test_ratio
+        || !args.plugins.is_empty()
+        || args.group_by.contains(&GroupBy::Extension)
+        || shows_extensions(args)
+        || args.handoffs
+        || args.split_by_dir.is_some()
+        || args.subcommand == Some(Subcommand::Graph)
+        || args.subcommand == Some(Subcommand::Export)
+        || args.subcommand == Some(Subcommand::Query)
+        || args.post.is_some();
+
+    if needs_files {
+        Diffstat::Files
+    } else {
+        Diffstat::Totals
+    }
+}
+
+/// Whether a bucket of files' extensions is shown, which needs files changed
+fn shows_extensions(args: &CliArgs) -> bool {
+    args.show_buckets
+        .iter()
+        .any(|bucket| bucket.group_by == GroupBy::Extension)
 }
 
 fn args_or_quit() -> CliArgs {
-    let args = parse_cli_args();
+    let mut args = parse_cli_args();
+
+    // The original flags for hours and weekdays keep their histograms
+    for (group, flag) in [
+        (GroupBy::Hour, &mut args.hours),
+        (GroupBy::Weekday, &mut args.days),
+    ] {
+        *flag |= args.group_by.contains(&group);
+    }
+    args.group_by
+        .retain(|group| !matches!(group, GroupBy::Hour | GroupBy::Weekday));
+
+    if !args.has_report() {
+        print_help_and_quit();
+    }
+
+    // Tallied one commit at a time from git, so nothing needing more of a
+    // commit, or every commit at once
+    let tallied = !args.has_untallied_report()
+        && !args.offsets
+        && args.team_offset.is_none()
+        && args.imports.is_empty()
+        && args.vcs == Vcs::Git
+        && args.chunk_days.is_none()
+        && args.split_by_dir.is_none()
+        && args.split_by.is_none()
+        && !args.first_parent
+        && !args.header
+        && !args.vs_previous
+        && args.aliases.is_empty()
+        && !args.by_team
+        && !args.anonymize
+        && args.workspace.is_none()
+        && args.discover.is_none();
 
-    if !args.days && !args.hours {
+    if args.low_memory && !tallied {
+        print_help_and_quit();
+    }
+
+    if args.hide_empty && args.show_empty {
+        print_help_and_quit();
+    }
+
+    if args.split_by_dir.is_some() && args.split_by.is_some() {
+        print_help_and_quit();
+    }
+
+    // Compared with the period before --since, and only as a whole
+    let split = args.split_by_dir.is_some() || args.split_by.is_some();
+
+    if args.vs_previous && (args.since.is_none() || split) {
+        print_help_and_quit();
+    }
+
+    // File ages and merge lead times need a git repository at hand
+    let elsewhere = !args.imports.is_empty()
+        || args.workspace.is_some()
+        || args.discover.is_some()
+        || args.vcs != Vcs::Git;
+
+    if (args.file_ages || args.merge_lead_time) && elsewhere {
+        print_help_and_quit();
+    }
+
+    // Only the commits of a single repository are read from before --since
+    let single = args.workspace.is_none() && args.discover.is_none();
+
+    if args.new_contributors && !single {
+        print_help_and_quit();
+    }
+
+    if args.handoffs && (args.since.is_none() || !single) {
+        print_help_and_quit();
+    }
+
+    if args.first_parent && !single {
+        print_help_and_quit();
+    }
+
+    let git_only = matches!(
+        args.subcommand,
+        Some(
+            Subcommand::HalfLife
+                | Subcommand::BlameOwnership
+                | Subcommand::Branches
+                | Subcommand::HookInstall
+                | Subcommand::HookUpdate
+        )
+    );
+
+    if git_only && args.vcs != Vcs::Git {
         print_help_and_quit();
     }
 
@@ -61,97 +1536,692 @@ fn parse_cli_args() -> CliArgs {
         print_help_and_quit();
     }
 
+    let subcommand = match args.subcommand() {
+        Ok(None) => None,
+        Ok(Some(name)) if name == "biggest" => Some(Subcommand::Biggest),
+        Ok(Some(name)) if name == "blame-ownership" => Some(Subcommand::BlameOwnership),
+        Ok(Some(name)) if name == "branches" => Some(Subcommand::Branches),
+        Ok(Some(name)) if name == "changes" => Some(Subcommand::Changes),
+        Ok(Some(name)) if name == "compare" => Some(Subcommand::Compare),
+        Ok(Some(name)) if name == "feed" => Some(Subcommand::Feed),
+        Ok(Some(name)) if name == "graph" => Some(Subcommand::Graph),
+        Ok(Some(name)) if name == "half-life" => Some(Subcommand::HalfLife),
+        Ok(Some(name)) if name == "hook" => match args.subcommand() {
+            Ok(Some(name)) if name == "install" => Some(Subcommand::HookInstall),
+            Ok(Some(name)) if name == "update" => Some(Subcommand::HookUpdate),
+            _ => print_help_and_quit(),
+        },
+        Ok(Some(name)) if name == "export" => Some(Subcommand::Export),
+        Ok(Some(name)) if name == "list" => Some(Subcommand::List),
+        Ok(Some(name)) if name == "q" => Some(Subcommand::Query),
+        Ok(Some(name)) if name == "serve" => Some(Subcommand::Serve),
+        _ => print_help_and_quit(),
+    };
+
+    let (since, until) = since_and_until(&mut args);
+    let capabilities = Capabilities::detect();
+    let vs_previous = args.contains("--vs-previous");
+    let max_width = args
+        .opt_value_from_str("--max-width")
+        .unwrap_or_else(|_| print_help_and_quit());
+
+    // A bad pattern says what's wrong with it rather than printing the help
+    let date_format = args
+        .opt_value_from_str("--date-format")
+        .unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            process::exit(1);
+        })
+        .unwrap_or_default();
+
     CliArgs {
+        subcommand,
+        group_by: args
+            .values_from_str("--group-by")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        show_buckets: args
+            .values_from_str("--show-bucket")
+            .unwrap_or_else(|_| print_help_and_quit()),
         hours: args.contains("--hours"),
         days: args.contains("--days"),
+        offsets: args.contains("--offsets"),
+        weight: args
+            .opt_value_from_str("--weight")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or_default(),
+        team_offset: args
+            .opt_value_from_fn("--team-offset", timezone::parse_offset)
+            .unwrap_or_else(|_| print_help_and_quit()),
+        sizes: args.contains("--sizes"),
+        anomalies: args.contains("--anomalies"),
+        sigma: args
+            .opt_value_from_str("--sigma")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or(anomaly::DEFAULT_SIGMA),
+        peak: args.contains("--peak"),
+        hygiene: args.contains("--hygiene"),
+        reverts: args.contains("--reverts"),
+        releases: args.contains("--releases"),
+        lag: args.contains("--lag"),
+        signoffs: args.contains("--signoffs"),
+        issues: args.contains("--issues"),
+        file_ages: args.contains("--file-ages"),
+        merge_lead_time: args.contains("--merge-lead-time"),
+        gitmoji: args.contains("--gitmoji"),
+        scopes: args.contains("--scopes"),
+        words: args.contains("--words"),
+        top_words: args
+            .opt_value_from_str("--top-words")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or(words::DEFAULT_COUNT),
+        rolling: args.contains("--rolling"),
+        weeks: args
+            .opt_value_from_str("--weeks")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or(rolling::DEFAULT_WEEKS),
+        churn_tree: args.contains("--churn-tree"),
+        extension_hours: args.contains("--extension-hours"),
+        tree_threshold: args
+            .opt_value_from_fn("--tree-threshold", parse_threshold)
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or(churn_tree::DEFAULT_THRESHOLD),
+        test_ratio: args.contains("--test-ratio"),
+        test_patterns: args
+            .values_from_fn("--test-pattern", Regex::new)
+            .unwrap_or_else(|_| print_help_and_quit()),
+        plugins: args
+            .values_from_str("--plugin")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        issue_pattern: args
+            .opt_value_from_fn("--issue-pattern", Regex::new)
+            .unwrap_or_else(|_| print_help_and_quit()),
+        authors: args.contains("--authors"),
+        retention: args.contains("--retention"),
+        new_contributors: args.contains("--new-contributors"),
+        handoffs: args.contains("--handoffs"),
+        split_by_dir: args
+            .opt_value_from_str("--split-by-dir")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        split_by: args
+            .opt_value_from_str("--split-by")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        github: args.contains("--github"),
+        gitlab: args.contains("--gitlab"),
+        token: args
+            .opt_value_from_str("--token")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        pr_limit: args
+            .opt_value_from_str("--pr-limit")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or(100),
+        out: args
+            .opt_value_from_str("--out")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        dot: args.contains("--dot"),
+        base: args
+            .opt_value_from_str("--base")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        sample: args
+            .opt_value_from_str("--sample")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or(200),
+        sqlite: args
+            .opt_value_from_str("--sqlite")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        parquet: args
+            .opt_value_from_str("--parquet")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        json: args
+            .opt_value_from_str("--json")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        jsonl: args
+            .opt_value_from_str("--jsonl")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        cube_csv: args
+            .opt_value_from_str("--cube-csv")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        cube_json: args
+            .opt_value_from_str("--cube-json")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        vcs: args
+            .opt_value_from_str("--vcs")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or_else(|| Vcs::detect(Path::new("."))),
+        imports: args
+            .values_from_str("--import")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        input_format: args
+            .opt_value_from_str("--input-format")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or(import::Format::Json),
+        post: args
+            .opt_value_from_str("--post")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        format: args
+            .opt_value_from_str("--format")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or(Format::Terminal),
+        numbers: args
+            .opt_value_from_str("--numbers")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or_default(),
+        date_format,
+        strict_encoding: args.contains("--strict-encoding"),
+        deterministic: args.contains("--deterministic"),
+        header: args.contains("--header"),
+        low_memory: args.contains("--low-memory"),
+        cache_reports: args.contains("--cache-reports"),
+        timeout: args
+            .opt_value_from_fn("--timeout", parse_timeout)
+            .unwrap_or_else(|_| print_help_and_quit()),
+        chunk_days: args
+            .opt_value_from_str("--chunk-days")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        partial: args.contains("--partial"),
+        since,
+        until,
+        vs_previous,
+        config: args
+            .opt_value_from_str("--config")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        aliases: args
+            .values_from_str("--alias")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        by_team: args.contains("--by-team"),
+        anonymize: args.contains("--anonymize"),
+        redact_emails: args.contains("--redact-emails"),
+        workspace: args
+            .opt_value_from_str("--workspace")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        label: args
+            .opt_value_from_str("--label")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        discover: args
+            .opt_value_from_str("--discover")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        hide_empty: args.contains("--hide-empty"),
+        show_empty: args.contains("--show-empty"),
+        bars: Bars {
+            scale: args
+                .opt_value_from_fn("--bar-scale", parse_bar_scale)
+                .unwrap_or_else(|_| print_help_and_quit()),
+            max_width,
+        },
+        layout: Layout {
+            max_width: max_width.or(capabilities.width),
+            borders: args.contains("--borders"),
+            ascii: args.contains("--ascii"),
+        },
+        capabilities,
+        first_parent: args.contains("--first-parent"),
+        sort: args
+            .opt_value_from_str("--sort")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        repos: args
+            .values_from_str("--repo")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        host: args
+            .opt_value_from_str("--host")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or_else(|| "127.0.0.1".to_string()),
+        port: args
+            .opt_value_from_str("--port")
+            .unwrap_or_else(|_| print_help_and_quit())
+            .unwrap_or(7070),
+        refresh: args
+            .opt_value_from_str("--refresh")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        // Free arguments come after every flag
+        biggest: match subcommand {
+            Some(Subcommand::Biggest) => args
+                .opt_free_from_str()
+                .unwrap_or_else(|_| print_help_and_quit())
+                .unwrap_or(biggest::DEFAULT_COUNT),
+            _ => biggest::DEFAULT_COUNT,
+        },
+        only_authors: args
+            .values_from_str("--author")
+            .unwrap_or_else(|_| print_help_and_quit()),
+        list: list::Filter {
+            hours: args
+                .values_from_fn("--hour", list::parse_hour)
+                .unwrap_or_else(|_| print_help_and_quit()),
+            weekdays: args
+                .values_from_fn("--weekday", list::parse_weekday)
+                .unwrap_or_else(|_| print_help_and_quit()),
+        },
+        query: match subcommand {
+            Some(Subcommand::Query) => args
+                .opt_free_from_str()
+                .unwrap_or_else(|_| print_help_and_quit()),
+            _ => None,
+        },
+    }
+}
+
+/// Whole seconds, at least one
+fn parse_timeout(text: &str) -> Result<Duration, String> {
+    match text.parse::<u64>() {
+        Ok(seconds) if seconds > 0 => Ok(Duration::from_secs(seconds)),
+        _ => Err(format!("Invalid timeout {}", text)),
+    }
+}
+
+/// Commits per character, which has to be more than none
+fn parse_bar_scale(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(scale) if scale > 0.0 && scale.is_finite() => Ok(scale),
+        _ => Err(format!("Invalid bar scale {}", text)),
     }
 }
 
-fn print_help_and_quit() {
+fn parse_threshold(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent),
+        _ => Err(format!("Invalid threshold {}", text)),
+    }
+}
+
+/// Either `--since` and `--until` or the ones a shortcut like `--today` stands
+/// for, but not both
+fn since_and_until(
+    args: &mut pico_args::Arguments,
+) -> (Option<OffsetDateTime>, Option<OffsetDateTime>) {
+    let since = args
+        .opt_value_from_fn("--since", |text| range::parse(text, range::now()))
+        .unwrap_or_else(|_| print_help_and_quit());
+    let until = args
+        .opt_value_from_fn("--until", |text| range::parse_until(text, range::now()))
+        .unwrap_or_else(|_| print_help_and_quit());
+
+    let periods: Vec<Period> = [
+        ("--today", Period::Today),
+        ("--this-week", Period::ThisWeek),
+        ("--this-month", Period::ThisMonth),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| args.contains(*flag))
+    .map(|(_, period)| period)
+    .collect();
+
+    match (periods.as_slice(), since, until) {
+        ([], since, until) => (since, until),
+        ([period], None, None) => {
+            let (since, until) = range::period(*period, range::now());
+            (Some(since), Some(until))
+        }
+        _ => print_help_and_quit(),
+    }
+}
+
+/// The value, or the error printed without a backtrace and yeesh exiting, for
+/// problems the user can fix, e.g. running yeesh outside a repository
+fn or_quit<T>(result: anyhow::Result<T>) -> T {
+    result.unwrap_or_else(|error| {
+        eprintln!("yeesh: {:#}", error);
+        process::exit(1);
+    })
+}
+
+fn print_help_and_quit() -> ! {
     print!("{}", HELP);
     process::exit(1);
 }
 
-fn get_git_logs() -> String {
-    // The date format below yields the committer's local date. Regardless when
-    // (or where) this program is run, the local time of the commit is what gets
-    // captured. This is more meaningful than coverting dates and times into the
-    // local timezone of the person running the tool.
-    //
-    // The following StackOverflow discussion has more details:
-    // https://stackoverflow.com/questions/7853332/how-to-change-git-log-date-formats
-    let proc_output = Command::new("git")
-        .arg("log")
-        .arg("--stat")
-        .arg("--date=rfc")
-        .output()
-        .unwrap();
+/// GitHub or GitLab, depending on which was asked for, for the repository's
+/// origin remote
+fn provider(args: &CliArgs) -> anyhow::Result<Box<dyn Provider>> {
+    let (host, variable) = if args.gitlab {
+        ("gitlab.com", "GITLAB_TOKEN")
+    } else {
+        ("github.com", "GITHUB_TOKEN")
+    };
+
+    let url = git::origin_url(Path::new("."))?;
+
+    let (owner, name) = provider::slug_from_url(&url, host)
+        .with_context(|| format!("The origin remote {} isn't on {}", url, host))?;
 
-    let git_logs = str::from_utf8(&proc_output.stdout).unwrap();
+    let token = args
+        .token
+        .clone()
+        .or_else(|| env::var(variable).ok())
+        .with_context(|| format!("A token is required, see --token or ${}", variable))?;
 
-    git_logs.to_string()
+    if args.gitlab {
+        Ok(Box::new(GitLab::new(owner, name, token)))
+    } else {
+        Ok(Box::new(GitHub::new(owner, name, token)))
+    }
 }
 
-fn print_hours(commits: &Vec<Commit>) {
-    println!(
-        "{}{}By hour:{}",
-        style::Bold,
-        color::Fg(color::Magenta),
-        style::Reset
-    );
+/// Send every report to a URL, e.g. for a nightly job feeding a dashboard
+fn post(url: &str, commits: &[Commit], deterministic: bool) -> anyhow::Result<()> {
+    let tags = commit::tags(commits);
 
-    let by_hour = histogram::of_kind(Kind::ByHour, commits);
+    let analyzed_at = match commits.iter().map(|commit| commit.date).max() {
+        Some(newest) if deterministic => newest,
+        _ => OffsetDateTime::now_utc(),
+    };
+    let analyzed_at = analyzed_at.format(&Rfc3339)?;
 
-    for hour in 1..24 {
-        let count = by_hour.count_at(hour) as usize;
+    let body = json::analysis(&repo_name(), &analyzed_at, &report::all(commits, &tags));
 
-        println!(
-            "{}{:02} {}| {}{}{}",
-            color::Fg(color::LightBlue),
-            hour,
-            color::Fg(color::White),
-            color::Fg(color::Yellow),
-            "-".repeat(count),
-            style::Reset,
-        );
+    http::post_json(url, &body)
+}
+
+/// Anything given on the command line that left commits out
+fn filters(args: &CliArgs) -> anyhow::Result<Vec<String>> {
+    let mut filters = Vec::new();
+
+    if let Some(since) = args.since {
+        filters.push(format!("since {}", since.format(&Rfc3339)?));
     }
 
-    println!(
-        "\n{}total: {}{}\n",
-        style::Faint,
-        by_hour.len(),
-        style::Reset
-    );
+    if let Some(until) = args.until {
+        filters.push(format!("until {}", until.format(&Rfc3339)?));
+    }
+
+    Ok(filters)
+}
+
+/// Where the commits came from, for the top of the output
+fn header(args: &CliArgs, commits: &[Commit]) -> anyhow::Result<Report> {
+    let filters = filters(args)?;
+
+    let context = if args.imports.is_empty() {
+        let repo = Path::new(".");
+
+        header::Context {
+            repository: repo.canonicalize()?.display().to_string(),
+            branch: Some(match args.vcs {
+                Vcs::Git => git::branch(repo)?,
+                Vcs::Hg => hg::branch(repo)?,
+                Vcs::Svn => svn::branch(repo)?,
+            }),
+            range: "HEAD".to_string(),
+            filters,
+        }
+    } else {
+        let files: Vec<String> = args
+            .imports
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+
+        header::Context {
+            repository: files.join(", "),
+            branch: None,
+            range: "imported".to_string(),
+            filters,
+        }
+    };
+
+    Ok(header::report(&context, commits))
+}
+
+/// Which of the workspace's repositories the commits came from
+fn workspace_header(args: &CliArgs, names: &[&str], commits: &[Commit]) -> anyhow::Result<Report> {
+    let mut filters = filters(args)?;
+
+    if let Some(label) = &args.label {
+        filters.push(format!("label {}", label));
+    }
+
+    let context = header::Context {
+        repository: names.join(", "),
+        branch: None,
+        range: "workspace".to_string(),
+        filters,
+    };
+
+    Ok(header::report(&context, commits))
 }
 
-fn print_weekdays(commits: &Vec<Commit>) {
-    println!(
-        "{}{}By weekday:{}",
+/// Compare against the snapshot the previous run left, then leave one for the
+/// next run
+fn changes(commits: &[Commit]) -> anyhow::Result<Report> {
+    let path = cache::file(Path::new("."), "snapshot.json")?;
+
+    let previous = fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| Snapshot::from_json(&text));
+    let current = Snapshot::of(commits, OffsetDateTime::now_utc());
+
+    fs::write(&path, current.to_json())
+        .with_context(|| format!("Can't write {}", path.display()))?;
+
+    Ok(changes::report(previous.as_ref(), &current))
+}
+
+/// The reports a query composes, printed as it says
+fn query(args: &CliArgs, text: &str, commits: &[Commit]) -> anyhow::Result<String> {
+    let query = Query::parse(text, range::now())?;
+
+    // Tags of every commit so releases are whole even when filtered
+    let tags = commit::tags(commits);
+
+    let commits: Vec<Commit> = commits
+        .iter()
+        .filter(|commit| query.matches(commit))
+        .cloned()
+        .collect();
+
+    let mut reports: Vec<Report> = query
+        .groups
+        .iter()
+        .map(|group_by| group::report(&commits, &tags, *group_by))
+        .collect();
+
+    query.top(&mut reports);
+
+    let output = match query.format.as_deref() {
+        Some("json") => format!("{}\n", json::reports(&reports)),
+        Some(format) => {
+            render_reports_as(args, &format.parse().map_err(anyhow::Error::msg)?, &reports)
+        }
+        None => render_reports(args, &reports),
+    };
+
+    Ok(output)
+}
+
+/// Reports comparing every repository, named like serve names them
+fn compare(args: &CliArgs) -> anyhow::Result<Vec<Report>> {
+    if args.repos.len() < 2 {
+        print_help_and_quit();
+    }
+
+    let repos = serve::repos(&args.repos);
+    let mut histories = Vec::new();
+
+    for repo in &repos {
+        let mut commits = history::commits(&repo.path, Diffstat::Totals, args.strict_encoding)?;
+
+        // Aliased first so an author is found under any of their identities
+        commit::alias(&mut commits, &args.aliases);
+        commit::retain_authors(&mut commits, &args.only_authors);
+
+        histories.push(commits);
+    }
+
+    let named: Vec<(&str, &[Commit])> = repos
+        .iter()
+        .zip(&histories)
+        .map(|(repo, commits)| (repo.name.as_str(), commits.as_slice()))
+        .collect();
+
+    Ok(compare::reports(&named))
+}
+
+/// The current directory's name, as serve would name it
+fn repo_name() -> String {
+    serve::repos(&[PathBuf::from(".")]).remove(0).name
+}
+
+fn serve(args: &CliArgs) {
+    let paths = if args.repos.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        args.repos.clone()
+    };
+
+    let address = format!("{}:{}", args.host, args.port);
+    let refresh = args.refresh.map(Duration::from_secs);
+
+    or_quit(serve::serve(
+        &address,
+        serve::repos(&paths),
+        refresh,
+        args.aliases.clone(),
+        args.only_authors.clone(),
+    ));
+}
+
+fn export(args: &CliArgs, commits: &[Commit]) {
+    if args.sqlite.is_none()
+        && args.parquet.is_none()
+        && args.json.is_none()
+        && args.jsonl.is_none()
+        && args.cube_csv.is_none()
+        && args.cube_json.is_none()
+    {
+        print_help_and_quit();
+    }
+
+    if let Some(path) = &args.sqlite {
+        or_quit(export::sqlite::write(path, commits));
+    }
+
+    if let Some(path) = &args.parquet {
+        or_quit(export::parquet::write(path, commits));
+    }
+
+    if let Some(path) = &args.json {
+        or_quit(export::json::write(path, commits, false));
+    }
+
+    if let Some(path) = &args.jsonl {
+        or_quit(export::json::write(path, commits, true));
+    }
+
+    if let Some(path) = &args.cube_csv {
+        or_quit(export::cube::write_csv(path, commits));
+    }
+
+    if let Some(path) = &args.cube_json {
+        or_quit(export::cube::write_json(path, commits));
+    }
+}
+
+/// The median age of surviving lines per directory, for `half-life`
+fn half_life(args: &CliArgs) -> anyhow::Result<Report> {
+    let files = half_life::sample(git::tracked_files(Path::new("."))?, args.sample);
+
+    let blamed = files
+        .into_iter()
+        .map(|file| {
+            let times = git::blame_times(Path::new("."), &file)?;
+            Ok((file, times))
+        })
+        .collect::<anyhow::Result<Vec<(String, Vec<i64>)>>>()?;
+
+    // Ages as of the latest commit only change when the history does
+    let now = if args.deterministic {
+        git::head_time(Path::new("."))?
+    } else {
+        OffsetDateTime::now_utc().unix_timestamp()
+    };
+
+    Ok(half_life::report(&blamed, now))
+}
+
+/// Every branch compared with `--base` or the default branch
+fn branches(args: &CliArgs) -> anyhow::Result<Report> {
+    let repo = Path::new(".");
+
+    let base = match &args.base {
+        Some(base) => base.clone(),
+        None => git::default_branch(repo)?,
+    };
+
+    let branches = git::branches(repo, &base)?;
+
+    // Ages as of the latest commit only change when the branches do
+    let now = match branches.iter().map(|branch| branch.time).max() {
+        Some(newest) if args.deterministic => newest,
+        _ => OffsetDateTime::now_utc().unix_timestamp(),
+    };
+
+    Ok(branches::report(&branches, &base, now))
+}
+
+/// Who authored each line of a sample of the tracked files
+fn blame_ownership(sample: usize) -> anyhow::Result<Vec<Report>> {
+    let files = half_life::sample(git::tracked_files(Path::new("."))?, sample);
+
+    let blamed = files
+        .into_iter()
+        .map(|file| {
+            let authors = git::blame_authors(Path::new("."), &file)?;
+            Ok((file, authors))
+        })
+        .collect::<anyhow::Result<Vec<(String, Vec<Author>)>>>()?;
+
+    Ok(ownership::reports(&blamed))
+}
+
+fn render_hours(args: &CliArgs, by_hour: &[u64; 24]) -> String {
+    let labels: Vec<String> = (0..24).map(|hour| format!("{:02}", hour)).collect();
+
+    render_legacy(args, "By hour:", &labels, by_hour)
+}
+
+fn render_weekdays(args: &CliArgs, by_weekday: &[u64; 7]) -> String {
+    let labels: Vec<String> = WEEKDAYS.iter().map(|weekday| weekday.to_string()).collect();
+
+    render_legacy(args, "By weekday:", &labels, by_weekday)
+}
+
+/// The original histograms, a dash per commit unless `--bar-scale` or
+/// `--max-width` say otherwise
+fn render_legacy(args: &CliArgs, title: &str, labels: &[String], counts: &[u64]) -> String {
+    let mut out = format!(
+        "{}{}{}{}\n",
         style::Bold,
         color::Fg(color::Magenta),
+        title,
         style::Reset
     );
 
-    let by_weekday = histogram::of_kind(Kind::ByWeekday, commits);
+    let max = counts.iter().copied().max().unwrap_or_default();
 
-    for weekday in 1..7 {
-        let count = by_weekday.count_at(weekday) as usize;
+    for (label, count) in labels.iter().zip(counts) {
+        if args.hide_empty && *count == 0 {
+            continue;
+        }
 
-        println!(
-            "{}{:02} {}| {}{}{}",
+        let width = args
+            .bars
+            .width(*count, max, label.chars().count() + 3, None);
+
+        out.push_str(&format!(
+            "{}{} {}| {}{}{}\n",
             color::Fg(color::LightBlue),
-            weekday,
+            label,
             color::Fg(color::White),
             color::Fg(color::Yellow),
-            "-".repeat(count),
+            "-".repeat(width),
             style::Reset,
-        );
+        ));
     }
 
-    println!(
-        "\n{}total: {}{}\n",
+    out.push_str(&format!(
+        "\n{}total: {}{}\n\n",
         style::Faint,
-        by_weekday.len(),
+        counts.iter().sum::<u64>(),
         style::Reset
-    );
+    ));
+
+    out
 }
@@ -0,0 +1,52 @@
+use crate::number::Numbers;
+use crate::report::Report;
+use crate::table::{self, Style};
+
+const STYLE: Style = Style { joint: '|', escape };
+
+/// Reports as markdown, each a heading over a table
+pub fn reports(reports: &[Report], numbers: Numbers) -> String {
+    reports
+        .iter()
+        .map(|report| {
+            format!(
+                "### {}\n\n{}\n",
+                report.title.trim_end_matches(':'),
+                table::pipes(&report.table, &STYLE, numbers)
+            )
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::number::Numbers;
+    use crate::report::{Report, Table, Value};
+
+    #[test]
+    fn reports() {
+        let mut table = Table::new(&["hour", "commits"]);
+        table.push(vec![Value::Text("00".to_string()), Value::Count(2)]);
+
+        assert_eq!(
+            super::reports(&[Report::histogram("By hour:", table)], Numbers::Plain),
+            "\
+### By hour
+
+| hour | commits |
+|------|---------|
+| 00   | 2       |
+
+"
+        );
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(super::escape("a|b"), "a\\|b");
+    }
+}
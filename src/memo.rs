@@ -0,0 +1,97 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::{cache, git};
+
+/// Output printed by an earlier run with the same arguments, if the history
+/// hasn't changed since. The history is the same when HEAD, the branch, and
+/// every branch and tag are, so e.g. tagging a release runs the reports again.
+pub fn read(repo: &Path, args: &str) -> anyhow::Result<Option<String>> {
+    let path = file(repo, args)?;
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(_) => return Ok(None),
+    };
+
+    let state = state(repo)?;
+
+    Ok(match text.split_once('\n') {
+        Some((key, output)) if key == state => Some(output.to_string()),
+        _ => None,
+    })
+}
+
+/// Outputs kept for each repository, so arguments that differ every run, e.g.
+/// `--since '2 weeks ago'` resolved against now, don't fill the cache
+const KEPT: usize = 16;
+
+/// Remember the output for the history as it is now. Only the latest output
+/// for each set of arguments is kept, and only for the `KEPT` sets of arguments
+/// run most recently.
+pub fn write(repo: &Path, args: &str, output: &str) -> anyhow::Result<()> {
+    let path = file(repo, args)?;
+    let text = format!("{}\n{}", state(repo)?, output);
+
+    // Written aside then moved into place like the commit cache so a run at
+    // the same time never reads half the output
+    let partial = path.with_extension("txt.partial");
+    fs::write(&partial, text).with_context(|| format!("Can't write {}", partial.display()))?;
+    fs::rename(&partial, &path)?;
+
+    evict(repo)
+}
+
+/// Remove all but the `KEPT` outputs written most recently for the repository
+fn evict(repo: &Path) -> anyhow::Result<()> {
+    let prefix = file_name(&cache::file(repo, "reports-")?);
+    let dir = cache::dir()?;
+
+    let mut outputs = Vec::new();
+
+    for entry in fs::read_dir(&dir).with_context(|| format!("Can't read {}", dir.display()))? {
+        let path = entry?.path();
+        let name = file_name(&path);
+
+        if name.starts_with(&prefix) && name.ends_with(".txt") {
+            outputs.push((fs::metadata(&path)?.modified()?, path));
+        }
+    }
+
+    outputs.sort_by_key(|(modified, _)| Reverse(*modified));
+
+    for (_, path) in outputs.into_iter().skip(KEPT) {
+        fs::remove_file(&path).with_context(|| format!("Can't remove {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn file(repo: &Path, args: &str) -> anyhow::Result<PathBuf> {
+    cache::file(repo, &format!("reports-{:016x}.txt", hash(&args)))
+}
+
+fn state(repo: &Path) -> anyhow::Result<String> {
+    let head = git::head(repo)?;
+    let branch = git::branch(repo)?;
+    let refs = git::refs(repo)?;
+
+    Ok(format!("{:016x}", hash(&(head, branch, refs))))
+}
+
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
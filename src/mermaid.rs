@@ -0,0 +1,123 @@
+use crate::report::{Report, Value};
+
+/// Reports as mermaid charts in fenced code blocks, ready to paste into
+/// markdown that renders mermaid, e.g. on GitHub or GitLab. Histograms are bar
+/// charts and shares are pies. Other reports have no chart and are skipped.
+pub fn charts(reports: &[Report]) -> String {
+    reports
+        .iter()
+        .filter_map(chart)
+        .map(|chart| format!("```mermaid\n{}```\n\n", chart))
+        .collect()
+}
+
+fn chart(report: &Report) -> Option<String> {
+    if report.histogram {
+        Some(xychart(report))
+    } else if report.shares {
+        Some(pie(report))
+    } else {
+        None
+    }
+}
+
+fn xychart(report: &Report) -> String {
+    let (labels, counts) = labelled_counts(report);
+
+    let labels: Vec<String> = labels.iter().map(|label| quote(label)).collect();
+    let counts: Vec<String> = counts.iter().map(u64::to_string).collect();
+    let y_axis = report
+        .table
+        .columns
+        .get(1)
+        .map(String::as_str)
+        .unwrap_or_default();
+
+    format!(
+        "xychart-beta\n    title {}\n    x-axis [{}]\n    y-axis {}\n    bar [{}]\n",
+        quote(title(report)),
+        labels.join(", "),
+        quote(y_axis),
+        counts.join(", ")
+    )
+}
+
+fn pie(report: &Report) -> String {
+    let mut pie = format!("pie title {}\n", title(report));
+    let (labels, counts) = labelled_counts(report);
+
+    for (label, count) in labels.iter().zip(counts) {
+        pie.push_str(&format!("    {} : {}\n", quote(label), count));
+    }
+
+    pie
+}
+
+/// Each row's label and count, skipping rows without a count
+fn labelled_counts(report: &Report) -> (Vec<String>, Vec<u64>) {
+    report
+        .table
+        .rows
+        .iter()
+        .filter_map(|row| match (row.first(), row.get(1)) {
+            (Some(label), Some(Value::Count(count))) => Some((label.to_string(), *count)),
+            _ => None,
+        })
+        .unzip()
+}
+
+/// Titles end with a colon for the terminal, which looks odd on a chart
+fn title(report: &Report) -> &str {
+    report.title.trim_end_matches(':')
+}
+
+/// A quoted label. Mermaid has no escape for quotes other than its entity.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "#quot;"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::{Report, Table, Value};
+
+    fn table(label: &str) -> Table {
+        let mut table = Table::new(&[label, "commits"]);
+        table.push(vec![Value::Text("Mon".to_string()), Value::Count(3)]);
+        table.push(vec![Value::Text("Tue".to_string()), Value::Count(1)]);
+        table
+    }
+
+    #[test]
+    fn charts() {
+        let reports = vec![
+            Report::histogram("By weekday:", table("weekday")),
+            Report::shares("Authors:", table("author")),
+            Report::new("Other:", table("other")),
+        ];
+
+        assert_eq!(
+            super::charts(&reports),
+            "\
+```mermaid
+xychart-beta
+    title \"By weekday\"
+    x-axis [\"Mon\", \"Tue\"]
+    y-axis \"commits\"
+    bar [3, 1]
+```
+
+```mermaid
+pie title Authors
+    \"Mon\" : 3
+    \"Tue\" : 1
+```
+
+"
+        );
+    }
+
+    #[test]
+    fn quote() {
+        assert_eq!(super::quote("say \"hi\""), "\"say #quot;hi#quot;\"");
+    }
+}
@@ -0,0 +1,181 @@
+use std::env;
+use std::str::FromStr;
+
+use crate::report::Value;
+
+/// How numbers are written for people to read. Machine formats like JSON always
+/// keep them exact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Numbers {
+    /// Exactly as computed, e.g. `1283749`
+    Plain,
+    /// With thousands separated, e.g. `1,283,749`, or `1.283.749` where the
+    /// locale uses a comma as the decimal mark
+    Grouped(char),
+    /// Abbreviated with SI prefixes, e.g. `1.3M`
+    Short,
+}
+
+impl FromStr for Numbers {
+    type Err = String;
+
+    fn from_str(numbers: &str) -> Result<Numbers, String> {
+        match numbers {
+            "plain" => Ok(Numbers::Plain),
+            "grouped" => Ok(Numbers::Grouped(separator(&locale()))),
+            "short" => Ok(Numbers::Short),
+            _ => Err(format!("Unknown number style {}", numbers)),
+        }
+    }
+}
+
+impl Default for Numbers {
+    fn default() -> Numbers {
+        Numbers::Grouped(separator(&locale()))
+    }
+}
+
+impl Numbers {
+    /// The value as text. Ratios and percentages are small enough that only
+    /// their decimal mark changes.
+    pub fn format(&self, value: &Value) -> String {
+        match (self, value) {
+            (Numbers::Grouped(separator), Value::Count(count)) => {
+                group(&count.to_string(), *separator)
+            }
+            (Numbers::Grouped(separator), Value::Decimal(decimal)) if decimal.is_finite() => {
+                let text = format!("{:.1}", decimal.abs());
+                let (whole, fraction) = text.split_once('.').unwrap_or((&text, "0"));
+                let sign = if *decimal < 0.0 { "-" } else { "" };
+
+                format!(
+                    "{}{}{}{}",
+                    sign,
+                    group(whole, *separator),
+                    decimal_mark(*separator),
+                    fraction
+                )
+            }
+            (Numbers::Grouped(separator), Value::Ratio(_) | Value::Percent(_)) => value
+                .to_string()
+                .replace('.', &decimal_mark(*separator).to_string()),
+            (Numbers::Short, Value::Count(count)) => short(*count as f64),
+            (Numbers::Short, Value::Decimal(decimal)) if decimal.abs() >= 1000.0 => short(*decimal),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Digits with a separator between every three, counting from the right
+fn group(digits: &str, separator: char) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+fn decimal_mark(separator: char) -> char {
+    if separator == '.' {
+        ','
+    } else {
+        '.'
+    }
+}
+
+/// e.g. `950`, `12k`, or `1.3M`. One decimal is kept below ten so the number
+/// stays meaningful.
+fn short(number: f64) -> String {
+    const PREFIXES: [&str; 5] = ["", "k", "M", "G", "T"];
+
+    let mut scaled = number;
+    let mut prefix = 0;
+
+    while scaled.abs() >= 999.95 && prefix < PREFIXES.len() - 1 {
+        scaled /= 1000.0;
+        prefix += 1;
+    }
+
+    if prefix == 0 {
+        format!("{}", number.round())
+    } else if scaled.abs() < 9.95 {
+        format!("{:.1}{}", scaled, PREFIXES[prefix])
+    } else {
+        format!("{:.0}{}", scaled, PREFIXES[prefix])
+    }
+}
+
+/// The locale numbers are written in, from the same variables as C programs
+fn locale() -> String {
+    ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default()
+}
+
+/// The thousands separator of a locale like `de_DE.UTF-8`, by its language
+fn separator(locale: &str) -> char {
+    let language = locale.split(['_', '.', '@']).next().unwrap_or_default();
+
+    match language {
+        "da" | "de" | "el" | "es" | "id" | "it" | "nl" | "pt" | "tr" => '.',
+        // A narrow no-break space, so numbers don't wrap
+        "cs" | "fi" | "fr" | "hu" | "nb" | "pl" | "ru" | "sk" | "sv" | "uk" => '\u{202f}',
+        _ => ',',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::Value;
+
+    use super::Numbers;
+
+    #[test]
+    fn grouped() {
+        let numbers = Numbers::Grouped(',');
+
+        assert_eq!(numbers.format(&Value::Count(1283749)), "1,283,749");
+        assert_eq!(numbers.format(&Value::Count(999)), "999");
+        assert_eq!(numbers.format(&Value::Count(1000)), "1,000");
+        assert_eq!(numbers.format(&Value::Decimal(-12345.67)), "-12,345.7");
+        assert_eq!(
+            Numbers::Grouped('.').format(&Value::Decimal(1234.5)),
+            "1.234,5"
+        );
+        assert_eq!(numbers.format(&Value::Percent(0.25)), "25.0%");
+        assert_eq!(Numbers::Grouped('.').format(&Value::Percent(0.25)), "25,0%");
+    }
+
+    #[test]
+    fn short() {
+        let numbers = Numbers::Short;
+
+        assert_eq!(numbers.format(&Value::Count(950)), "950");
+        assert_eq!(numbers.format(&Value::Count(1283749)), "1.3M");
+        assert_eq!(numbers.format(&Value::Count(12345)), "12k");
+        assert_eq!(numbers.format(&Value::Count(999_999)), "1.0M");
+        assert_eq!(numbers.format(&Value::Decimal(2.5)), "2.5");
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(Numbers::Plain.format(&Value::Count(1283749)), "1283749");
+    }
+
+    #[test]
+    fn separator() {
+        assert_eq!(super::separator("en_CA.UTF-8"), ',');
+        assert_eq!(super::separator("de_DE.UTF-8"), '.');
+        assert_eq!(super::separator("fr_FR"), '\u{202f}');
+        assert_eq!(super::separator("C"), ',');
+        assert_eq!(super::separator(""), ',');
+    }
+}
@@ -0,0 +1,47 @@
+use crate::number::Numbers;
+use crate::report::Report;
+use crate::table::{self, Style};
+
+const STYLE: Style = Style { joint: '+', escape };
+
+/// Reports as org-mode, each a headline over a table
+pub fn reports(reports: &[Report], numbers: Numbers) -> String {
+    reports
+        .iter()
+        .map(|report| {
+            format!(
+                "* {}\n{}\n",
+                report.title.trim_end_matches(':'),
+                table::pipes(&report.table, &STYLE, numbers)
+            )
+        })
+        .collect()
+}
+
+/// Org has no escape for a pipe inside a table, only the `\vert` entity
+fn escape(text: &str) -> String {
+    text.replace('|', "\\vert{}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::number::Numbers;
+    use crate::report::{Report, Table, Value};
+
+    #[test]
+    fn reports() {
+        let mut table = Table::new(&["hour", "commits"]);
+        table.push(vec![Value::Text("00".to_string()), Value::Count(2)]);
+
+        assert_eq!(
+            super::reports(&[Report::histogram("By hour:", table)], Numbers::Plain),
+            "\
+* By hour
+| hour | commits |
+|------+---------|
+| 00   | 2       |
+
+"
+        );
+    }
+}
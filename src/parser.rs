@@ -1,9 +1,14 @@
+use std::mem;
+
 use anyhow::Context;
 use lazy_static::lazy_static;
 use regex::Regex;
-use time::{format_description::well_known::Rfc2822, OffsetDateTime};
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::OffsetDateTime;
 
-use crate::commit::{Author, Commit};
+use crate::commit::{Author, Commit, CommitRef, FileChange, Ref, RefKind};
 
 /// Represents the state machine's current state
 enum State {
@@ -14,6 +19,10 @@ enum State {
     /// Indicates the parser expects the next line to contain the commit's hash
     Hash,
 
+    /// Indicates the parser expects the next line to list a merge commit's
+    /// parents, or otherwise contain the author's name and email
+    Merge,
+
     /// Indicates the parser expects the next line to contain the author's name
     /// and email
     Author,
@@ -21,21 +30,48 @@ enum State {
     /// Indicates the parser expects the next line to contain the date
     Date,
 
-    /// Indicates the parser expects the next line to contain the number of
-    /// files modified, insertions, and deletions
+    /// Indicates the parser expects the committer and commit date next, which
+    /// only `--pretty=fuller` has
+    Committed,
+
+    /// Indicates the parser expects the commit message and eventually a line
+    /// containing the number of files modified, insertions, and deletions.
+    /// Empty commits and merges have no such line, so the next commit or the
+    /// end of the input also ends the commit.
     Stats,
 
     /// Used to clean up success parsing of a commit
     Accept,
 }
 
-// Compile regular expressions only once and at compile time
+/// `--date=iso`, e.g. `2022-11-28 16:52:42 -0800`
+const ISO: &[FormatItem] = format_description!(
+    "[year]-[month]-[day] [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
+);
+
+/// `--date=default`, e.g. `Mon Nov 28 16:52:42 2022 -0800`
+const DEFAULT: &[FormatItem] = format_description!(
+    "[weekday repr:short] [month repr:short] [day padding:none] [hour]:[minute]:[second] [year] [offset_hour sign:mandatory][offset_minute]"
+);
+
+/// Prefix git puts before each line of a commit message
+const MESSAGE_INDENT: &str = "    ";
+
+// Compile regular expressions only once and at compile time. Each line is
+// first parsed by hand since that's several times faster, and these are only
+// used for lines the hand-written parsing doesn't understand.
 lazy_static! {
-    // TODO Fix so hash doesn't include `(HEAD -> main)`. Will require
-    // additional and/or updated tests.
-    static ref HASH_REGEX: Regex = Regex::new(r"^commit (.+)$").unwrap();
-    static ref AUTHOR_REGEX: Regex = Regex::new(r"^Author: (.+) <(.+)>$").unwrap();
-    static ref DATE_REGEX: Regex = Regex::new(r"^Date:(.+)$").unwrap();
+    // Parents and then decorations follow the hash, e.g.
+    // `commit abc123 789fed (HEAD -> main, tag: v1)`
+    static ref HASH_REGEX: Regex =
+        Regex::new(r"^commit (\S+)((?: [0-9a-f]+)*)(?: \((.*)\))?$").unwrap();
+    static ref MERGE_REGEX: Regex = Regex::new(r"^Merge: (.+)$").unwrap();
+    // The name is greedy so one containing `<` keeps it, and the email may be
+    // empty or wrapped in extra brackets, e.g. `Jon <>` or `Jon <<jon@x.ca>>`
+    static ref AUTHOR_REGEX: Regex = Regex::new(r"^Author: (.*) <(.*)>$").unwrap();
+    static ref DATE_REGEX: Regex = Regex::new(r"^(?:Author|Commit)?Date:(.+)$").unwrap();
+    static ref FILE_REGEX: Regex = Regex::new(r"^\s+(.+?)\s+\|\s+(\d+|Bin)").unwrap();
+    static ref RENAME_REGEX: Regex = Regex::new(r"^(.*)\{(.*) => (.*)\}(.*)$").unwrap();
     static ref FILES_REGEX: Regex = Regex::new(r"(\d+) files? changed.+$").unwrap();
     static ref INSERTS_REGEX: Regex = Regex::new(r"\s(\d+) insertions?.+$").unwrap();
     static ref DELETES_REGEX: Regex = Regex::new(r"\s(\d+) deletions?.+$").unwrap();
@@ -45,20 +81,33 @@ lazy_static! {
 /// parsing fails the result contains a meaningful error.
 ///
 /// Note that the input format is specific. That is, the git logs must contain
-/// stats via `--stat` and a particular date format. These are defined in
-/// `main.rs` and are tightly coupled to the implementation. In other words,
-/// this is brittle!
+/// stats via `--stat`, as `git::logs()` asks for. Dates may be in any of git's
+/// `rfc`, `iso`, `iso-strict`, `unix`, or `default` formats so logs captured
+/// with other settings still parse.
 pub fn parse(input: &str) -> anyhow::Result<Vec<Commit>> {
     let mut result = Vec::new();
 
+    parse_each(input.split('\n'), |commit| result.push(commit))?;
+
+    Ok(result)
+}
+
+/// Like `parse()` but each commit is handed to `f` as soon as it's parsed
+/// rather than collected, so logs can be parsed as git writes them without
+/// holding all of them at once
+pub fn parse_each<I, S>(lines: I, mut f: impl FnMut(Commit)) -> anyhow::Result<()>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
     let mut state = State::Hash;
     let mut commit = Commit::default();
 
-    let mut lines = input.split('\n').peekable();
+    let mut lines = lines.peekable();
 
     loop {
         match state {
-            State::Start => match lines.peek() {
+            State::Start => match lines.peek().map(AsRef::as_ref) {
                 // When there are no more lines in the start state then the
                 // machine terminates successfully
                 None => break,
@@ -74,19 +123,86 @@ pub fn parse(input: &str) -> anyhow::Result<Vec<Commit>> {
                 }
             },
             State::Hash => {
-                commit.hash = parse_hash(lines.next())?;
+                (commit.hash, commit.parents, commit.refs) =
+                    parse_hash(lines.next().as_ref().map(AsRef::as_ref))?;
+                state = State::Merge;
+            }
+            State::Merge => {
+                // Only merges have the line, between the hash and author. Its
+                // parents are abbreviated so those after the hash are kept
+                // when git was asked for them.
+                if let Some(parents) = lines.peek().and_then(|line| parse_merge(line.as_ref())) {
+                    if commit.parents.is_empty() {
+                        commit.parents = parents;
+                    }
+                    let _merge = lines.next();
+                }
+
                 state = State::Author;
             }
             State::Author => {
-                commit.author = parse_author(lines.next())?;
+                commit.author = parse_author(lines.next().as_ref().map(AsRef::as_ref))?;
                 state = State::Date;
             }
             State::Date => {
-                commit.date = parse_date(lines.next())?;
+                commit.date = parse_date(lines.next().as_ref().map(AsRef::as_ref))?;
+                state = State::Committed;
+            }
+            State::Committed => {
+                if lines
+                    .peek()
+                    .is_some_and(|line| line.as_ref().starts_with("Commit:"))
+                {
+                    let _committer = lines.next();
+                }
+
+                if lines
+                    .peek()
+                    .is_some_and(|line| line.as_ref().starts_with("CommitDate:"))
+                {
+                    commit.committed = Some(parse_date(lines.next().as_ref().map(AsRef::as_ref))?);
+                }
+
                 state = State::Stats;
             }
             State::Stats => {
+                // Only the hash line of the next commit is flush with the
+                // start of the line and begins with `commit `
+                let ended = match lines.peek() {
+                    None => true,
+                    Some(line) => is_hash(line.as_ref()),
+                };
+
+                if ended {
+                    state = State::Accept;
+                    continue;
+                }
+
                 let line = lines.next();
+                let line = line.as_ref().map(AsRef::as_ref);
+
+                // git indents every line of the message, including blank
+                // ones, by four spaces. Nothing else in the stat block is
+                // indented that far.
+                if let Some(message) = line.and_then(|line| line.strip_prefix(MESSAGE_INDENT)) {
+                    commit.message.push_str(message);
+                    commit.message.push('\n');
+                    continue;
+                }
+
+                if let Some(change) = line.and_then(parse_file_change) {
+                    commit.file_changes.push(change);
+                    continue;
+                }
+
+                if let Some((files, inserts, deletes)) = line.and_then(parse_stats) {
+                    commit.files = files;
+                    commit.inserts = inserts;
+                    commit.deletes = deletes;
+
+                    state = State::Accept;
+                    continue;
+                }
 
                 let files = parse_stat(&FILES_REGEX, line);
                 let inserts = parse_stat(&INSERTS_REGEX, line);
@@ -103,25 +219,181 @@ pub fn parse(input: &str) -> anyhow::Result<Vec<Commit>> {
                 state = State::Accept;
             }
             State::Accept => {
-                result.push(commit.clone());
+                commit.message = commit.message.trim().to_string();
+                f(mem::take(&mut commit));
                 state = State::Start;
             }
         }
     }
 
-    Ok(result)
+    Ok(())
+}
+
+/// Commits with only a hash and date from `git::dates()`, one per line, e.g.
+/// `abc123 2022-11-28T16:52:42-08:00`
+pub fn parse_dates(input: &str) -> anyhow::Result<Vec<Commit>> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (hash, date) = line
+                .split_once(' ')
+                .with_context(|| format!("Expected hash and date on input {:?}", line))?;
+
+            Ok(Commit {
+                hash: hash.to_string(),
+                date: OffsetDateTime::parse(date, &Rfc3339)?,
+                ..Default::default()
+            })
+        })
+        .collect()
 }
 
-fn parse_hash(line: Option<&str>) -> anyhow::Result<String> {
+/// Like `parse()` but borrowing from the input rather than copying out of it,
+/// for callers needing only what `CommitRef` has. Files, and the message past
+/// its subject, are skipped.
+pub fn parse_borrowed(input: &str) -> anyhow::Result<Vec<CommitRef<'_>>> {
+    let mut commits = Vec::new();
+    let mut lines = input.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let hash = line
+            .strip_prefix("commit ")
+            .with_context(|| format!("Expected commit hash on input {:?}", line))?;
+
+        let hashes = hash.split_once(" (").map_or(hash, |(hashes, _)| hashes);
+        let parents = hashes.split_once(' ').map_or("", |(_, parents)| parents);
+
+        let mut commit = CommitRef {
+            parents,
+            ..Default::default()
+        };
+        let mut date = None;
+
+        while let Some(line) = lines.next_if(|line| !is_hash(line)) {
+            if let Some(message) = line.strip_prefix(MESSAGE_INDENT) {
+                if commit.subject.is_empty() {
+                    commit.subject = message.trim();
+                }
+            } else if let Some(parents) = line.strip_prefix("Merge: ") {
+                if commit.parents.is_empty() {
+                    commit.parents = parents;
+                }
+            } else if line.starts_with("Author:") {
+                let (name, email) =
+                    split_author(line).unwrap_or((line.trim_start_matches("Author:"), ""));
+
+                commit.name = name.trim();
+                commit.email = email.trim_matches(|c| c == '<' || c == '>');
+            } else if line.starts_with("Date:") || line.starts_with("AuthorDate:") {
+                date = Some(parse_date(Some(line))?);
+            } else if line.starts_with("CommitDate:") {
+                commit.committed = Some(parse_date(Some(line))?);
+            } else if let Some((files, inserts, deletes)) = parse_stats(line) {
+                commit.files = files;
+                commit.inserts = inserts;
+                commit.deletes = deletes;
+            }
+        }
+
+        commit.date = date.with_context(|| format!("Expected a date for commit {}", hash))?;
+        commits.push(commit);
+    }
+
+    Ok(commits)
+}
+
+/// A commit's hash, its parents when git was asked for them, and its
+/// decorations, e.g. `commit abc123 789fed (HEAD -> main)`
+fn parse_hash(line: Option<&str>) -> anyhow::Result<(String, Vec<String>, Vec<Ref>)> {
     let message = format!(
         "Expected line to parse commit hash from on input {:?} but got None",
         line
     );
 
     let line = line.context(message)?;
+
+    if let Some(rest) = line.strip_prefix("commit ") {
+        let (hashes, decorations) = match rest.split_once(" (") {
+            Some((hashes, decorations)) => (hashes, decorations.strip_suffix(')')),
+            None => (rest, Some("")),
+        };
+
+        let mut hashes = hashes.split(' ');
+
+        if let (Some(hash), Some(decorations)) = (hashes.next(), decorations) {
+            let parents: Vec<String> = hashes.map(String::from).collect();
+
+            if !hash.is_empty() && parents.iter().all(|parent| !parent.is_empty()) {
+                return Ok((hash.to_string(), parents, parse_refs(decorations)));
+            }
+        }
+    }
+
     let hash = one_match(&HASH_REGEX, line)?;
+    let captures = HASH_REGEX.captures(line);
+
+    let parents = captures
+        .as_ref()
+        .and_then(|captures| captures.get(2))
+        .map(|parents| {
+            parents
+                .as_str()
+                .split_whitespace()
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let refs = captures
+        .as_ref()
+        .and_then(|captures| captures.get(3))
+        .map(|decorations| parse_refs(decorations.as_str()))
+        .unwrap_or_default();
+
+    Ok((hash, parents, refs))
+}
 
-    Ok(hash)
+/// Whether the line starts a commit, i.e. `commit abc123`. Only these lines
+/// are flush with the start of the line and begin with `commit `.
+pub fn is_hash(line: &str) -> bool {
+    line.starts_with("commit ")
+}
+
+/// Parse decorations, e.g. `HEAD -> main, tag: v1.0, origin/main`. HEAD is
+/// left out since it's wherever the repository happened to be checked out.
+pub fn parse_refs(decorations: &str) -> Vec<Ref> {
+    decorations
+        .split(", ")
+        .filter_map(|decoration| {
+            let decoration = decoration.trim_start_matches("HEAD -> ");
+
+            if decoration.is_empty() || decoration == "HEAD" || decoration.ends_with("/HEAD") {
+                return None;
+            }
+
+            Some(match decoration.strip_prefix("tag: ") {
+                Some(tag) => Ref::new(tag.to_string(), RefKind::Tag),
+                None => Ref::new(decoration.to_string(), RefKind::Branch),
+            })
+        })
+        .collect()
+}
+
+/// Parse a merge's parents from e.g. `Merge: abc123 def456`. Returns `None`
+/// for any other line.
+fn parse_merge(line: &str) -> Option<Vec<String>> {
+    if !line.starts_with("Merge: ") {
+        return None;
+    }
+
+    let captures = MERGE_REGEX.captures(line)?;
+
+    Some(captures[1].split_whitespace().map(String::from).collect())
 }
 
 fn parse_author(line: Option<&str>) -> anyhow::Result<Author> {
@@ -132,13 +404,35 @@ fn parse_author(line: Option<&str>) -> anyhow::Result<Author> {
 
     let line = line.context(message)?;
 
+    let matched = match split_author(line) {
+        Some((name, email)) => Ok((name.to_string(), email.to_string())),
+        None => two_matches(&AUTHOR_REGEX, line),
+    };
+
     // TODO use the `newtype` idiom so destructuring the tuple is harder to get
     // wrong, i.e. confuse the order of two `String`s
-    let (name, email) = two_matches(&AUTHOR_REGEX, line)?;
-
-    let author = Author::new(name, email);
+    let (name, email) = match matched {
+        Ok((name, email)) => (
+            name.trim().to_string(),
+            email.trim_matches(|c| c == '<' || c == '>').to_string(),
+        ),
+        // Rather than fail the whole history over one odd author, keep
+        // whatever git printed
+        Err(why) => match line.strip_prefix("Author:") {
+            Some(raw) => (raw.trim().to_string(), String::new()),
+            None => return Err(why),
+        },
+    };
+
+    Ok(Author::identified(&name, &email))
+}
 
-    Ok(author)
+/// Split e.g. `Author: Jon <jon@email.ca>` into the name and email. Like the
+/// regex, the name runs up to the last ` <`.
+fn split_author(line: &str) -> Option<(&str, &str)> {
+    line.strip_prefix("Author: ")
+        .and_then(|author| author.strip_suffix('>'))
+        .and_then(|author| author.rsplit_once(" <"))
 }
 
 fn parse_date(line: Option<&str>) -> anyhow::Result<OffsetDateTime> {
@@ -148,18 +442,135 @@ fn parse_date(line: Option<&str>) -> anyhow::Result<OffsetDateTime> {
     );
 
     let line = line.context(message)?;
-    let date = one_match(&DATE_REGEX, line)?;
+    // `Date:` by default, or `AuthorDate:` and `CommitDate:` when fuller
+    let date = match ["Date:", "AuthorDate:", "CommitDate:"]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+    {
+        Some(date) if !date.is_empty() => date.to_string(),
+        _ => one_match(&DATE_REGEX, line)?,
+    };
 
     // git's output likely contains whitespace that isn't relevant to this
     // program's parsing. So far, it hasn't been cleaned/modified in any way.
     // Call `trim()` to remove such whitespace.
     let date = date.trim();
 
-    let date = OffsetDateTime::parse(date, &Rfc2822)?;
+    let date = OffsetDateTime::parse(date, &Rfc2822)
+        .or_else(|_| OffsetDateTime::parse(date, &Rfc3339))
+        .or_else(|_| OffsetDateTime::parse(date, ISO))
+        .or_else(|_| OffsetDateTime::parse(date, DEFAULT))
+        .ok()
+        .or_else(|| parse_unix(date))
+        .with_context(|| format!("Unknown date format {:?}", date))?;
 
     Ok(date)
 }
 
+/// `--date=unix` has seconds since the epoch but no offset, so dates are in UTC
+fn parse_unix(date: &str) -> Option<OffsetDateTime> {
+    let seconds = date.parse::<i64>().ok()?;
+
+    OffsetDateTime::from_unix_timestamp(seconds).ok()
+}
+
+/// Parse a line of the stat block describing a single file, e.g.
+/// ` src/parser.rs | 105 +++---`. Returns `None` for any other line.
+fn parse_file_change(line: &str) -> Option<FileChange> {
+    if !line.starts_with(char::is_whitespace) || !line.contains('|') {
+        return None;
+    }
+
+    if let Some(change) = split_file_change(line) {
+        return Some(change);
+    }
+
+    let captures = FILE_REGEX.captures(line)?;
+
+    let path = resolve_rename(&captures[1]);
+    let changes = captures[2].parse::<u32>().unwrap_or_default();
+
+    Some(FileChange::new(path, changes))
+}
+
+/// Like `FILE_REGEX`, the path ends at the first `|` with whitespace either
+/// side that's followed by a count or `Bin`
+fn split_file_change(line: &str) -> Option<FileChange> {
+    let line = line.trim_start();
+
+    for (i, _) in line.match_indices('|') {
+        let (path, changes) = (&line[..i], &line[i + 1..]);
+
+        let path_ends = path.ends_with(char::is_whitespace) && !path.trim_end().is_empty();
+        let changes_start = changes.starts_with(char::is_whitespace);
+
+        if !path_ends || !changes_start {
+            continue;
+        }
+
+        let changes = changes.trim_start();
+        let digits = changes.len()
+            - changes
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+
+        if digits > 0 || changes.starts_with("Bin") {
+            let path = resolve_rename(path.trim_end());
+            let changes = changes[..digits].parse::<u32>().unwrap_or_default();
+
+            return Some(FileChange::new(path, changes));
+        }
+    }
+
+    None
+}
+
+/// Parse the summary at the end of the stat block, e.g. `1 file changed, 43
+/// insertions(+), 62 deletions(-)`, into files, inserts, and deletes. Returns
+/// `None` for any other line, or one worded differently.
+fn parse_stats(line: &str) -> Option<(u32, u32, u32)> {
+    let mut stats = (0, 0, 0);
+
+    for part in line.trim().split(", ") {
+        let (count, what) = part.split_once(' ')?;
+        let count = count.parse::<u32>().ok()?;
+
+        if what.starts_with("file") && what.ends_with(" changed") {
+            stats.0 = count;
+        } else if what.starts_with("insertion") {
+            stats.1 = count;
+        } else if what.starts_with("deletion") {
+            stats.2 = count;
+        } else {
+            return None;
+        }
+    }
+
+    Some(stats)
+}
+
+/// git shows renames as either `old => new` or, when only part of the path
+/// changed, `src/{old => new}/file.rs`. Either way the new path is the one
+/// that matters.
+fn resolve_rename(path: &str) -> String {
+    if !path.contains(" => ") {
+        return path.to_string();
+    }
+
+    if let Some(captures) = RENAME_REGEX.captures(path) {
+        let path = format!("{}{}{}", &captures[1], &captures[3], &captures[4]);
+
+        // Moving a file up or down a directory leaves one side of the braces
+        // empty, e.g. `src/{ => report}/size.rs`
+        return path.replace("//", "/");
+    }
+
+    match path.split_once(" => ") {
+        Some((_, new)) => new.to_string(),
+        None => path.to_string(),
+    }
+}
+
 // TODO is it more idiomatic to return `usize` when I can't see a need for the
 // particular sizing? Otherwise, the restricting the return values to
 // non-negative should be sufficient.
@@ -230,6 +641,8 @@ fn two_matches(regex: &Regex, line: &str) -> anyhow::Result<(String, String)> {
 
 #[cfg(test)]
 mod tests {
+    use crate::commit::{Author, Ref, RefKind};
+
     #[test]
     fn all_stats() {
         let input = r"commit a75c00d4baa851fbd03d514cd980c999153fc21f
@@ -246,7 +659,7 @@ Date:   Mon, 28 Nov 2022 16:52:42 -0800
             Ok(commits) => {
                 assert_eq!(commits.len(), 1);
 
-                let commit = commits.get(0).unwrap();
+                let commit = commits.first().unwrap();
 
                 assert_eq!(commit.hash, "a75c00d4baa851fbd03d514cd980c999153fc21f");
                 assert_eq!(commit.author.name, "Jonathan Neufeld");
@@ -258,6 +671,197 @@ Date:   Mon, 28 Nov 2022 16:52:42 -0800
         }
     }
 
+    #[test]
+    fn fuller() {
+        let input = r"commit abc123 (HEAD -> main)
+Author:     Jon <jon@email.ca>
+AuthorDate: Mon, 28 Nov 2022 16:52:42 -0800
+Commit:     Not Jon <not.jon@email.ca>
+CommitDate: Tue, 29 Nov 2022 09:00:00 +0100
+
+    Land it
+
+ src/main.rs | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+commit def456
+Author: Jon <jon@email.ca>
+Date:   Mon, 28 Nov 2022 16:52:42 -0800
+
+    Plain
+";
+
+        let commits = super::parse(input).unwrap();
+
+        assert_eq!(commits[0].author.name, "Jon");
+        assert_eq!(commits[0].date.unix_timestamp(), 1669683162);
+        assert_eq!(commits[0].committed.unwrap().hour(), 9);
+        assert_eq!(commits[0].message, "Land it");
+        assert_eq!(commits[0].inserts, 1);
+        assert_eq!(commits[1].committed, None);
+
+        let borrowed = super::parse_borrowed(input).unwrap();
+
+        assert_eq!(borrowed[0].name, "Jon");
+        assert_eq!(borrowed[0].date, commits[0].date);
+        assert_eq!(borrowed[0].committed, commits[0].committed);
+        assert_eq!(borrowed[1].committed, None);
+    }
+
+    #[test]
+    fn offsets() {
+        let input = r"commit abc123
+Author: Jon <jon@email.ca>
+Date:   Mon, 28 Nov 2022 16:52:42 +0530
+
+    East
+
+commit def456
+Author: Jon <jon@email.ca>
+Date:   Mon, 28 Nov 2022 16:52:42 -0800
+
+    West
+";
+
+        let commits = super::parse(input).unwrap();
+        let offsets: Vec<(u8, i32)> = commits
+            .iter()
+            .map(|commit| {
+                (
+                    commit.date.hour(),
+                    commit.date.offset().whole_minutes() as i32,
+                )
+            })
+            .collect();
+
+        assert_eq!(offsets, vec![(16, 330), (16, -480)]);
+    }
+
+    #[test]
+    fn date_formats() {
+        let dates = [
+            "Mon, 28 Nov 2022 16:52:42 -0800",
+            "2022-11-28 16:52:42 -0800",
+            "2022-11-28T16:52:42-08:00",
+            "Mon Nov 28 16:52:42 2022 -0800",
+            "1669683162",
+        ];
+
+        for date in dates {
+            let line = format!("Date:   {}", date);
+            let parsed = super::parse_date(Some(&line)).unwrap();
+
+            assert_eq!(parsed.unix_timestamp(), 1669683162, "{}", date);
+        }
+
+        let offset = super::parse_date(Some("Date:   2022-11-28 16:52:42 +0530")).unwrap();
+        assert_eq!(offset.offset().whole_minutes(), 330);
+
+        assert!(super::parse_date(Some("Date:   yesterday")).is_err());
+    }
+
+    #[test]
+    fn fast_paths() {
+        let change = |line| super::split_file_change(line).unwrap();
+
+        assert_eq!(change(" src/main.rs | 12 +++---").path, "src/main.rs");
+        assert_eq!(change(" src/main.rs | 12 +++---").changes, 12);
+        assert_eq!(change(" a | b.rs | 3 +++").path, "a | b.rs");
+        assert_eq!(change(" logo.png | Bin 0 -> 12 bytes").changes, 0);
+        assert_eq!(change(" src/{a => b}/c.rs | 2 +-").path, "src/b/c.rs");
+        assert!(super::split_file_change(" a|b").is_none());
+
+        let stats = super::parse_stats;
+
+        assert_eq!(
+            stats(" 1 file changed, 43 insertions(+), 62 deletions(-)"),
+            Some((1, 43, 62))
+        );
+        assert_eq!(stats(" 2 files changed, 1 deletion(-)"), Some((2, 0, 1)));
+        assert_eq!(stats(" 3 files changed"), Some((3, 0, 0)));
+        assert_eq!(stats(""), None);
+        assert_eq!(stats(" 3 Dateien geändert"), None);
+    }
+
+    #[test]
+    fn parse_dates() {
+        let input = "abc123 2022-11-28T16:52:42-08:00\ndef456 2022-11-27T09:00:00+05:30\n";
+
+        let commits = super::parse_dates(input).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "abc123");
+        assert_eq!(commits[0].date.hour(), 16);
+        assert_eq!(commits[1].date.offset().whole_minutes(), 330);
+
+        assert!(super::parse_dates("abc123").is_err());
+    }
+
+    #[test]
+    fn parse_borrowed() {
+        let input = r"commit abc123 (HEAD -> main, tag: v1)
+Merge: a1 b2
+Author: Jon <jon@email.ca>
+Date:   Mon, 28 Nov 2022 16:52:42 -0800
+
+    Merge branch 'feature'
+
+    With a body
+
+commit def456
+Author: <anon@email.ca>
+Date:   Sun, 27 Nov 2022 09:00:00 +0000
+
+    Add things
+
+ src/main.rs | 3 ++-
+ 1 file changed, 2 insertions(+), 1 deletion(-)
+";
+
+        let borrowed = super::parse_borrowed(input).unwrap();
+        let owned = super::parse(input).unwrap();
+
+        assert_eq!(borrowed.len(), 2);
+        assert_eq!(borrowed[0].parents, "a1 b2");
+        assert_eq!(borrowed[0].subject, "Merge branch 'feature'");
+        assert_eq!(borrowed[1].deletes, 1);
+
+        for (borrowed, owned) in borrowed.into_iter().zip(owned) {
+            assert_eq!(
+                borrowed.parents.split_whitespace().collect::<Vec<_>>(),
+                owned.parents
+            );
+            let author = Author::identified(borrowed.name, borrowed.email);
+            assert_eq!(author.name, owned.author.name);
+            assert_eq!(author.email, owned.author.email);
+            assert_eq!(borrowed.date, owned.date);
+            assert_eq!(borrowed.subject, owned.subject());
+            assert_eq!(borrowed.files, owned.files);
+            assert_eq!(
+                borrowed.inserts + borrowed.deletes,
+                owned.inserts + owned.deletes
+            );
+        }
+    }
+
+    #[test]
+    fn parse_each() {
+        let lines = vec![
+            "commit abc123".to_string(),
+            "Author: Jon <jon@email.ca>".to_string(),
+            "Date:   Mon, 28 Nov 2022 16:52:42 -0800".to_string(),
+            "".to_string(),
+            "    Streamed".to_string(),
+            "".to_string(),
+            " 1 file changed, 2 insertions(+)".to_string(),
+        ];
+
+        let mut hashes = Vec::new();
+        super::parse_each(lines.into_iter(), |commit| hashes.push(commit.hash)).unwrap();
+
+        assert_eq!(hashes, vec!["abc123"]);
+    }
+
     #[test]
     fn no_deletes() {
         let input = r"commit a75
@@ -273,7 +877,7 @@ Date:   Mon, 28 Nov 2022 16:52:42 -0800
             Ok(commits) => {
                 assert_eq!(commits.len(), 1);
 
-                let commit = commits.get(0).unwrap();
+                let commit = commits.first().unwrap();
 
                 assert_eq!(commit.hash, "a75");
                 assert_eq!(commit.author.name, "Jonathan Neufeld");
@@ -300,7 +904,7 @@ Date:   Mon, 28 Nov 2022 16:52:42 -0800
             Ok(commits) => {
                 assert_eq!(commits.len(), 1);
 
-                let commit = commits.get(0).unwrap();
+                let commit = commits.first().unwrap();
 
                 assert_eq!(commit.hash, "a75");
                 assert_eq!(commit.author.name, "Jonathan Neufeld");
@@ -312,6 +916,311 @@ Date:   Mon, 28 Nov 2022 16:52:42 -0800
         }
     }
 
+    #[test]
+    fn message() {
+        let input = r"commit a75
+Author: Jonathan Neufeld <jneufeld@alumni.ubc.ca>
+Date:   Mon, 28 Nov 2022 16:52:42 -0800
+
+    Refactor parser error handling
+    
+    Errors now say which line failed.
+
+ src/parser.rs | 105 +++++++++++++++++++++++++++++++++++++++++++--------------------------------------------------------------
+ 1 file changed, 43 insertions(+), 62 deletions(-)";
+
+        match super::parse(input) {
+            Err(why) => panic!("Error parsing commit because {:?}", why),
+            Ok(commits) => {
+                let commit = commits.first().unwrap();
+
+                assert_eq!(
+                    commit.message,
+                    "Refactor parser error handling\n\nErrors now say which line failed."
+                );
+                assert_eq!(commit.subject(), "Refactor parser error handling");
+                assert_eq!(commit.body(), "Errors now say which line failed.");
+                assert_eq!(commit.inserts, 43);
+            }
+        }
+    }
+
+    #[test]
+    fn file_changes() {
+        let input = r"commit a75
+Author: Jonathan Neufeld <jneufeld@alumni.ubc.ca>
+Date:   Mon, 28 Nov 2022 16:52:42 -0800
+
+    Move things around
+
+ README.md                     |  3 +
+ docs/{old.md => new.md}       |  0
+ src/{ => report}/size.rs      | 12 +
+ logo.png                      | Bin 0 -> 1234 bytes
+ notes.txt => archive/notes.txt |  1 +
+ 5 files changed, 16 insertions(+)";
+
+        match super::parse(input) {
+            Err(why) => panic!("Error parsing commit because {:?}", why),
+            Ok(commits) => {
+                let commit = commits.first().unwrap();
+
+                let changes: Vec<(&str, u32)> = commit
+                    .file_changes
+                    .iter()
+                    .map(|change| (change.path.as_str(), change.changes))
+                    .collect();
+
+                assert_eq!(
+                    changes,
+                    vec![
+                        ("README.md", 3),
+                        ("docs/new.md", 0),
+                        ("src/report/size.rs", 12),
+                        ("logo.png", 0),
+                        ("archive/notes.txt", 1),
+                    ]
+                );
+                assert_eq!(commit.files, 5);
+                assert_eq!(commit.inserts, 16);
+            }
+        }
+    }
+
+    #[test]
+    fn decorations() {
+        let input = r"commit abc123 (HEAD -> main, tag: v1.0, origin/main, origin/HEAD)
+Author: Jon <jon@email.ca>
+Date:   Mon, 28 Nov 2022 16:52:42 -0800
+
+    Release
+
+ 1 file changed, 2 deletions(-)
+
+commit def456
+Author: Jon <jon@email.ca>
+Date:   Mon, 28 Nov 2022 12:50:02 -0800
+
+    Before release
+
+ 1 file changed, 2 deletions(-)";
+
+        match super::parse(input) {
+            Err(why) => panic!("Error parsing commit because {:?}", why),
+            Ok(commits) => {
+                let commit = commits.first().unwrap();
+
+                assert_eq!(commit.hash, "abc123");
+                assert_eq!(
+                    commit.refs,
+                    vec![
+                        Ref::new("main".to_string(), RefKind::Branch),
+                        Ref::new("v1.0".to_string(), RefKind::Tag),
+                        Ref::new("origin/main".to_string(), RefKind::Branch),
+                    ]
+                );
+
+                let commit = commits.get(1).unwrap();
+
+                assert_eq!(commit.hash, "def456");
+                assert!(commit.refs.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn merges() {
+        let input = r"commit abc123
+Author: Jon <jon@email.ca>
+Date:   Mon, 28 Nov 2022 16:52:42 -0800
+
+    Do things
+
+ 1 file changed, 2 deletions(-)
+
+commit def456
+Merge: abc123 789fed
+Author: Not Jon <notjon@email.org>
+Date:   Mon, 28 Nov 2022 12:50:02 -0800
+
+    Merge branch 'feature'
+
+ src/main.rs | 3 +++
+ 1 file changed, 3 insertions(+)
+
+commit 789fed
+Author: Jon <jon@email.ca>
+Date:   Sun, 27 Nov 2022 09:00:00 -0800
+
+    Start feature
+
+ 1 file changed, 4 insertions(+)";
+
+        match super::parse(input) {
+            Err(why) => panic!("Error parsing commit because {:?}", why),
+            Ok(commits) => {
+                let parents: Vec<Vec<String>> = commits
+                    .iter()
+                    .map(|commit| commit.parents.clone())
+                    .collect();
+
+                assert_eq!(commits.len(), 3);
+                assert_eq!(
+                    parents,
+                    vec![
+                        vec![],
+                        vec!["abc123".to_string(), "789fed".to_string()],
+                        vec![]
+                    ]
+                );
+
+                let merge = commits.get(1).unwrap();
+
+                assert_eq!(merge.hash, "def456");
+                assert_eq!(merge.author.name, "Not Jon");
+                assert_eq!(merge.subject(), "Merge branch 'feature'");
+                assert_eq!(merge.inserts, 3);
+                assert_eq!(commits.get(2).unwrap().inserts, 4);
+            }
+        }
+    }
+
+    #[test]
+    fn parents() {
+        // As git writes them with --parents, where merges still list theirs
+        // abbreviated on a line of their own
+        let input = r"commit def4567 abc1234 789fedc (HEAD -> main)
+Merge: abc123 789fed
+Author: Not Jon <notjon@email.org>
+Date:   Mon, 28 Nov 2022 12:50:02 -0800
+
+    Merge branch 'feature'
+
+commit abc1234 0123456
+Author: Jon <jon@email.ca>
+Date:   Mon, 28 Nov 2022 10:52:42 -0800
+
+    Do things
+
+ 1 file changed, 2 deletions(-)
+
+commit 0123456
+Author: Jon <jon@email.ca>
+Date:   Sun, 27 Nov 2022 09:00:00 -0800
+
+    First
+";
+
+        let commits = super::parse(input).unwrap();
+        let parents: Vec<Vec<&str>> = commits
+            .iter()
+            .map(|commit| commit.parents.iter().map(String::as_str).collect())
+            .collect();
+
+        assert_eq!(
+            parents,
+            vec![vec!["abc1234", "789fedc"], vec!["0123456"], vec![]]
+        );
+        assert_eq!(commits[0].hash, "def4567");
+        assert_eq!(commits[0].refs[0].name, "main");
+        assert_eq!(commits[0].author.name, "Not Jon");
+
+        let borrowed = super::parse_borrowed(input).unwrap();
+
+        assert_eq!(borrowed[0].parents, "abc1234 789fedc");
+        assert_eq!(borrowed[1].parents, "0123456");
+        assert_eq!(borrowed[2].parents, "");
+    }
+
+    #[test]
+    fn empty_commits() {
+        let input = r"commit abc123
+Author: Jon <jon@email.ca>
+Date:   Mon, 28 Nov 2022 16:52:42 -0800
+
+    Empty, e.g. with --allow-empty
+
+commit def456
+Merge: 789fed 111aaa
+Author: Not Jon <notjon@email.org>
+Date:   Mon, 28 Nov 2022 12:50:02 -0800
+
+    Merge without a diffstat
+
+commit 789fed
+Author: Jon <jon@email.ca>
+Date:   Sun, 27 Nov 2022 09:00:00 -0800
+
+    Change things
+
+ src/main.rs | 4 ++++
+ 1 file changed, 4 insertions(+)
+
+commit 111aaa
+Author: Jon <jon@email.ca>
+Date:   Sat, 26 Nov 2022 09:00:00 -0800
+
+    Empty and last
+";
+
+        match super::parse(input) {
+            Err(why) => panic!("Error parsing commit because {:?}", why),
+            Ok(commits) => {
+                let summary: Vec<(&str, &str, u32, u32)> = commits
+                    .iter()
+                    .map(|commit| {
+                        (
+                            commit.hash.as_str(),
+                            commit.subject(),
+                            commit.files,
+                            commit.inserts,
+                        )
+                    })
+                    .collect();
+
+                assert_eq!(
+                    summary,
+                    vec![
+                        ("abc123", "Empty, e.g. with --allow-empty", 0, 0),
+                        ("def456", "Merge without a diffstat", 0, 0),
+                        ("789fed", "Change things", 1, 4),
+                        ("111aaa", "Empty and last", 0, 0),
+                    ]
+                );
+                assert!(commits[0].file_changes.is_empty());
+                assert_eq!(commits[2].file_changes.len(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn unusual_authors() {
+        let author = |line: &str| {
+            let author = super::parse_author(Some(line)).unwrap();
+            (author.name, author.email)
+        };
+        let pair = |name: &str, email: &str| (name.to_string(), email.to_string());
+
+        assert_eq!(author("Author: Jon <>"), pair("Jon", "Jon"));
+        assert_eq!(
+            author("Author: Jon <<jon@email.ca>>"),
+            pair("Jon", "jon@email.ca")
+        );
+        assert_eq!(
+            author("Author: Jon <the> Dev <jon@email.ca>"),
+            pair("Jon <the> Dev", "jon@email.ca")
+        );
+        assert_eq!(
+            author("Author:  <jon@email.ca>"),
+            pair("jon@email.ca", "jon@email.ca")
+        );
+        assert_eq!(
+            author("Author: Jon jon@email.ca"),
+            pair("Jon jon@email.ca", "Jon jon@email.ca")
+        );
+        assert!(super::parse_author(Some("Date: whenever")).is_err());
+    }
+
     #[test]
     fn two_commits() {
         let input = r"commit abc123
@@ -335,7 +1244,7 @@ Date:   Mon, 20 Nov 2021 12:50:02 -0800
             Ok(commits) => {
                 assert_eq!(commits.len(), 2);
 
-                let commit = commits.get(0).unwrap();
+                let commit = commits.first().unwrap();
 
                 assert_eq!(commit.hash, "abc123");
                 assert_eq!(commit.author.name, "Jon");
@@ -0,0 +1,234 @@
+//! Reports from programs outside yeesh. A plugin reads commits from stdin as
+//! JSON lines, the same as `yeesh export --jsonl` writes, and writes reports
+//! to stdout in the shape `--post` sends them, or a single report on its own:
+//!
+//! ```text
+//! {"title": "Commits per ticket:", "columns": ["ticket", "commits"], "rows": [["ABC-12", 4]]}
+//! ```
+//!
+//! Strings are text, whole numbers are counts, other numbers are decimals, and
+//! null is empty. Anything it writes to stderr is passed through.
+
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use anyhow::{bail, Context};
+use serde_json::Value as Json;
+
+use crate::commit::Commit;
+use crate::export::json;
+use crate::json::SCHEMA_VERSION;
+use crate::report::{Report, Table, Value};
+
+/// Run the program with the commits on its stdin and read the reports it
+/// writes
+pub fn run(program: &Path, commits: &[Commit]) -> anyhow::Result<Vec<Report>> {
+    let name = program.display();
+
+    let mut child = Command::new(program)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("Can't run the plugin {}", name))?;
+
+    // Written from another thread so a plugin writing before it's read
+    // everything can't leave both waiting on each other
+    let mut stdin = child.stdin.take().unwrap();
+    let input = json::lines(commits);
+    let writer = thread::spawn(move || match stdin.write_all(input.as_bytes()) {
+        // Plugins needn't read every commit
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    });
+
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Can't read the plugin {}", name))?;
+
+    writer
+        .join()
+        .unwrap()
+        .with_context(|| format!("Can't write commits to the plugin {}", name))?;
+
+    if !output.status.success() {
+        bail!("The plugin {} failed with {}", name, output.status);
+    }
+
+    parse(&String::from_utf8_lossy(&output.stdout))
+        .with_context(|| format!("Can't read reports from the plugin {}", name))
+}
+
+fn parse(output: &str) -> anyhow::Result<Vec<Report>> {
+    let document: Json = serde_json::from_str(output)?;
+
+    let reports = match document.get("reports") {
+        Some(reports) => {
+            let version = document.get("schema_version").and_then(Json::as_u64);
+
+            if version.is_some_and(|version| version > SCHEMA_VERSION as u64) {
+                bail!(
+                    "Schema version {} is newer than this yeesh reads",
+                    version.unwrap()
+                );
+            }
+
+            reports.as_array().context("Expected a list of reports")?
+        }
+        None => return Ok(vec![parse_report(&document)?]),
+    };
+
+    reports
+        .iter()
+        .enumerate()
+        .map(|(i, report)| {
+            parse_report(report).with_context(|| format!("Report {} is invalid", i + 1))
+        })
+        .collect()
+}
+
+fn parse_report(report: &Json) -> anyhow::Result<Report> {
+    let title = report
+        .get("title")
+        .and_then(Json::as_str)
+        .context("Expected a title")?;
+
+    let columns = report
+        .get("columns")
+        .and_then(Json::as_array)
+        .context("Expected a list of columns")?
+        .iter()
+        .map(|column| column.as_str().context("Expected columns to be strings"))
+        .collect::<anyhow::Result<Vec<&str>>>()?;
+
+    let mut table = Table::new(&columns);
+
+    let rows = match report.get("rows") {
+        Some(rows) => rows
+            .as_array()
+            .context("Expected a list of rows")?
+            .as_slice(),
+        None => &[],
+    };
+
+    for (i, row) in rows.iter().enumerate() {
+        let row = row
+            .as_array()
+            .with_context(|| format!("Expected row {} to be a list", i + 1))?;
+
+        if row.len() != columns.len() {
+            bail!(
+                "Row {} has {} values but there are {} columns",
+                i + 1,
+                row.len(),
+                columns.len()
+            );
+        }
+
+        table.push(row.iter().map(cell).collect());
+    }
+
+    let flag = |name: &str| report.get(name).and_then(Json::as_bool).unwrap_or(false);
+
+    let mut report = Report::new(title, table);
+    report.histogram = flag("histogram");
+    report.shares = flag("shares");
+
+    Ok(report)
+}
+
+fn cell(value: &Json) -> Value {
+    match value {
+        Json::String(text) => Value::Text(text.clone()),
+        Json::Number(number) => match number.as_u64() {
+            Some(count) => Value::Count(count),
+            None => number.as_f64().map(Value::Decimal).unwrap_or(Value::Empty),
+        },
+        Json::Bool(flag) => Value::Text(flag.to_string()),
+        _ => Value::Empty,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    use crate::commit::Commit;
+    use crate::report::Value;
+
+    #[test]
+    fn parse() {
+        let reports = super::parse(
+            r#"{"schema_version": 1, "reports": [
+                {"title": "Tickets:", "columns": ["ticket", "commits", "share", "note"],
+                 "rows": [["ABC-12", 4, 0.5, null]], "histogram": true},
+                {"title": "Nothing:", "columns": []}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].title, "Tickets:");
+        assert!(reports[0].histogram);
+        assert_eq!(
+            reports[0].table.rows[0],
+            vec![
+                Value::Text("ABC-12".to_string()),
+                Value::Count(4),
+                Value::Decimal(0.5),
+                Value::Empty,
+            ]
+        );
+        assert!(reports[1].table.rows.is_empty());
+
+        let single = super::parse(r#"{"title": "One:", "columns": ["n"], "rows": [[-1]]}"#);
+        assert_eq!(single.unwrap()[0].table.rows[0][0], Value::Decimal(-1.0));
+    }
+
+    #[test]
+    fn invalid() {
+        let error = |output: &str| format!("{:#}", super::parse(output).unwrap_err());
+
+        assert_eq!(
+            error(r#"{"reports": [{"title": "A:", "columns": ["a"], "rows": [[1, 2]]}]}"#),
+            "Report 1 is invalid: Row 1 has 2 values but there are 1 columns"
+        );
+        assert_eq!(error(r#"{"columns": []}"#), "Expected a title");
+        assert_eq!(
+            error(r#"{"schema_version": 99, "reports": []}"#),
+            "Schema version 99 is newer than this yeesh reads"
+        );
+    }
+
+    #[test]
+    fn run() {
+        let dir = std::env::temp_dir().join(format!("yeesh-plugin-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let plugin = dir.join("count.sh");
+        fs::write(
+            &plugin,
+            "#!/bin/sh\n\
+            n=$(wc -l | tr -d ' ')\n\
+            echo \"{\\\"title\\\": \\\"Lines:\\\", \\\"columns\\\": [\\\"n\\\"], \\\"rows\\\": [[$n]]}\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let commits = vec![Commit::default(), Commit::default(), Commit::default()];
+        let reports = super::run(&plugin, &commits).unwrap();
+
+        assert_eq!(reports[0].table.rows[0][0], Value::Count(3));
+
+        let failing = dir.join("fail.sh");
+        fs::write(&failing, "#!/bin/sh\nexit 3\n").unwrap();
+        fs::set_permissions(&failing, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(super::run(&failing, &commits).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
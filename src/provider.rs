@@ -0,0 +1,140 @@
+pub mod github;
+pub mod gitlab;
+
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// A pull request, or whatever the hosting provider calls it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    /// The provider's username rather than git's author name
+    pub author: String,
+    pub created_at: OffsetDateTime,
+    /// `None` for pull requests closed without merging
+    pub merged_at: Option<OffsetDateTime>,
+    /// When someone other than the author first reviewed it
+    pub first_review_at: Option<OffsetDateTime>,
+    /// The commit the merge left in history, which may be a merge commit, a
+    /// squashed commit, or the last of several rebased commits
+    pub merge_commit: Option<String>,
+}
+
+impl PullRequest {
+    pub fn hours_to_merge(&self) -> Option<f64> {
+        self.merged_at
+            .map(|merged_at| hours_between(self.created_at, merged_at))
+    }
+
+    pub fn hours_to_first_review(&self) -> Option<f64> {
+        self.first_review_at
+            .map(|reviewed_at| hours_between(self.created_at, reviewed_at))
+    }
+}
+
+/// A CI pipeline run against a single commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    /// Hash of the commit the pipeline ran against
+    pub commit: String,
+    /// The provider's status, e.g. `success` or `failed`
+    pub status: String,
+    pub created_at: OffsetDateTime,
+    /// When the pipeline last changed, which is when it finished for
+    /// pipelines that aren't still running
+    pub updated_at: OffsetDateTime,
+}
+
+impl Pipeline {
+    pub fn succeeded(&self) -> bool {
+        self.status == "success"
+    }
+
+    pub fn failed(&self) -> bool {
+        self.status == "failed"
+    }
+
+    pub fn minutes(&self) -> f64 {
+        hours_between(self.created_at, self.updated_at) * 60.0
+    }
+}
+
+/// Somewhere repositories are hosted that knows more about the history than
+/// git does, e.g. who reviewed what and when
+pub trait Provider {
+    /// Up to `limit` of the most recently created pull requests that have been
+    /// closed, merged or not
+    fn pull_requests(&self, limit: usize) -> anyhow::Result<Vec<PullRequest>>;
+
+    /// Up to `limit` of the most recently created CI pipelines. Providers
+    /// without pipelines have none.
+    fn pipelines(&self, _limit: usize) -> anyhow::Result<Vec<Pipeline>> {
+        Ok(Vec::new())
+    }
+}
+
+fn hours_between(start: OffsetDateTime, end: OffsetDateTime) -> f64 {
+    (end - start).as_seconds_f64() / 3600.0
+}
+
+/// Providers' APIs all use RFC 3339 dates, or null when there isn't one
+fn parse_date(value: &serde_json::Value) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(value.as_str()?, &Rfc3339).ok()
+}
+
+/// The owner and name of a repository from its remote URL, e.g.
+/// `("jneufeld", "yeesh")` given `git@github.com:jneufeld/yeesh.git` or
+/// `https://github.com/jneufeld/yeesh`
+pub fn slug_from_url(url: &str, host: &str) -> Option<(String, String)> {
+    let path = url
+        .split_once(&format!("{}:", host))
+        .or_else(|| url.split_once(&format!("{}/", host)))?
+        .1;
+
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    let (owner, name) = path.rsplit_once('/')?;
+
+    if owner.is_empty() || name.is_empty() {
+        return None;
+    }
+
+    Some((owner.to_string(), name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    fn slug(owner: &str, name: &str) -> Option<(String, String)> {
+        Some((owner.to_string(), name.to_string()))
+    }
+
+    #[test]
+    fn slug_from_url() {
+        let from = |url| super::slug_from_url(url, "github.com");
+
+        assert_eq!(
+            from("git@github.com:jneufeld/yeesh.git"),
+            slug("jneufeld", "yeesh")
+        );
+        assert_eq!(
+            from("https://github.com/jneufeld/yeesh"),
+            slug("jneufeld", "yeesh")
+        );
+        assert_eq!(
+            from("https://github.com/jneufeld/yeesh.git/"),
+            slug("jneufeld", "yeesh")
+        );
+        assert_eq!(
+            from("ssh://git@github.com/jneufeld/yeesh"),
+            slug("jneufeld", "yeesh")
+        );
+        assert_eq!(from("https://gitlab.com/jneufeld/yeesh"), None);
+    }
+
+    #[test]
+    fn slug_from_url_with_subgroups() {
+        assert_eq!(
+            super::slug_from_url("git@gitlab.com:group/subgroup/yeesh.git", "gitlab.com"),
+            slug("group/subgroup", "yeesh")
+        );
+    }
+}
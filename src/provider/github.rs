@@ -0,0 +1,158 @@
+use anyhow::Context;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+use crate::http;
+use crate::provider::{parse_date, Provider, PullRequest};
+
+const API: &str = "https://api.github.com";
+
+/// The most GitHub returns per page
+const PER_PAGE: usize = 100;
+
+/// A repository hosted on GitHub, read through its REST API
+pub struct GitHub {
+    owner: String,
+    name: String,
+    token: String,
+}
+
+impl GitHub {
+    pub fn new(owner: String, name: String, token: String) -> GitHub {
+        GitHub { owner, name, token }
+    }
+
+    fn get(&self, path: &str) -> anyhow::Result<Value> {
+        let url = format!("{}/repos/{}/{}{}", API, self.owner, self.name, path);
+
+        let headers = [
+            format!("Authorization: Bearer {}", self.token),
+            "Accept: application/vnd.github+json".to_string(),
+            "X-GitHub-Api-Version: 2022-11-28".to_string(),
+            "User-Agent: yeesh".to_string(),
+        ];
+
+        http::get_json(&url, &headers)
+    }
+}
+
+impl Provider for GitHub {
+    /// Reviews are fetched one pull request at a time, so this makes roughly
+    /// one request per merged pull request
+    fn pull_requests(&self, limit: usize) -> anyhow::Result<Vec<PullRequest>> {
+        let mut pulls = Vec::new();
+
+        for page in 1.. {
+            let path = format!(
+                "/pulls?state=closed&sort=created&direction=desc&per_page={}&page={}",
+                PER_PAGE, page
+            );
+
+            let response = self.get(&path)?;
+            let values = response
+                .as_array()
+                .context("Expected a list of pull requests")?;
+
+            pulls.extend(values.iter().filter_map(parse_pull));
+
+            if values.len() < PER_PAGE || pulls.len() >= limit {
+                break;
+            }
+        }
+
+        pulls.truncate(limit);
+
+        for pull in pulls.iter_mut().filter(|pull| pull.merged_at.is_some()) {
+            let reviews = self.get(&format!(
+                "/pulls/{}/reviews?per_page={}",
+                pull.number, PER_PAGE
+            ))?;
+            pull.first_review_at = first_review(&reviews, &pull.author);
+        }
+
+        Ok(pulls)
+    }
+}
+
+fn parse_pull(value: &Value) -> Option<PullRequest> {
+    Some(PullRequest {
+        number: value["number"].as_u64()?,
+        title: value["title"].as_str().unwrap_or_default().to_string(),
+        author: value["user"]["login"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        created_at: parse_date(&value["created_at"])?,
+        merged_at: parse_date(&value["merged_at"]),
+        first_review_at: None,
+        merge_commit: value["merge_commit_sha"].as_str().map(String::from),
+    })
+}
+
+/// The earliest submitted review by anyone but the author. Authors replying to
+/// comments on their own pull request shows up as a review too.
+fn first_review(reviews: &Value, author: &str) -> Option<OffsetDateTime> {
+    reviews
+        .as_array()?
+        .iter()
+        .filter(|review| review["user"]["login"].as_str() != Some(author))
+        .filter_map(|review| parse_date(&review["submitted_at"]))
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    #[test]
+    fn parse_pull() {
+        let value = serde_json::json!({
+            "number": 42,
+            "title": "Add things",
+            "user": { "login": "jneufeld" },
+            "created_at": "2022-11-28T16:00:00Z",
+            "merged_at": "2022-11-29T04:00:00Z",
+            "merge_commit_sha": "abc123"
+        });
+
+        let pull = super::parse_pull(&value).unwrap();
+
+        assert_eq!(pull.number, 42);
+        assert_eq!(pull.author, "jneufeld");
+        assert_eq!(pull.merged_at, Some(datetime!(2022-11-29 04:00 UTC)));
+        assert_eq!(pull.merge_commit.as_deref(), Some("abc123"));
+        assert_eq!(pull.hours_to_merge(), Some(12.0));
+    }
+
+    #[test]
+    fn closed_without_merging() {
+        let value = serde_json::json!({
+            "number": 7,
+            "title": "Nope",
+            "user": { "login": "jneufeld" },
+            "created_at": "2022-11-28T16:00:00Z",
+            "merged_at": null,
+            "merge_commit_sha": null
+        });
+
+        let pull = super::parse_pull(&value).unwrap();
+
+        assert_eq!(pull.merged_at, None);
+        assert_eq!(pull.hours_to_merge(), None);
+    }
+
+    #[test]
+    fn first_review_ignores_author() {
+        let reviews = serde_json::json!([
+            { "user": { "login": "jneufeld" }, "submitted_at": "2022-11-28T17:00:00Z" },
+            { "user": { "login": "reviewer" }, "submitted_at": "2022-11-28T20:00:00Z" },
+            { "user": { "login": "other" }, "submitted_at": "2022-11-28T19:00:00Z" },
+            { "user": { "login": "pending" }, "submitted_at": null }
+        ]);
+
+        assert_eq!(
+            super::first_review(&reviews, "jneufeld"),
+            Some(datetime!(2022-11-28 19:00 UTC))
+        );
+    }
+}
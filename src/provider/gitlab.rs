@@ -0,0 +1,215 @@
+use anyhow::Context;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+use crate::http;
+use crate::provider::{parse_date, Pipeline, Provider, PullRequest};
+
+const API: &str = "https://gitlab.com/api/v4";
+
+/// The most GitLab returns per page
+const PER_PAGE: usize = 100;
+
+/// A project hosted on GitLab, read through its REST API. Merge requests are
+/// reported as pull requests.
+pub struct GitLab {
+    /// The project's full path, e.g. `group/subgroup/name`
+    project: String,
+    token: String,
+}
+
+impl GitLab {
+    pub fn new(owner: String, name: String, token: String) -> GitLab {
+        GitLab {
+            project: format!("{}/{}", owner, name),
+            token,
+        }
+    }
+
+    fn get(&self, path: &str) -> anyhow::Result<Value> {
+        // The project's path can stand in for its ID once encoded
+        let url = format!(
+            "{}/projects/{}{}",
+            API,
+            self.project.replace('/', "%2F"),
+            path
+        );
+
+        let headers = [
+            format!("PRIVATE-TOKEN: {}", self.token),
+            "User-Agent: yeesh".to_string(),
+        ];
+
+        http::get_json(&url, &headers)
+    }
+
+    /// Every page of a listing until there are `limit` items or no more
+    fn pages<T>(
+        &self,
+        path: &str,
+        limit: usize,
+        parse: impl Fn(&Value) -> Option<T>,
+    ) -> anyhow::Result<Vec<T>> {
+        let mut items = Vec::new();
+
+        for page in 1.. {
+            let response = self.get(&format!("{}&per_page={}&page={}", path, PER_PAGE, page))?;
+            let values = response.as_array().context("Expected a list")?;
+
+            items.extend(values.iter().filter_map(&parse));
+
+            if values.len() < PER_PAGE || items.len() >= limit {
+                break;
+            }
+        }
+
+        items.truncate(limit);
+
+        Ok(items)
+    }
+}
+
+impl Provider for GitLab {
+    /// Notes are fetched one merge request at a time, so this makes roughly
+    /// one request per merged merge request
+    fn pull_requests(&self, limit: usize) -> anyhow::Result<Vec<PullRequest>> {
+        let mut pulls = self.pages(
+            "/merge_requests?scope=all&order_by=created_at&sort=desc",
+            limit,
+            parse_merge_request,
+        )?;
+
+        for pull in pulls.iter_mut().filter(|pull| pull.merged_at.is_some()) {
+            let notes = self.get(&format!(
+                "/merge_requests/{}/notes?sort=asc&order_by=created_at&per_page={}",
+                pull.number, PER_PAGE
+            ))?;
+            pull.first_review_at = first_review(&notes, &pull.author);
+        }
+
+        Ok(pulls)
+    }
+
+    fn pipelines(&self, limit: usize) -> anyhow::Result<Vec<Pipeline>> {
+        self.pages("/pipelines?order_by=id&sort=desc", limit, parse_pipeline)
+    }
+}
+
+/// Open merge requests are skipped, matching the closed pull requests GitHub
+/// is asked for
+fn parse_merge_request(value: &Value) -> Option<PullRequest> {
+    if !matches!(value["state"].as_str(), Some("merged" | "closed")) {
+        return None;
+    }
+
+    // Squashed merge requests without a merge commit only leave the squash
+    let merge_commit = value["merge_commit_sha"]
+        .as_str()
+        .or_else(|| value["squash_commit_sha"].as_str())
+        .map(String::from);
+
+    Some(PullRequest {
+        number: value["iid"].as_u64()?,
+        title: value["title"].as_str().unwrap_or_default().to_string(),
+        author: value["author"]["username"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        created_at: parse_date(&value["created_at"])?,
+        merged_at: parse_date(&value["merged_at"]),
+        first_review_at: None,
+        merge_commit,
+    })
+}
+
+fn parse_pipeline(value: &Value) -> Option<Pipeline> {
+    Some(Pipeline {
+        commit: value["sha"].as_str()?.to_string(),
+        status: value["status"].as_str()?.to_string(),
+        created_at: parse_date(&value["created_at"])?,
+        updated_at: parse_date(&value["updated_at"])?,
+    })
+}
+
+/// GitLab has no reviews as such, so the earliest comment by anyone but the
+/// author stands in for one. System notes, e.g. "added 1 commit", are skipped.
+fn first_review(notes: &Value, author: &str) -> Option<OffsetDateTime> {
+    notes
+        .as_array()?
+        .iter()
+        .filter(|note| !note["system"].as_bool().unwrap_or_default())
+        .filter(|note| note["author"]["username"].as_str() != Some(author))
+        .filter_map(|note| parse_date(&note["created_at"]))
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    #[test]
+    fn parse_merge_request() {
+        let value = serde_json::json!({
+            "iid": 42,
+            "title": "Add things",
+            "state": "merged",
+            "author": { "username": "jneufeld" },
+            "created_at": "2022-11-28T16:00:00.000Z",
+            "merged_at": "2022-11-29T04:00:00.000Z",
+            "merge_commit_sha": null,
+            "squash_commit_sha": "abc123"
+        });
+
+        let pull = super::parse_merge_request(&value).unwrap();
+
+        assert_eq!(pull.number, 42);
+        assert_eq!(pull.author, "jneufeld");
+        assert_eq!(pull.merge_commit.as_deref(), Some("abc123"));
+        assert_eq!(pull.hours_to_merge(), Some(12.0));
+    }
+
+    #[test]
+    fn open_merge_requests_skipped() {
+        let value = serde_json::json!({
+            "iid": 7,
+            "title": "Not yet",
+            "state": "opened",
+            "author": { "username": "jneufeld" },
+            "created_at": "2022-11-28T16:00:00.000Z",
+            "merged_at": null
+        });
+
+        assert_eq!(super::parse_merge_request(&value), None);
+    }
+
+    #[test]
+    fn parse_pipeline() {
+        let value = serde_json::json!({
+            "id": 1,
+            "sha": "abc123",
+            "status": "failed",
+            "created_at": "2022-11-28T16:00:00.000Z",
+            "updated_at": "2022-11-28T16:15:00.000Z"
+        });
+
+        let pipeline = super::parse_pipeline(&value).unwrap();
+
+        assert_eq!(pipeline.commit, "abc123");
+        assert!(pipeline.failed());
+        assert_eq!(pipeline.minutes(), 15.0);
+    }
+
+    #[test]
+    fn first_review_ignores_author_and_system() {
+        let notes = serde_json::json!([
+            { "author": { "username": "jneufeld" }, "system": false, "created_at": "2022-11-28T17:00:00Z" },
+            { "author": { "username": "reviewer" }, "system": true, "created_at": "2022-11-28T18:00:00Z" },
+            { "author": { "username": "reviewer" }, "system": false, "created_at": "2022-11-28T20:00:00Z" }
+        ]);
+
+        assert_eq!(
+            super::first_review(&notes, "jneufeld"),
+            Some(datetime!(2022-11-28 20:00 UTC))
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use anyhow::{bail, Context};
+use time::OffsetDateTime;
+
+use crate::commit::Commit;
+use crate::range;
+use crate::report::group::GroupBy;
+use crate::report::{Report, Value};
+
+/// Formats a query can end with, e.g. `| json`
+pub const FORMATS: [&str; 6] = ["terminal", "slack", "mermaid", "markdown", "org", "json"];
+
+/// Reports composed from stages separated by `|`, e.g.
+///
+/// ```text
+/// since 2024 | author alice | group-by hour | top 5 | json
+/// ```
+///
+/// Stages filter commits by date or author, group them like `--group-by`,
+/// keep the groups with the most commits, or name a format to print in. Each
+/// stage is a word followed by its argument, if it takes one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub since: Option<OffsetDateTime>,
+    pub until: Option<OffsetDateTime>,
+    /// Parts of names or emails, one of which the author's has to contain,
+    /// ignoring case
+    pub authors: Vec<String>,
+    pub groups: Vec<GroupBy>,
+    /// How many groups with the most commits each report keeps
+    pub top: Option<usize>,
+    /// One of `FORMATS`, or however other reports print when not given
+    pub format: Option<String>,
+}
+
+impl Query {
+    /// Dates are relative to `now`, like `--since` and `--until`
+    pub fn parse(text: &str, now: OffsetDateTime) -> anyhow::Result<Query> {
+        let mut query = Query::default();
+
+        for stage in text.split('|') {
+            let stage = stage.trim();
+
+            let (word, argument) = match stage.split_once(char::is_whitespace) {
+                Some((word, argument)) => (word, argument.trim()),
+                None => (stage, ""),
+            };
+
+            let date = |text| range::parse(text, now).map_err(anyhow::Error::msg);
+            // Including the whole day, like --until
+            let end = |text| range::parse_until(text, now).map_err(anyhow::Error::msg);
+
+            match (word, argument) {
+                ("", _) => bail!("Expected a stage in {:?}", text),
+                ("since", _) => query.since = Some(date(argument)?),
+                ("until", _) => query.until = Some(end(argument)?),
+                ("author", "") => bail!("Expected a name or email after author"),
+                ("author", author) => query.authors.push(author.to_lowercase()),
+                ("group-by", group) => query
+                    .groups
+                    .push(group.parse().map_err(anyhow::Error::msg)?),
+                ("top", count) => {
+                    let count = count
+                        .parse()
+                        .with_context(|| format!("Expected a number after top, not {:?}", count))?;
+                    query.top = Some(count);
+                }
+                (format, "") if FORMATS.contains(&format) => {
+                    query.format = Some(format.to_string())
+                }
+                _ => bail!("Unknown stage {:?}", stage),
+            }
+        }
+
+        if query.groups.is_empty() {
+            bail!("Expected a group-by stage in {:?}", text);
+        }
+
+        Ok(query)
+    }
+
+    /// Whether the commit passes every filter
+    pub fn matches(&self, commit: &Commit) -> bool {
+        let author = || {
+            let name = commit.author.name.to_lowercase();
+            let email = commit.author.email.to_lowercase();
+
+            self.authors
+                .iter()
+                .any(|author| name.contains(author) || email.contains(author))
+        };
+
+        self.since.is_none_or(|since| commit.date >= since)
+            && self.until.is_none_or(|until| commit.date < until)
+            && (self.authors.is_empty() || author())
+    }
+
+    /// Keep the rows of each report with the most commits, in the order they
+    /// were in, when the query has a top stage. Rows without any commits are
+    /// never kept, even when there are fewer rows with some.
+    pub fn top(&self, reports: &mut [Report]) {
+        let count = match self.top {
+            Some(count) => count,
+            None => return,
+        };
+
+        for report in reports {
+            let commits = |row: &Vec<Value>| match row.last() {
+                Some(Value::Count(count)) => *count,
+                _ => 0,
+            };
+
+            let mut ranked: Vec<(usize, u64)> = report
+                .table
+                .rows
+                .iter()
+                .enumerate()
+                .filter(|(_, row)| row.last() != Some(&Value::Count(0)))
+                .map(|(i, row)| (i, commits(row)))
+                .collect();
+            ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+            let mut kept: Vec<usize> = ranked.into_iter().take(count).map(|(i, _)| i).collect();
+            kept.sort_unstable();
+
+            let rows = std::mem::take(&mut report.table.rows);
+            report.table.rows = kept.into_iter().map(|i| rows[i].clone()).collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::report::group::GroupBy;
+    use crate::report::{Report, Table, Value};
+    use crate::test_support::commit;
+
+    use super::Query;
+
+    #[test]
+    fn parse() {
+        let now = datetime!(2024-06-01 12:00 UTC);
+
+        let query = Query::parse(
+            "since 2024 | author Alice | group-by hour | top 5 | json",
+            now,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query,
+            Query {
+                since: Some(datetime!(2024-01-01 0:00 UTC)),
+                authors: vec!["alice".to_string()],
+                groups: vec![GroupBy::Hour],
+                top: Some(5),
+                format: Some("json".to_string()),
+                ..Default::default()
+            }
+        );
+
+        let query = Query::parse("until 2 weeks ago|group-by month|group-by tag", now).unwrap();
+        assert_eq!(query.until, Some(datetime!(2024-05-18 12:00 UTC)));
+        assert_eq!(query.groups, vec![GroupBy::Month, GroupBy::Tag]);
+
+        for invalid in [
+            "",
+            "author alice",
+            "group-by year",
+            "group-by hour | top five",
+            "group-by hour | since soon",
+            "group-by hour | |",
+            "group-by hour | xml",
+        ] {
+            assert!(Query::parse(invalid, now).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn matches() {
+        let query = Query {
+            since: Some(datetime!(2024-01-01 0:00 UTC)),
+            authors: vec!["alice".to_string(), "@bob.ca".to_string()],
+            ..Default::default()
+        };
+
+        let by = |name, email, date| commit().author(name, email).on(date).build();
+
+        let date = datetime!(2024-02-01 0:00 UTC);
+
+        assert!(query.matches(&by("Alice", "a@email.ca", date)));
+        assert!(query.matches(&by("Robert", "r@bob.ca", date)));
+        assert!(!query.matches(&by("Zed", "z@email.ca", date)));
+
+        let before = datetime!(2023-12-31 0:00 UTC);
+        assert!(!query.matches(&by("Alice", "a@email.ca", before)));
+    }
+
+    #[test]
+    fn top() {
+        let query = Query {
+            top: Some(2),
+            ..Default::default()
+        };
+
+        let mut table = Table::new(&["hour", "commits"]);
+        for (hour, count) in [("09", 3), ("10", 8), ("11", 1), ("12", 5), ("13", 0)] {
+            table.push(vec![Value::Text(hour.to_string()), Value::Count(count)]);
+        }
+
+        let mut reports = vec![Report::histogram("By hour:", table)];
+        query.top(&mut reports);
+
+        let hours: Vec<&Value> = reports[0].table.rows.iter().map(|row| &row[0]).collect();
+        assert_eq!(
+            hours,
+            vec![
+                &Value::Text("10".to_string()),
+                &Value::Text("12".to_string())
+            ]
+        );
+
+        let mut table = Table::new(&["hour", "commits"]);
+        for (hour, count) in [("09", 0), ("10", 2), ("11", 0)] {
+            table.push(vec![Value::Text(hour.to_string()), Value::Count(count)]);
+        }
+
+        let mut reports = vec![Report::histogram("By hour:", table)];
+        query.top(&mut reports);
+
+        assert_eq!(reports[0].table.rows.len(), 1);
+        assert_eq!(reports[0].table.rows[0][1], Value::Count(2));
+    }
+}
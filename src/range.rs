@@ -0,0 +1,253 @@
+use std::process::Command;
+use std::str;
+
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, Duration, Month, OffsetDateTime, Time, UtcOffset, Weekday};
+
+/// Calendar periods containing now, e.g. for a standup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Today,
+    /// From Monday
+    ThisWeek,
+    ThisMonth,
+}
+
+/// When the period containing `now` starts and when the next one does, at
+/// midnight in the offset of `now`
+pub fn period(period: Period, now: OffsetDateTime) -> (OffsetDateTime, OffsetDateTime) {
+    let today = now.date();
+
+    let (start, end) = match period {
+        Period::Today => (today, today + Duration::days(1)),
+        Period::ThisWeek => {
+            let monday = today - Duration::days(today.weekday().number_days_from_monday() as i64);
+            (monday, monday + Duration::weeks(1))
+        }
+        Period::ThisMonth => {
+            let first = today.replace_day(1).unwrap_or(today);
+            let days = time::util::days_in_year_month(today.year(), today.month());
+            (first, first + Duration::days(days as i64))
+        }
+    };
+
+    let midnight = |date: Date| date.with_time(Time::MIDNIGHT).assume_offset(now.offset());
+
+    (midnight(start), midnight(end))
+}
+
+/// Now in the offset of whoever's running yeesh, so e.g. `yesterday` starts at
+/// their midnight
+pub fn now() -> OffsetDateTime {
+    OffsetDateTime::now_utc().to_offset(local_offset())
+}
+
+/// The local UTC offset as `date` reports it. The `time` crate can only find
+/// it with a feature that isn't sound on every platform, and UTC is a fine
+/// fallback for a tool reporting on commits in their own offsets anyway.
+fn local_offset() -> UtcOffset {
+    Command::new("date")
+        .arg("+%z")
+        .output()
+        .ok()
+        .and_then(|output| parse_offset(str::from_utf8(&output.stdout).ok()?.trim()))
+        .unwrap_or(UtcOffset::UTC)
+}
+
+/// An offset as `date +%z` writes it, e.g. `-0800`
+fn parse_offset(offset: &str) -> Option<UtcOffset> {
+    let format = format_description!("[offset_hour sign:mandatory][offset_minute]");
+
+    UtcOffset::parse(offset, &format).ok()
+}
+
+/// A point in time as given on the command line, relative to `now`. Either a
+/// date like `2022-11-28`, a year like `2022` for its first day, an RFC 3339
+/// date and time, `now`, `today`, `yesterday`, `last friday`, or an amount of
+/// time ago like `2 weeks ago` or `an hour ago`. Days start at midnight in the
+/// offset of `now`.
+pub fn parse(text: &str, now: OffsetDateTime) -> Result<OffsetDateTime, String> {
+    parse_span(text, now).map(|(start, _)| start)
+}
+
+/// Like `parse()` but the end of a day or year given without a time, e.g. for
+/// `--until`, so `2023-06-30` is up to July 1st and includes June 30th
+pub fn parse_until(text: &str, now: OffsetDateTime) -> Result<OffsetDateTime, String> {
+    parse_span(text, now).map(|(start, end)| end.unwrap_or(start))
+}
+
+/// When the time given starts and, when it's a whole day or year, when the next
+/// one does
+fn parse_span(
+    text: &str,
+    now: OffsetDateTime,
+) -> Result<(OffsetDateTime, Option<OffsetDateTime>), String> {
+    let text = text.trim().to_lowercase();
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    let midnight = |date: Date| date.with_time(Time::MIDNIGHT).assume_offset(now.offset());
+    let day = |date: Date| (midnight(date), date.next_day().map(midnight));
+    let instant = |time: OffsetDateTime| (time, None);
+
+    let parsed = match words.as_slice() {
+        ["now"] => Some(instant(now)),
+        ["today"] => Some(day(now.date())),
+        ["yesterday"] => now.date().previous_day().map(day),
+        ["last", weekday] => weekday_named(weekday).map(|weekday| {
+            let mut date = now.date() - Duration::days(1);
+
+            while date.weekday() != weekday {
+                date -= Duration::days(1);
+            }
+
+            day(date)
+        }),
+        [amount, unit, "ago"] => ago(amount, unit, now).map(instant),
+        [year] if year.len() == 4 && year.bytes().all(|b| b.is_ascii_digit()) => {
+            year.parse().ok().and_then(|year: i32| {
+                let first = |year| Date::from_calendar_date(year, Month::January, 1).ok();
+
+                first(year).map(|date| (midnight(date), first(year + 1).map(midnight)))
+            })
+        }
+        [date] => Date::parse(date, format_description!("[year]-[month]-[day]"))
+            .map(day)
+            .or_else(|_| OffsetDateTime::parse(date, &Rfc3339).map(instant))
+            .ok(),
+        _ => None,
+    };
+
+    parsed.ok_or_else(|| format!("Unknown date {:?}", text))
+}
+
+/// E.g. `2 weeks` or `a month` before `now`. Months and years are calendar
+/// ones, so a month before March 31st is the last day of February.
+fn ago(amount: &str, unit: &str, now: OffsetDateTime) -> Option<OffsetDateTime> {
+    let amount: i64 = match amount {
+        "a" | "an" => 1,
+        amount => amount.parse().ok()?,
+    };
+
+    let duration = match unit.trim_end_matches('s') {
+        "second" => Duration::seconds(amount),
+        "minute" => Duration::minutes(amount),
+        "hour" => Duration::hours(amount),
+        "day" => Duration::days(amount),
+        "week" => Duration::weeks(amount),
+        "month" => return months_before(now, amount),
+        "year" => return months_before(now, amount * 12),
+        _ => return None,
+    };
+
+    now.checked_sub(duration)
+}
+
+fn months_before(now: OffsetDateTime, months: i64) -> Option<OffsetDateTime> {
+    let total = now.year() as i64 * 12 + now.month() as i64 - 1 - months;
+    let year = total.div_euclid(12) as i32;
+    let month = Month::try_from(total.rem_euclid(12) as u8 + 1).ok()?;
+
+    let day = now.day().min(time::util::days_in_year_month(year, month));
+
+    Some(now.replace_date(Date::from_calendar_date(year, month, day).ok()?))
+}
+
+fn weekday_named(name: &str) -> Option<Weekday> {
+    let weekdays = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    // Full names or any abbreviation of at least three letters, e.g. `fri`
+    weekdays.into_iter().find(|weekday| {
+        let full = weekday.to_string().to_lowercase();
+        name.len() >= 3 && full.starts_with(name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{datetime, offset};
+
+    use super::Period;
+
+    #[test]
+    fn parse() {
+        // A Wednesday
+        let now = datetime!(2023-03-29 15:30 -8);
+        let parse = |text| super::parse(text, now).unwrap();
+
+        assert_eq!(parse("now"), now);
+        assert_eq!(parse("today"), datetime!(2023-03-29 0:00 -8));
+        assert_eq!(parse("Yesterday"), datetime!(2023-03-28 0:00 -8));
+        assert_eq!(parse("last friday"), datetime!(2023-03-24 0:00 -8));
+        assert_eq!(parse("last wed"), datetime!(2023-03-22 0:00 -8));
+        assert_eq!(parse("2 weeks ago"), datetime!(2023-03-15 15:30 -8));
+        assert_eq!(parse("an hour ago"), datetime!(2023-03-29 14:30 -8));
+        assert_eq!(parse("1 month ago"), datetime!(2023-02-28 15:30 -8));
+        assert_eq!(parse("2 years ago"), datetime!(2021-03-29 15:30 -8));
+        assert_eq!(parse("2022-11-28"), datetime!(2022-11-28 0:00 -8));
+        assert_eq!(parse("2022"), datetime!(2022-01-01 0:00 -8));
+        assert_eq!(
+            parse("2022-11-28T16:52:42+05:30"),
+            datetime!(2022-11-28 16:52:42 +5:30)
+        );
+
+        assert!(super::parse("last tuesdays", now).is_err());
+        assert!(super::parse("soon", now).is_err());
+    }
+
+    #[test]
+    fn parse_until() {
+        let now = datetime!(2023-03-29 15:30 -8);
+        let parse = |text| super::parse_until(text, now).unwrap();
+
+        // Whole days and years are included
+        assert_eq!(parse("2023-06-30"), datetime!(2023-07-01 0:00 -8));
+        assert_eq!(parse("today"), datetime!(2023-03-30 0:00 -8));
+        assert_eq!(parse("yesterday"), datetime!(2023-03-29 0:00 -8));
+        assert_eq!(parse("last friday"), datetime!(2023-03-25 0:00 -8));
+        assert_eq!(parse("2022"), datetime!(2023-01-01 0:00 -8));
+
+        // Times are as given
+        assert_eq!(parse("now"), now);
+        assert_eq!(parse("2 weeks ago"), datetime!(2023-03-15 15:30 -8));
+        assert_eq!(
+            parse("2022-11-28T16:52:42+05:30"),
+            datetime!(2022-11-28 16:52:42 +5:30)
+        );
+    }
+
+    #[test]
+    fn period() {
+        // A Wednesday
+        let now = datetime!(2023-03-29 15:30 -8);
+        let period = |period| super::period(period, now);
+
+        assert_eq!(
+            period(Period::Today),
+            (datetime!(2023-03-29 0:00 -8), datetime!(2023-03-30 0:00 -8))
+        );
+        assert_eq!(
+            period(Period::ThisWeek),
+            (datetime!(2023-03-27 0:00 -8), datetime!(2023-04-03 0:00 -8))
+        );
+        assert_eq!(
+            period(Period::ThisMonth),
+            (datetime!(2023-03-01 0:00 -8), datetime!(2023-04-01 0:00 -8))
+        );
+    }
+
+    #[test]
+    fn parse_offset() {
+        assert_eq!(super::parse_offset("-0800"), Some(offset!(-8)));
+        assert_eq!(super::parse_offset("+0530"), Some(offset!(+5:30)));
+        assert_eq!(super::parse_offset("PST"), None);
+    }
+}
@@ -0,0 +1,306 @@
+pub mod activity;
+pub mod anomaly;
+pub mod author;
+pub mod biggest;
+pub mod branches;
+pub mod changes;
+pub mod churn_tree;
+pub mod compare;
+pub mod conventional;
+pub mod extension_hours;
+pub mod file_age;
+pub mod gitmoji;
+pub mod group;
+pub mod half_life;
+pub mod handoff;
+pub mod header;
+pub mod hygiene;
+pub mod issue;
+pub mod lag;
+pub mod list;
+pub mod merge_lead;
+pub mod newcomer;
+pub mod ownership;
+pub mod peak;
+pub mod pipeline;
+pub mod pull_request;
+pub mod release;
+pub mod repository;
+pub mod retention;
+pub mod revert;
+pub mod rolling;
+pub mod signoff;
+pub mod size;
+pub mod test_ratio;
+pub mod timezone;
+pub mod words;
+
+use std::slice;
+
+use time::Date;
+
+use crate::bar::Bars;
+use crate::commit::Commit;
+use crate::git::Tag;
+use crate::histogram::Weight;
+use crate::report::group::GroupBy;
+
+/// A titled block of output. Reports hold plain data so the same report can
+/// be printed to the terminal, served as JSON, and so on.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub title: String,
+    pub table: Table,
+    /// Histograms have a label column followed by a count column and read
+    /// best as bars rather than numbers
+    pub histogram: bool,
+    /// Shares have a label column followed by a count column whose rows are
+    /// parts of a whole, e.g. commits by author, and read well as a pie
+    pub shares: bool,
+    /// A histogram's count for each row in the period before, when comparing
+    /// with it, see `annotate()`
+    pub previous: Option<Vec<u64>>,
+}
+
+impl Report {
+    pub fn new(title: &str, table: Table) -> Report {
+        Report {
+            title: title.to_string(),
+            table,
+            histogram: false,
+            shares: false,
+            previous: None,
+        }
+    }
+
+    pub fn histogram(title: &str, table: Table) -> Report {
+        Report {
+            histogram: true,
+            ..Report::new(title, table)
+        }
+    }
+
+    pub fn shares(title: &str, table: Table) -> Report {
+        Report {
+            shares: true,
+            ..Report::new(title, table)
+        }
+    }
+
+    /// Drop a histogram's rows without any commits to compact a sparse one
+    pub fn hide_empty(&mut self) {
+        if self.histogram {
+            self.table
+                .rows
+                .retain(|row| row.last() != Some(&Value::Count(0)));
+        }
+    }
+}
+
+/// Give each histogram the counts of the same rows in the same report on the
+/// period before, matched by title and label. Rows missing before had none.
+pub fn annotate(reports: &mut [Report], previous: &[Report]) {
+    for report in reports.iter_mut().filter(|report| report.histogram) {
+        let before = match previous.iter().find(|p| p.title == report.title) {
+            Some(before) => before,
+            None => continue,
+        };
+
+        let counts = report
+            .table
+            .rows
+            .iter()
+            .map(|row| {
+                before
+                    .table
+                    .rows
+                    .iter()
+                    .find(|old| old.first() == row.first())
+                    .and_then(|old| match old.last() {
+                        Some(Value::Count(count)) => Some(*count),
+                        _ => None,
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        report.previous = Some(counts);
+    }
+}
+
+/// Makes a report's blocks from the history and its tags
+pub type Make = fn(&[Commit], &[Tag]) -> Vec<Report>;
+
+/// Each report that needs nothing but the history, by name, with default
+/// settings
+pub const REGISTRY: &[(&str, Make)] = &[
+    ("hours", |commits, _| {
+        vec![activity::hours(commits, Weight::Count)]
+    }),
+    ("weekdays", |commits, _| {
+        vec![activity::weekdays(commits, Weight::Count)]
+    }),
+    ("offsets", |commits, _| {
+        vec![activity::offsets(commits, false)]
+    }),
+    ("group-by", |commits, tags| {
+        vec![
+            group::report(commits, tags, GroupBy::Month),
+            group::report(commits, tags, GroupBy::Author),
+        ]
+    }),
+    ("sizes", |commits, _| {
+        vec![size::overall(commits), size::by_author(commits)]
+    }),
+    ("anomalies", |commits, _| {
+        vec![anomaly::report(commits, anomaly::DEFAULT_SIGMA)]
+    }),
+    ("peak", |commits, _| {
+        vec![peak::report(commits, Weight::Count)]
+    }),
+    ("hygiene", |commits, _| {
+        vec![hygiene::report(commits, &issue::DEFAULT_PATTERN)]
+    }),
+    ("reverts", |commits, _| revert::reports(commits)),
+    ("releases", release::reports),
+    ("authors", |commits, _| author::reports(commits)),
+    ("retention", |commits, _| vec![retention::report(commits)]),
+    ("lag", |commits, _| lag::reports(commits)),
+    ("signoffs", |commits, _| signoff::reports(commits)),
+    ("issues", |commits, _| {
+        issue::reports(commits, &issue::DEFAULT_PATTERN)
+    }),
+    ("gitmoji", |commits, _| gitmoji::reports(commits)),
+    ("scopes", |commits, _| conventional::reports(commits)),
+    ("words", |commits, _| vec![words::report(commits, 10)]),
+    ("rolling", |commits, _| {
+        vec![rolling::report(commits, rolling::DEFAULT_WEEKS)]
+    }),
+    ("test-ratio", |commits, _| {
+        test_ratio::reports(commits, slice::from_ref(&*test_ratio::DEFAULT_PATTERN))
+    }),
+    ("churn-tree", |commits, _| {
+        vec![churn_tree::report(
+            commits,
+            churn_tree::DEFAULT_THRESHOLD,
+            Bars::default(),
+        )]
+    }),
+    ("biggest", |commits, _| vec![biggest::report(commits, 5)]),
+    ("extension-hours", |commits, _| {
+        vec![extension_hours::report(commits, Weight::Count)]
+    }),
+];
+
+/// Of `REGISTRY`, those cheap enough to run on any repository, in the order
+/// `all()` gives them
+const CHEAP: [&str; 15] = [
+    "hours",
+    "weekdays",
+    "offsets",
+    "peak",
+    "sizes",
+    "anomalies",
+    "hygiene",
+    "rolling",
+    "reverts",
+    "releases",
+    "authors",
+    "lag",
+    "signoffs",
+    "issues",
+    "test-ratio",
+];
+
+/// Every report that's cheap enough to run on any repository with default
+/// settings, e.g. for a dashboard
+pub fn all(commits: &[Commit], tags: &[Tag]) -> Vec<Report> {
+    CHEAP
+        .iter()
+        .flat_map(|name| {
+            let (_, make) = REGISTRY.iter().find(|(named, _)| named == name).unwrap();
+            make(commits, tags)
+        })
+        .collect()
+}
+
+/// Rows of values under named columns
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    pub fn new(columns: &[&str]) -> Table {
+        Table {
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<Value>) {
+        self.rows.push(row);
+    }
+}
+
+/// A single cell of a table. Numbers are kept as numbers so formatting is left
+/// up to whatever prints them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Nothing to show, e.g. the time since the previous release for the very
+    /// first release
+    Empty,
+    Text(String),
+    Count(u64),
+    Decimal(f64),
+    /// A unitless measure between zero and one, e.g. a Gini coefficient
+    Ratio(f64),
+    /// A fraction where `1.0` is one hundred percent
+    Percent(f64),
+    /// A day, written as `--date-format` says
+    Date(Date),
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Empty => Ok(()),
+            Value::Text(text) => write!(f, "{}", text),
+            Value::Count(count) => write!(f, "{}", count),
+            Value::Decimal(decimal) => write!(f, "{:.1}", decimal),
+            Value::Ratio(ratio) => write!(f, "{:.2}", ratio),
+            Value::Percent(fraction) => write!(f, "{:.1}%", fraction * 100.0),
+            Value::Date(date) => write!(f, "{}", date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Report, Table, Value};
+
+    fn histogram(counts: &[(&str, u64)]) -> Report {
+        let mut table = Table::new(&["hour", "commits"]);
+
+        for (hour, count) in counts {
+            table.push(vec![Value::Text(hour.to_string()), Value::Count(*count)]);
+        }
+
+        Report::histogram("By hour:", table)
+    }
+
+    #[test]
+    fn annotate() {
+        let mut reports = vec![
+            histogram(&[("09", 4), ("10", 2)]),
+            Report::new("Other:", Table::new(&["a"])),
+        ];
+        let previous = vec![histogram(&[("09", 2)])];
+
+        super::annotate(&mut reports, &previous);
+
+        assert_eq!(reports[0].previous, Some(vec![2, 0]));
+        assert_eq!(reports[1].previous, None);
+    }
+}
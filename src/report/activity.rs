@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use crate::commit::Commit;
+use crate::histogram::{self, Weight, WEEKDAYS};
+use crate::report::{Report, Table, Value};
+
+/// Commits, or whatever they're weighted by, for every hour of the day
+pub fn hours(commits: &[Commit], weight: Weight) -> Report {
+    hours_of(histogram::by_hour(commits, weight), weight)
+}
+
+/// Like `hours()`, from the totals for each hour
+pub fn hours_of(totals: [u64; 24], weight: Weight) -> Report {
+    let mut table = Table::new(&["hour", weight.name()]);
+
+    for (hour, count) in totals.into_iter().enumerate() {
+        table.push(vec![
+            Value::Text(format!("{:02}", hour)),
+            Value::Count(count),
+        ]);
+    }
+
+    Report::histogram("By hour:", table)
+}
+
+/// Commits, or whatever they're weighted by, for every day of the week
+/// starting from Monday
+pub fn weekdays(commits: &[Commit], weight: Weight) -> Report {
+    weekdays_of(histogram::by_weekday(commits, weight), weight)
+}
+
+/// Like `weekdays()`, from the totals for each weekday
+pub fn weekdays_of(totals: [u64; 7], weight: Weight) -> Report {
+    let mut table = Table::new(&["weekday", weight.name()]);
+
+    for (weekday, count) in WEEKDAYS.iter().zip(totals) {
+        table.push(vec![Value::Text(weekday.to_string()), Value::Count(count)]);
+    }
+
+    Report::histogram("By weekday:", table)
+}
+
+/// Commits for every UTC offset they were made in, from west to east. Hours
+/// and weekdays are in each commit's own offset so this shows how spread out
+/// those are.
+pub fn offsets(commits: &[Commit], show_empty: bool) -> Report {
+    let mut counts: BTreeMap<i32, u64> = BTreeMap::new();
+
+    for commit in commits {
+        *counts
+            .entry(commit.date.offset().whole_seconds())
+            .or_default() += 1;
+    }
+
+    // Every whole hour between the furthest offsets seen, to show the gap
+    // between teams on either side of the world
+    if show_empty {
+        if let (Some(&west), Some(&east)) = (counts.keys().next(), counts.keys().last()) {
+            let hour = 3600;
+
+            for seconds in (west.div_euclid(hour)..=east.div_euclid(hour)).map(|h| h * hour) {
+                if seconds >= west {
+                    counts.entry(seconds).or_default();
+                }
+            }
+        }
+    }
+
+    let mut table = Table::new(&["offset", "commits"]);
+
+    for (seconds, count) in counts {
+        table.push(vec![Value::Text(offset(seconds)), Value::Count(count)]);
+    }
+
+    Report::histogram("By UTC offset:", table)
+}
+
+/// e.g. `-08:00` or `+05:30`
+pub fn offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+
+    format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::commit::Commit;
+    use crate::histogram::Weight;
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn every_hour_and_weekday() {
+        // A Monday just after midnight and a Sunday just before
+        let commits = vec![
+            commit().on(datetime!(2022-11-28 00:10 -8)).build(),
+            commit().on(datetime!(2022-12-04 23:50 -8)).build(),
+        ];
+
+        let hours = super::hours(&commits, Weight::Count);
+
+        assert!(hours.histogram);
+        assert_eq!(hours.table.rows.len(), 24);
+        assert_eq!(hours.table.rows[0][1], Value::Count(1));
+        assert_eq!(hours.table.rows[23][1], Value::Count(1));
+
+        let weekdays = super::weekdays(&commits, Weight::Count);
+
+        assert_eq!(weekdays.table.rows.len(), 7);
+        assert_eq!(weekdays.table.rows[0][0], Value::Text("Mon".to_string()));
+        assert_eq!(weekdays.table.rows[0][1], Value::Count(1));
+        assert_eq!(weekdays.table.rows[6][1], Value::Count(1));
+    }
+
+    #[test]
+    fn hide_empty() {
+        let commits = vec![commit().on(datetime!(2022-11-28 00:10 -8)).build()];
+
+        let mut hours = super::hours(&commits, Weight::Count);
+        hours.hide_empty();
+
+        assert_eq!(
+            hours.table.rows,
+            vec![vec![Value::Text("00".to_string()), Value::Count(1)]]
+        );
+    }
+
+    #[test]
+    fn offsets() {
+        let commits: Vec<Commit> = [
+            datetime!(2022-11-28 09:00 +5:30),
+            datetime!(2022-11-28 09:00 -8),
+            datetime!(2022-11-28 10:00 -8),
+        ]
+        .into_iter()
+        .map(|date| commit().on(date).build())
+        .collect();
+
+        let offsets = super::offsets(&commits, false);
+
+        assert_eq!(
+            offsets.table.rows,
+            vec![
+                vec![Value::Text("-08:00".to_string()), Value::Count(2)],
+                vec![Value::Text("+05:30".to_string()), Value::Count(1)],
+            ]
+        );
+
+        let rows = super::offsets(&commits, true).table.rows;
+
+        // Every whole hour from -08:00 to +05:00 then +05:30
+        assert_eq!(rows.len(), 15);
+        assert_eq!(
+            rows[1],
+            vec![Value::Text("-07:00".to_string()), Value::Count(0)]
+        );
+        assert_eq!(rows[14][0], Value::Text("+05:30".to_string()));
+    }
+}
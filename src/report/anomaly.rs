@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+
+use time::{Date, Duration};
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// How many preceding weeks make up the trailing average
+const TRAILING_WEEKS: usize = 8;
+
+/// Weeks without this much history are not judged at all. A handful of weeks
+/// is too few to say what normal looks like.
+const MIN_TRAILING_WEEKS: usize = 4;
+
+/// Standard deviations from the trailing average for a week to be unusual
+/// unless told otherwise
+pub const DEFAULT_SIGMA: f64 = 2.0;
+
+/// How many of a week's largest commits are listed as likely causes, largest
+/// first then by hash
+const CAUSES: usize = 3;
+
+/// A week whose commit count is unusual compared to the weeks before it
+#[derive(Debug, Clone)]
+pub struct Anomaly<'a> {
+    /// The Monday starting the week
+    pub week: Date,
+    pub commits: usize,
+    pub trailing_average: f64,
+    /// Standard deviations away from the trailing average, negative for quiet
+    /// weeks
+    pub deviation: f64,
+    /// The week's largest commits, which are most likely to explain a spike
+    pub causes: Vec<&'a Commit>,
+}
+
+/// Flag every week whose commit count is more than `sigma` standard deviations
+/// from the average of the preceding weeks. Weeks without any commits count as
+/// zero so outages show up as well as crunches.
+pub fn detect(commits: &[Commit], sigma: f64) -> Vec<Anomaly<'_>> {
+    let weeks = by_week(commits);
+    let counts: Vec<usize> = weeks.iter().map(|(_, commits)| commits.len()).collect();
+
+    let mut anomalies = Vec::new();
+
+    for (i, (week, week_commits)) in weeks.iter().enumerate() {
+        let trailing = &counts[i.saturating_sub(TRAILING_WEEKS)..i];
+
+        if trailing.len() < MIN_TRAILING_WEEKS {
+            continue;
+        }
+
+        let (mean, std_dev) = mean_and_std_dev(trailing);
+
+        // When every trailing week is identical there is no spread to measure
+        // against and any change at all would be infinitely unusual
+        if std_dev == 0.0 {
+            continue;
+        }
+
+        let deviation = (week_commits.len() as f64 - mean) / std_dev;
+
+        if deviation.abs() <= sigma {
+            continue;
+        }
+
+        let mut causes = week_commits.clone();
+        causes.sort_by(|a, b| b.churn().cmp(&a.churn()).then(a.hash.cmp(&b.hash)));
+        causes.truncate(CAUSES);
+
+        anomalies.push(Anomaly {
+            week: *week,
+            commits: week_commits.len(),
+            trailing_average: mean,
+            deviation,
+            causes,
+        });
+    }
+
+    anomalies
+}
+
+pub fn report(commits: &[Commit], sigma: f64) -> Report {
+    let mut table = Table::new(&["week", "commits", "average", "sigma", "likely causes"]);
+
+    for anomaly in detect(commits, sigma) {
+        let causes: Vec<String> = anomaly
+            .causes
+            .iter()
+            .map(|commit| format!("{} ({})", short_hash(&commit.hash), commit.author.name))
+            .collect();
+
+        table.push(vec![
+            Value::Text(anomaly.week.to_string()),
+            Value::Count(anomaly.commits as u64),
+            Value::Decimal(anomaly.trailing_average),
+            Value::Decimal(anomaly.deviation),
+            Value::Text(causes.join(", ")),
+        ]);
+    }
+
+    Report::new("Unusual weeks:", table)
+}
+
+/// Group commits by the Monday of their week, filling in any empty weeks
+/// between the first and last commit
+fn by_week(commits: &[Commit]) -> Vec<(Date, Vec<&Commit>)> {
+    let mut weeks: BTreeMap<Date, Vec<&Commit>> = BTreeMap::new();
+
+    for commit in commits {
+        weeks.entry(monday_of(commit)).or_default().push(commit);
+    }
+
+    let (first, last) = match (weeks.keys().next(), weeks.keys().next_back()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Vec::new(),
+    };
+
+    let mut week = first;
+
+    while week < last {
+        week += Duration::weeks(1);
+        weeks.entry(week).or_default();
+    }
+
+    weeks.into_iter().collect()
+}
+
+/// The Monday starting the week of the commit, in its author's time
+pub fn monday_of(commit: &Commit) -> Date {
+    let date = commit.date.date();
+    let days_since_monday = date.weekday().number_days_from_monday();
+
+    date - Duration::days(days_since_monday as i64)
+}
+
+fn mean_and_std_dev(counts: &[usize]) -> (f64, f64) {
+    let n = counts.len() as f64;
+    let mean = counts.iter().sum::<usize>() as f64 / n;
+
+    let variance = counts
+        .iter()
+        .map(|count| (*count as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+
+    (mean, variance.sqrt())
+}
+
+fn short_hash(hash: &str) -> &str {
+    hash.get(..7).unwrap_or(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+    use time::Duration;
+
+    use crate::commit::Commit;
+    use crate::test_support::commit;
+
+    /// Ten weeks alternating between one and two commits, then a final week
+    /// with the given number of commits
+    fn history(last_week: u32) -> Vec<Commit> {
+        let start = datetime!(2022-11-07 12:00 -8);
+        let mut commits = Vec::new();
+
+        for week in 0..10 {
+            for _ in 0..(1 + week % 2) {
+                commits.push(
+                    commit()
+                        .hash("1")
+                        .on(start + Duration::weeks(week))
+                        .lines(1, 0)
+                        .build(),
+                );
+            }
+        }
+
+        for size in 0..last_week {
+            commits.push(
+                commit()
+                    .hash(&size.to_string())
+                    .on(start + Duration::weeks(10))
+                    .lines(size, 0)
+                    .build(),
+            );
+        }
+
+        commits
+    }
+
+    #[test]
+    fn steady_history_has_no_anomalies() {
+        let commits = history(2);
+
+        assert!(super::detect(&commits, 2.0).is_empty());
+    }
+
+    #[test]
+    fn spike_is_flagged_with_largest_commits() {
+        let commits = history(12);
+
+        let anomalies = super::detect(&commits, 2.0);
+
+        assert_eq!(anomalies.len(), 1);
+
+        let anomaly = &anomalies[0];
+
+        assert_eq!(anomaly.week, date!(2023 - 01 - 16));
+        assert_eq!(anomaly.commits, 12);
+        assert_eq!(anomaly.trailing_average, 1.5);
+        assert!(anomaly.deviation > 2.0);
+
+        let causes: Vec<&str> = anomaly.causes.iter().map(|c| c.hash.as_str()).collect();
+        assert_eq!(causes, vec!["11", "10", "9"]);
+    }
+
+    #[test]
+    fn empty_week_is_flagged() {
+        let start = datetime!(2022-11-07 12:00 -8);
+        let mut commits = Vec::new();
+
+        for week in 0..8 {
+            for _ in 0..(4 + week % 2) {
+                commits.push(
+                    commit()
+                        .hash("1")
+                        .on(start + Duration::weeks(week))
+                        .lines(1, 0)
+                        .build(),
+                );
+            }
+        }
+
+        // Skip a week entirely then resume
+        commits.push(
+            commit()
+                .hash("1")
+                .on(start + Duration::weeks(9))
+                .lines(1, 0)
+                .build(),
+        );
+
+        let anomalies = super::detect(&commits, 2.0);
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].week, date!(2023 - 01 - 02));
+        assert_eq!(anomalies[0].commits, 0);
+        assert!(anomalies[0].deviation < -2.0);
+        assert!(anomalies[0].causes.is_empty());
+    }
+}
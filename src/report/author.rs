@@ -0,0 +1,131 @@
+use crate::commit::{self, Commit};
+use crate::report::{Report, Table, Value};
+use crate::stats::gini;
+
+/// Fraction of authors whose share of the total is reported alongside the Gini
+/// coefficient, i.e. the top ten percent
+const TOP_FRACTION: f64 = 0.1;
+
+/// Totals for a single author
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contributions<'a> {
+    pub name: &'a str,
+    pub commits: u64,
+    pub inserts: u64,
+    pub deletes: u64,
+}
+
+impl Contributions<'_> {
+    pub fn churn(&self) -> u64 {
+        self.inserts + self.deletes
+    }
+}
+
+/// Every author's totals, most commits first then by name
+pub fn contributions(commits: &[Commit]) -> Vec<Contributions<'_>> {
+    let mut authors: Vec<Contributions> = commit::by_author(commits)
+        .into_iter()
+        .map(|(author, commits)| Contributions {
+            name: &author.name,
+            commits: commits.len() as u64,
+            inserts: commits.iter().map(|commit| commit.inserts as u64).sum(),
+            deletes: commits.iter().map(|commit| commit.deletes as u64).sum(),
+        })
+        .collect();
+
+    authors.sort_by(|a, b| b.commits.cmp(&a.commits).then(a.name.cmp(b.name)));
+
+    authors
+}
+
+/// Authors ranked by commits followed by how evenly the work is spread across
+/// them
+pub fn reports(commits: &[Commit]) -> Vec<Report> {
+    let authors = contributions(commits);
+
+    vec![leaderboard(&authors, commits.len()), inequality(&authors)]
+}
+
+fn leaderboard(authors: &[Contributions], total: usize) -> Report {
+    let mut table = Table::new(&["author", "commits", "inserts", "deletes", "share"]);
+
+    for author in authors {
+        table.push(vec![
+            Value::Text(author.name.to_string()),
+            Value::Count(author.commits),
+            Value::Count(author.inserts),
+            Value::Count(author.deletes),
+            Value::Percent(author.commits as f64 / total.max(1) as f64),
+        ]);
+    }
+
+    Report::shares("Authors:", table)
+}
+
+/// How unevenly commits and churn are spread across authors, see `gini()`
+fn inequality(authors: &[Contributions]) -> Report {
+    let commits: Vec<u64> = authors.iter().map(|author| author.commits).collect();
+    let churn: Vec<u64> = authors.iter().map(Contributions::churn).collect();
+
+    let mut table = Table::new(&["measure", "gini", "top 10% share"]);
+
+    for (measure, values) in [("commits", commits), ("churn", churn)] {
+        table.push(vec![
+            Value::Text(measure.to_string()),
+            Value::Ratio(gini(&values)),
+            Value::Percent(top_share(&values, TOP_FRACTION)),
+        ]);
+    }
+
+    Report::new("Contribution inequality:", table)
+}
+
+/// Share of the total held by the largest `fraction` of values, rounding up so
+/// there's always at least one
+pub fn top_share(values: &[u64], fraction: f64) -> f64 {
+    let mut values = values.to_vec();
+    values.sort_unstable_by(|a, b| b.cmp(a));
+
+    let total: u64 = values.iter().sum();
+
+    if total == 0 {
+        return 0.0;
+    }
+
+    let top = (values.len() as f64 * fraction).ceil() as usize;
+    let top_total: u64 = values.iter().take(top).sum();
+
+    top_total as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::commit;
+
+    #[test]
+    fn top_share() {
+        let values: Vec<u64> = (1..=20).collect();
+
+        // The top two of twenty values are 20 and 19 out of 210 in total
+        assert!((super::top_share(&values, 0.1) - 39.0 / 210.0).abs() < 1e-9);
+        assert_eq!(super::top_share(&[3], 0.1), 1.0);
+        assert_eq!(super::top_share(&[0, 0], 0.1), 0.0);
+    }
+
+    #[test]
+    fn contributions() {
+        let commits = vec![
+            commit().by("Jon").lines(1, 0).build(),
+            commit().by("Not Jon").lines(2, 0).build(),
+            commit().by("Not Jon").lines(3, 0).build(),
+        ];
+
+        let authors = super::contributions(&commits);
+
+        assert_eq!(authors.len(), 2);
+        assert_eq!(authors[0].name, "Not Jon");
+        assert_eq!(authors[0].commits, 2);
+        assert_eq!(authors[0].churn(), 5);
+        assert_eq!(authors[1].name, "Jon");
+    }
+}
@@ -0,0 +1,87 @@
+use std::cmp::Reverse;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// How many commits are listed unless asked for more or fewer
+pub const DEFAULT_COUNT: usize = 10;
+
+/// The `count` commits changing the most lines, largest first, e.g. to find
+/// vendored dependencies or generated files bloating the history
+pub fn report(commits: &[Commit], count: usize) -> Report {
+    let mut biggest: Vec<&Commit> = commits.iter().collect();
+    biggest.sort_by_key(|commit| (Reverse(commit.churn()), &commit.hash));
+    biggest.truncate(count);
+
+    let mut table = Table::new(&[
+        "commit",
+        "author",
+        "date",
+        "subject",
+        "files",
+        "inserts",
+        "deletes",
+        "lines changed",
+    ]);
+
+    for commit in biggest {
+        table.push(vec![
+            Value::Text(commit.hash.chars().take(8).collect()),
+            Value::Text(commit.author.name.clone()),
+            Value::Date(commit.date.date()),
+            Value::Text(commit.subject().to_string()),
+            Value::Count(commit.files as u64),
+            Value::Count(commit.inserts as u64),
+            Value::Count(commit.deletes as u64),
+            Value::Count(commit.churn()),
+        ]);
+    }
+
+    Report::new("Largest commits:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn largest_first() {
+        let commits = vec![
+            commit()
+                .hash("aaaaaaaaaa")
+                .message("Commit aaaaaaaaaa")
+                .files(1)
+                .lines(1, 1)
+                .build(),
+            commit()
+                .hash("bbbbbbbbbb")
+                .message("Commit bbbbbbbbbb")
+                .files(1)
+                .lines(5000, 0)
+                .build(),
+            commit()
+                .hash("cccccccccc")
+                .message("Commit cccccccccc")
+                .files(1)
+                .lines(10, 20)
+                .build(),
+            commit()
+                .hash("dddddddddd")
+                .message("Commit dddddddddd")
+                .files(1)
+                .lines(0, 30)
+                .build(),
+        ];
+
+        let report = super::report(&commits, 3);
+        let rows = &report.table.rows;
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0], Value::Text("bbbbbbbb".to_string()));
+        assert_eq!(rows[0][3], Value::Text("Commit bbbbbbbbbb".to_string()));
+        assert_eq!(rows[0][7], Value::Count(5000));
+        assert_eq!(rows[1][0], Value::Text("cccccccc".to_string()));
+        assert_eq!(rows[2][0], Value::Text("dddddddd".to_string()));
+    }
+}
@@ -0,0 +1,76 @@
+use time::OffsetDateTime;
+
+use crate::git::Branch;
+use crate::report::{Report, Table, Value};
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+/// Every branch with how long ago its latest commit was made, by whom, and how
+/// far it's diverged from `base`, the stalest first so abandoned branches
+/// stand out
+pub fn report(branches: &[Branch], base: &str, now: i64) -> Report {
+    let mut branches: Vec<&Branch> = branches.iter().collect();
+    branches.sort_by(|a, b| a.time.cmp(&b.time).then(a.name.cmp(&b.name)));
+
+    let mut table = Table::new(&["branch", "last commit", "days", "author", "ahead", "behind"]);
+
+    for branch in branches {
+        let date = OffsetDateTime::from_unix_timestamp(branch.time)
+            .map(|date| Value::Date(date.date()))
+            .unwrap_or(Value::Empty);
+
+        table.push(vec![
+            Value::Text(branch.name.clone()),
+            date,
+            // Clocks are wrong sometimes and commits from the future are new
+            Value::Count(((now - branch.time).max(0) / SECONDS_PER_DAY) as u64),
+            Value::Text(branch.author.clone()),
+            Value::Count(branch.ahead),
+            Value::Count(branch.behind),
+        ]);
+    }
+
+    Report::new(&format!("Branches compared with {}:", base), table)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use crate::git::Branch;
+    use crate::report::Value;
+
+    fn branch(name: &str, time: i64, ahead: u64, behind: u64) -> Branch {
+        Branch {
+            name: name.to_string(),
+            time,
+            author: "Jon".to_string(),
+            ahead,
+            behind,
+        }
+    }
+
+    #[test]
+    fn stalest_first() {
+        let branches = vec![
+            branch("fix/parser", 1669680000, 2, 0),
+            branch("origin/old", 1600000000, 12, 340),
+        ];
+
+        let report = super::report(&branches, "main", 1669680000 + 86400 * 3);
+
+        assert_eq!(report.title, "Branches compared with main:");
+        assert_eq!(
+            report.table.rows[0],
+            vec![
+                Value::Text("origin/old".to_string()),
+                Value::Date(date!(2020 - 09 - 13)),
+                Value::Count(809),
+                Value::Text("Jon".to_string()),
+                Value::Count(12),
+                Value::Count(340),
+            ]
+        );
+        assert_eq!(report.table.rows[1][2], Value::Count(3));
+    }
+}
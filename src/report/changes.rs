@@ -0,0 +1,229 @@
+use std::collections::BTreeSet;
+
+use serde_json::Value as Json;
+use time::macros::format_description;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::commit::{self, Commit};
+use crate::json::string;
+use crate::report::{Report, Table, Value};
+
+/// What a run remembers for the next run to compare against
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    /// Always UTC
+    pub taken_at: OffsetDateTime,
+    pub commits: u64,
+    pub churn: u64,
+    pub authors: BTreeSet<String>,
+    /// Fraction of commits made from 22:00 until 06:00 in the author's time
+    pub late_night: f64,
+    /// Fraction of commits made on Saturday or Sunday
+    pub weekend: f64,
+}
+
+impl Snapshot {
+    pub fn of(commits: &[Commit], taken_at: OffsetDateTime) -> Snapshot {
+        let share = |f: fn(&Commit) -> bool| {
+            commits.iter().filter(|commit| f(commit)).count() as f64 / commits.len().max(1) as f64
+        };
+
+        Snapshot {
+            taken_at,
+            commits: commits.len() as u64,
+            churn: commits.iter().map(Commit::churn).sum(),
+            authors: commit::by_author(commits)
+                .into_iter()
+                .map(|(author, _)| author.name.clone())
+                .collect(),
+            late_night: share(|commit| commit.date.hour() >= 22 || commit.date.hour() < 6),
+            weekend: share(|commit| commit.date.weekday().number_from_monday() >= 6),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let authors: Vec<String> = self.authors.iter().map(|a| string(a)).collect();
+
+        format!(
+            "{{\"taken_at\":{},\"commits\":{},\"churn\":{},\"authors\":[{}],\"late_night\":{},\"weekend\":{}}}\n",
+            string(&self.taken_at.format(&Rfc3339).unwrap_or_default()),
+            self.commits,
+            self.churn,
+            authors.join(","),
+            self.late_night,
+            self.weekend
+        )
+    }
+
+    /// `None` for snapshots that can't be read, e.g. written by an older
+    /// version, which are treated like there was no previous run
+    pub fn from_json(text: &str) -> Option<Snapshot> {
+        let json: Json = serde_json::from_str(text).ok()?;
+
+        Some(Snapshot {
+            taken_at: OffsetDateTime::parse(json["taken_at"].as_str()?, &Rfc3339).ok()?,
+            commits: json["commits"].as_u64()?,
+            churn: json["churn"].as_u64()?,
+            authors: json["authors"]
+                .as_array()?
+                .iter()
+                .filter_map(|author| author.as_str().map(String::from))
+                .collect(),
+            late_night: json["late_night"].as_f64()?,
+            weekend: json["weekend"].as_f64()?,
+        })
+    }
+}
+
+/// What changed between the previous run's snapshot and now, e.g.
+/// "+37 commits" or "late-night share up 4pts"
+pub fn report(previous: Option<&Snapshot>, current: &Snapshot) -> Report {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => {
+            let mut table = Table::new(&["change"]);
+            table.push(vec![Value::Text(
+                "no previous run, the next run will compare against this one".to_string(),
+            )]);
+            return Report::new("Changes:", table);
+        }
+    };
+
+    let mut changes = vec![
+        format!(
+            "{:+} commits",
+            current.commits as i64 - previous.commits as i64
+        ),
+        format!(
+            "{:+} lines changed",
+            current.churn as i64 - previous.churn as i64
+        ),
+    ];
+
+    for author in current.authors.difference(&previous.authors) {
+        changes.push(format!("new contributor: {}", author));
+    }
+
+    changes.extend(points(
+        "late-night share",
+        previous.late_night,
+        current.late_night,
+    ));
+    changes.extend(points("weekend share", previous.weekend, current.weekend));
+
+    let mut table = Table::new(&["change"]);
+
+    for change in changes {
+        table.push(vec![Value::Text(change)]);
+    }
+
+    let since = previous
+        .taken_at
+        .format(format_description!(
+            "[year]-[month]-[day] [hour]:[minute] UTC"
+        ))
+        .unwrap_or_default();
+
+    Report::new(&format!("Changes since {}:", since), table)
+}
+
+/// A share's change in percentage points, when it changed by at least one
+fn points(name: &str, previous: f64, current: f64) -> Option<String> {
+    let points = ((current - previous) * 100.0).round();
+
+    if points >= 1.0 {
+        Some(format!("{} up {}pts", name, points))
+    } else if points <= -1.0 {
+        Some(format!("{} down {}pts", name, -points))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    use super::Snapshot;
+
+    #[test]
+    fn snapshot_round_trip() {
+        let commits = vec![
+            commit()
+                .by("Jon")
+                .on(datetime!(2022-11-26 23:00 -8))
+                .lines(2, 0)
+                .build(),
+            commit()
+                .by("Not Jon")
+                .on(datetime!(2022-11-28 12:00 -8))
+                .lines(2, 0)
+                .build(),
+        ];
+
+        let snapshot = Snapshot::of(&commits, datetime!(2022-12-01 0:00 UTC));
+
+        assert_eq!(snapshot.commits, 2);
+        assert_eq!(snapshot.churn, 4);
+        assert_eq!(snapshot.late_night, 0.5);
+        assert_eq!(snapshot.weekend, 0.5);
+        assert_eq!(Snapshot::from_json(&snapshot.to_json()), Some(snapshot));
+        assert_eq!(Snapshot::from_json("{}"), None);
+    }
+
+    #[test]
+    fn changes() {
+        let before = vec![commit()
+            .by("Jon")
+            .on(datetime!(2022-11-28 12:00 -8))
+            .lines(2, 0)
+            .build()];
+        let after = vec![
+            commit()
+                .by("alice")
+                .on(datetime!(2022-11-29 23:00 -8))
+                .lines(2, 0)
+                .build(),
+            commit()
+                .by("Jon")
+                .on(datetime!(2022-11-28 12:00 -8))
+                .lines(2, 0)
+                .build(),
+        ];
+
+        let previous = Snapshot::of(&before, datetime!(2022-11-28 13:00 UTC));
+        let current = Snapshot::of(&after, datetime!(2022-11-30 0:00 UTC));
+
+        let report = super::report(Some(&previous), &current);
+        let changes: Vec<String> = report
+            .table
+            .rows
+            .iter()
+            .map(|row| row[0].to_string())
+            .collect();
+
+        assert_eq!(report.title, "Changes since 2022-11-28 13:00 UTC:");
+        assert_eq!(
+            changes,
+            vec![
+                "+1 commits",
+                "+2 lines changed",
+                "new contributor: alice",
+                "late-night share up 50pts",
+            ]
+        );
+    }
+
+    #[test]
+    fn first_run() {
+        let current = Snapshot::of(&[], datetime!(2022-11-30 0:00 UTC));
+        let report = super::report(None, &current);
+
+        assert!(
+            matches!(&report.table.rows[0][0], Value::Text(text) if text.starts_with("no previous run"))
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use crate::bar::Bars;
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// Percent of all lines changed a directory needs to be expanded by default
+pub const DEFAULT_THRESHOLD: f64 = 2.0;
+
+/// Characters in the bar of the directory with the most lines changed
+const BAR_WIDTH: usize = 30;
+
+/// Lines changed under a directory and in each of its subdirectories
+#[derive(Debug, Default)]
+struct Node<'a> {
+    churn: u64,
+    children: BTreeMap<&'a str, Node<'a>>,
+}
+
+/// Lines changed per directory as an indented tree, each directory with its
+/// share of every line changed and a bar. Subdirectories with less than
+/// `threshold` percent of the lines are collapsed into a single row under
+/// their parent, e.g. `(4 more)`, and files are counted in the directory
+/// they're in.
+pub fn report(commits: &[Commit], threshold: f64, bars: Bars) -> Report {
+    let mut root = Node::default();
+
+    for commit in commits {
+        for change in &commit.file_changes {
+            let mut dirs: Vec<&str> = change.path.split('/').collect();
+            dirs.pop();
+
+            let churn = change.changes as u64;
+            let mut node = &mut root;
+            node.churn += churn;
+
+            for dir in dirs {
+                node = node.children.entry(dir).or_default();
+                node.churn += churn;
+            }
+        }
+    }
+
+    let mut rows = Vec::new();
+    flatten(&root, 0, root.churn, threshold, &mut rows);
+
+    let max = rows
+        .iter()
+        .map(|(_, churn)| *churn)
+        .max()
+        .unwrap_or_default();
+    let total = root.churn.max(1) as f64;
+
+    // The bar comes after the name, count, and share so leave room for them
+    let prefix = rows
+        .iter()
+        .map(|(name, churn)| name.chars().count() + churn.to_string().len() + 12)
+        .max()
+        .unwrap_or_default();
+
+    let mut table = Table::new(&["directory", "lines changed", "share", "bar"]);
+
+    for (name, churn) in rows {
+        let width = bars.width(churn, max, prefix, Some(BAR_WIDTH));
+
+        table.push(vec![
+            Value::Text(name),
+            Value::Count(churn),
+            Value::Percent(churn as f64 / total),
+            Value::Text("█".repeat(width)),
+        ]);
+    }
+
+    Report::new("Lines changed by directory:", table)
+}
+
+/// A row for each of the node's subdirectories big enough to show, each
+/// followed by its own, largest first
+fn flatten(node: &Node, depth: usize, total: u64, threshold: f64, rows: &mut Vec<(String, u64)>) {
+    let mut children: Vec<(&&str, &Node)> = node.children.iter().collect();
+    children.sort_by(|a, b| b.1.churn.cmp(&a.1.churn).then(a.0.cmp(b.0)));
+
+    let indent = "  ".repeat(depth);
+    let (mut collapsed, mut collapsed_churn) = (0, 0);
+
+    for (name, child) in children {
+        let percent = child.churn as f64 * 100.0 / total.max(1) as f64;
+
+        if percent < threshold {
+            collapsed += 1;
+            collapsed_churn += child.churn;
+            continue;
+        }
+
+        rows.push((format!("{}{}/", indent, name), child.churn));
+        flatten(child, depth + 1, total, threshold, rows);
+    }
+
+    if collapsed > 0 {
+        rows.push((format!("{}({} more)", indent, collapsed), collapsed_churn));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bar::Bars;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn tree() {
+        let commits = vec![
+            commit()
+                .changes(&[("src/main.rs", 40), ("src/report/tree.rs", 30)])
+                .build(),
+            commit()
+                .changes(&[("docs/guide.md", 20), ("README.md", 5)])
+                .build(),
+            commit()
+                .changes(&[("src/bin/tiny.rs", 2), ("tests/a/b.rs", 3)])
+                .build(),
+        ];
+
+        let report = super::report(&commits, 10.0, Bars::default());
+        let rows: Vec<(Value, Value)> = report
+            .table
+            .rows
+            .iter()
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect();
+
+        let row = |name: &str, churn| (Value::Text(name.to_string()), Value::Count(churn));
+
+        assert_eq!(
+            rows,
+            vec![
+                row("src/", 72),
+                row("  report/", 30),
+                row("  (1 more)", 2),
+                row("docs/", 20),
+                row("(1 more)", 3),
+            ]
+        );
+
+        assert_eq!(report.table.rows[0][2], Value::Percent(0.72));
+        assert_eq!(report.table.rows[0][3], Value::Text("█".repeat(30)));
+        assert_eq!(report.table.rows[1][3], Value::Text("█".repeat(13)));
+    }
+}
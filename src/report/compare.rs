@@ -0,0 +1,191 @@
+use time::OffsetDateTime;
+
+use crate::commit::Commit;
+use crate::histogram::{self, Weight, WEEKDAYS};
+use crate::report::changes::Snapshot;
+use crate::report::{Report, Table, Value};
+use crate::stats::median;
+
+/// Headline metrics and activity by hour and weekday for several repositories
+/// side by side. Counts are per contributor so a large team and a small one
+/// can be compared.
+pub fn reports(repos: &[(&str, &[Commit])]) -> Vec<Report> {
+    vec![headlines(repos), hours(repos), weekdays(repos)]
+}
+
+const HEADLINES: [&str; 7] = [
+    "commits",
+    "contributors",
+    "commits per contributor",
+    "lines changed per contributor",
+    "median lines per commit",
+    "late-night share",
+    "weekend share",
+];
+
+fn headlines(repos: &[(&str, &[Commit])]) -> Report {
+    let mut table = columns("measure", repos);
+
+    let values: Vec<[Value; 7]> = repos
+        .iter()
+        .map(|(_, commits)| headline_values(commits))
+        .collect();
+
+    for (i, name) in HEADLINES.iter().enumerate() {
+        let mut row = vec![Value::Text(name.to_string())];
+        row.extend(values.iter().map(|values| values[i].clone()));
+        table.push(row);
+    }
+
+    Report::new("Compared:", table)
+}
+
+/// A repository's value for each of `HEADLINES`
+fn headline_values(commits: &[Commit]) -> [Value; 7] {
+    let snapshot = Snapshot::of(commits, OffsetDateTime::UNIX_EPOCH);
+    let contributors = snapshot.authors.len().max(1) as f64;
+    let sizes = commits.iter().map(|commit| commit.churn() as f64).collect();
+
+    [
+        Value::Count(snapshot.commits),
+        Value::Count(snapshot.authors.len() as u64),
+        Value::Decimal(snapshot.commits as f64 / contributors),
+        Value::Decimal(snapshot.churn as f64 / contributors),
+        median(sizes).map_or(Value::Empty, Value::Decimal),
+        Value::Percent(snapshot.late_night),
+        Value::Percent(snapshot.weekend),
+    ]
+}
+
+fn hours(repos: &[(&str, &[Commit])]) -> Report {
+    let counts = by_weekday_and_hour(repos);
+    let mut table = columns("hour", repos);
+
+    for hour in 0..24 {
+        let mut row = vec![Value::Text(format!("{:02}", hour))];
+
+        for (contributors, counts) in &counts {
+            let count: u64 = counts.iter().map(|day| day[hour]).sum();
+            row.push(Value::Decimal(count as f64 / contributors));
+        }
+
+        table.push(row);
+    }
+
+    Report::new("Commits per contributor by hour:", table)
+}
+
+fn weekdays(repos: &[(&str, &[Commit])]) -> Report {
+    let counts = by_weekday_and_hour(repos);
+    let mut table = columns("weekday", repos);
+
+    for (day, weekday) in WEEKDAYS.iter().enumerate() {
+        let mut row = vec![Value::Text(weekday.to_string())];
+
+        for (contributors, counts) in &counts {
+            let count: u64 = counts[day].iter().sum();
+            row.push(Value::Decimal(count as f64 / contributors));
+        }
+
+        table.push(row);
+    }
+
+    Report::new("Commits per contributor by weekday:", table)
+}
+
+/// A label column followed by a column per repository
+fn columns(label: &str, repos: &[(&str, &[Commit])]) -> Table {
+    let columns: Vec<&str> = [label]
+        .into_iter()
+        .chain(repos.iter().map(|(name, _)| *name))
+        .collect();
+
+    Table::new(&columns)
+}
+
+/// Each repository's number of contributors, at least one, and its commits by
+/// weekday and hour
+fn by_weekday_and_hour(repos: &[(&str, &[Commit])]) -> Vec<(f64, [[u64; 24]; 7])> {
+    repos
+        .iter()
+        .map(|(_, commits)| {
+            let snapshot = Snapshot::of(commits, OffsetDateTime::UNIX_EPOCH);
+            let contributors = snapshot.authors.len().max(1) as f64;
+
+            (
+                contributors,
+                histogram::by_weekday_and_hour(*commits, Weight::Count),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn per_contributor() {
+        let small = vec![
+            commit()
+                .by("jon")
+                .on(datetime!(2022-11-28 10:00 -8))
+                .lines(10, 0)
+                .build(),
+            commit()
+                .by("jon")
+                .on(datetime!(2022-11-28 10:00 -8))
+                .lines(10, 0)
+                .build(),
+        ];
+        let large = vec![
+            commit()
+                .by("amy")
+                .on(datetime!(2022-11-28 10:00 -8))
+                .lines(10, 0)
+                .build(),
+            commit()
+                .by("bob")
+                .on(datetime!(2022-11-28 10:00 -8))
+                .lines(10, 0)
+                .build(),
+            commit()
+                .by("cat")
+                .on(datetime!(2022-11-28 10:00 -8))
+                .lines(10, 0)
+                .build(),
+            commit()
+                .by("dan")
+                .on(datetime!(2022-11-28 10:00 -8))
+                .lines(10, 0)
+                .build(),
+        ];
+
+        let reports = super::reports(&[("small", &small), ("large", &large)]);
+
+        let headlines = &reports[0].table;
+        assert_eq!(headlines.columns, vec!["measure", "small", "large"]);
+        assert_eq!(headlines.rows[0][1..], [Value::Count(2), Value::Count(4)]);
+        assert_eq!(headlines.rows[1][1..], [Value::Count(1), Value::Count(4)]);
+        assert_eq!(
+            headlines.rows[2][1..],
+            [Value::Decimal(2.0), Value::Decimal(1.0)]
+        );
+
+        let hours = &reports[1].table;
+        assert_eq!(
+            hours.rows[10][1..],
+            [Value::Decimal(2.0), Value::Decimal(1.0)]
+        );
+
+        let weekdays = &reports[2].table;
+        assert_eq!(weekdays.rows[0][0], Value::Text("Mon".to_string()));
+        assert_eq!(
+            weekdays.rows[0][1..],
+            [Value::Decimal(2.0), Value::Decimal(1.0)]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::{HashMap, HashSet};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use time::OffsetDateTime;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// Conventional commits without a scope
+const NO_SCOPE: &str = "(none)";
+
+lazy_static! {
+    /// `type(scope)!: description`, where the scope and `!` are optional
+    static ref SUBJECT: Regex =
+        Regex::new(r"^([A-Za-z]+)(?:\(([^()]*)\))?!?: \S").unwrap();
+}
+
+/// The type and scope of a subject following Conventional Commits, e.g.
+/// `fix` and `parser` for `fix(parser): handle tabs`. Types and scopes are
+/// lowercased and empty scopes are none.
+pub fn parse(subject: &str) -> Option<(String, Option<String>)> {
+    let captures = SUBJECT.captures(subject)?;
+
+    let kind = captures[1].to_lowercase();
+    let scope = captures
+        .get(2)
+        .map(|scope| scope.as_str().trim().to_lowercase())
+        .filter(|scope| !scope.is_empty());
+
+    Some((kind, scope))
+}
+
+/// Activity by scope for the commits following Conventional Commits, a view
+/// of which components are worked on without looking at files, along with
+/// how many commits are of each type
+pub fn reports(commits: &[Commit]) -> Vec<Report> {
+    #[derive(Default)]
+    struct Scope<'a> {
+        commits: u64,
+        authors: HashSet<&'a str>,
+        churn: u64,
+        latest: Option<OffsetDateTime>,
+    }
+
+    let mut kinds: HashMap<String, u64> = HashMap::new();
+    let mut scopes: HashMap<String, Scope> = HashMap::new();
+    let mut conventional = 0;
+
+    for commit in commits {
+        let (kind, scope) = match parse(commit.subject()) {
+            Some(parsed) => parsed,
+            None => continue,
+        };
+
+        conventional += 1;
+        *kinds.entry(kind).or_default() += 1;
+
+        let scope = scopes
+            .entry(scope.unwrap_or_else(|| NO_SCOPE.to_string()))
+            .or_default();
+        scope.commits += 1;
+        scope.authors.insert(&commit.author.email);
+        scope.churn += commit.churn();
+        scope.latest = scope.latest.max(Some(commit.date));
+    }
+
+    let mut kinds: Vec<(String, u64)> = kinds.into_iter().collect();
+    kinds.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut by_kind = Table::new(&["type", "commits", "share"]);
+
+    for (kind, count) in kinds {
+        by_kind.push(vec![
+            Value::Text(kind),
+            Value::Count(count),
+            Value::Percent(count as f64 / conventional.max(1) as f64),
+        ]);
+    }
+
+    let mut scopes: Vec<(String, Scope)> = scopes.into_iter().collect();
+    scopes.sort_by(|a, b| b.1.commits.cmp(&a.1.commits).then(a.0.cmp(&b.0)));
+
+    let mut by_scope = Table::new(&["scope", "commits", "authors", "lines", "latest"]);
+
+    for (name, scope) in scopes {
+        let latest = match scope.latest {
+            Some(latest) => Value::Date(latest.date()),
+            None => Value::Empty,
+        };
+
+        by_scope.push(vec![
+            Value::Text(name),
+            Value::Count(scope.commits),
+            Value::Count(scope.authors.len() as u64),
+            Value::Count(scope.churn),
+            latest,
+        ]);
+    }
+
+    vec![
+        Report::new("Conventional commit types:", by_kind),
+        Report::new("Activity by scope:", by_scope),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn parse() {
+        let parsed = |kind: &str, scope: Option<&str>| {
+            Some((kind.to_string(), scope.map(|scope| scope.to_string())))
+        };
+
+        assert_eq!(
+            super::parse("fix(parser): handle tabs"),
+            parsed("fix", Some("parser"))
+        );
+        assert_eq!(
+            super::parse("Feat(API)!: drop v1"),
+            parsed("feat", Some("api"))
+        );
+        assert_eq!(super::parse("docs: typo"), parsed("docs", None));
+        assert_eq!(super::parse("chore(): bump"), parsed("chore", None));
+        assert_eq!(super::parse("Fix the parser"), None);
+        assert_eq!(super::parse("fix(parser):handle tabs"), None);
+        assert_eq!(super::parse("Merge branch 'main': oops"), None);
+    }
+
+    #[test]
+    fn by_scope() {
+        let commits = vec![
+            commit()
+                .email("jon")
+                .message("fix(parser): tabs")
+                .on(datetime!(2022-11-01 12:00 UTC))
+                .lines(3, 0)
+                .build(),
+            commit()
+                .email("not.jon")
+                .message("feat(parser): spaces")
+                .on(datetime!(2022-11-03 12:00 UTC))
+                .lines(5, 0)
+                .build(),
+            commit()
+                .email("jon")
+                .message("docs: readme")
+                .on(datetime!(2022-11-02 12:00 UTC))
+                .lines(1, 0)
+                .build(),
+            commit()
+                .email("jon")
+                .message("Not conventional")
+                .on(datetime!(2022-11-04 12:00 UTC))
+                .lines(8, 0)
+                .build(),
+        ];
+
+        let reports = super::reports(&commits);
+
+        let kinds = &reports[0].table.rows;
+        assert_eq!(kinds.len(), 3);
+        assert_eq!(kinds[0][2], Value::Percent(1.0 / 3.0));
+
+        let scopes = &reports[1].table.rows;
+        assert_eq!(
+            scopes[0],
+            vec![
+                Value::Text("parser".to_string()),
+                Value::Count(2),
+                Value::Count(2),
+                Value::Count(8),
+                Value::Date(date!(2022 - 11 - 03)),
+            ]
+        );
+        assert_eq!(scopes[1][0], Value::Text("(none)".to_string()));
+        assert_eq!(scopes.len(), 2);
+    }
+}
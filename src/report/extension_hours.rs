@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::commit::Commit;
+use crate::histogram::{Histogram, Weight};
+use crate::report::{group, Report, Table, Value};
+
+/// Parts of the day, by the hours in each
+pub const PARTS: [(&str, Range<usize>); 4] = [
+    ("night", 0..6),
+    ("morning", 6..12),
+    ("afternoon", 12..18),
+    ("evening", 18..24),
+];
+
+/// Files of each extension changed by the hour they're changed in, in each
+/// commit's own offset like `--hours`, e.g. docs in the morning and CI config
+/// at night. A commit counts once for each extension it changes, or by its
+/// files or lines changed with that extension when weighted. Each part of the
+/// day is its share of the extension's total, and the peak is its busiest
+/// hour.
+pub fn report(commits: &[Commit], weight: Weight) -> Report {
+    let mut extensions: HashMap<String, Histogram<24>> = HashMap::new();
+
+    for commit in commits {
+        let mut totals: HashMap<String, u64> = HashMap::new();
+
+        for change in &commit.file_changes {
+            let total = totals.entry(group::extension(&change.path)).or_default();
+
+            match weight {
+                Weight::Count => *total = 1,
+                Weight::Lines => *total += change.changes as u64,
+                Weight::Files => *total += 1,
+            }
+        }
+
+        let hour = commit.date.hour() as usize;
+
+        for (extension, total) in totals {
+            extensions
+                .entry(extension)
+                .or_default()
+                .record_weighted(hour, total);
+        }
+    }
+
+    let mut extensions: Vec<(String, [u64; 24], u64)> = extensions
+        .into_iter()
+        .map(|(extension, histogram)| {
+            let hours = histogram.totals();
+            (extension, hours, hours.iter().sum())
+        })
+        // e.g. images have no lines changed
+        .filter(|(_, _, total)| *total > 0)
+        .collect();
+
+    extensions.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+
+    let columns: Vec<&str> = ["extension", weight.name()]
+        .into_iter()
+        .chain(PARTS.iter().map(|(name, _)| *name))
+        .chain(["peak"])
+        .collect();
+    let mut table = Table::new(&columns);
+
+    for (extension, hours, total) in extensions {
+        let mut row = vec![Value::Text(extension), Value::Count(total)];
+
+        for (_, part) in PARTS {
+            let share = hours[part].iter().sum::<u64>() as f64 / total as f64;
+            row.push(Value::Percent(share));
+        }
+
+        // The earliest of the busiest hours
+        let peak = (0..24).rev().max_by_key(|hour| hours[*hour]).unwrap();
+        row.push(Value::Text(format!("{:02}", peak)));
+
+        table.push(row);
+    }
+
+    Report::new("By file extension and time of day:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::histogram::Weight;
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn report() {
+        let commits = vec![
+            commit()
+                .on(datetime!(2022-11-28 9:15 -8))
+                .changes(&[("README.md", 10), ("docs/guide.md", 5)])
+                .build(),
+            commit()
+                .on(datetime!(2022-11-28 8:00 +1))
+                .changes(&[("docs/api.md", 1)])
+                .build(),
+            commit()
+                .on(datetime!(2022-11-28 23:30 -8))
+                .changes(&[(".github/ci.yml", 4), ("logo.png", 0), ("src/main.rs", 20)])
+                .build(),
+            commit()
+                .on(datetime!(2022-11-29 2:00 -8))
+                .changes(&[(".github/ci.yml", 2)])
+                .build(),
+        ];
+
+        let report = super::report(&commits, Weight::Count);
+
+        assert_eq!(
+            report.table.columns,
+            vec![
+                "extension",
+                "commits",
+                "night",
+                "morning",
+                "afternoon",
+                "evening",
+                "peak"
+            ]
+        );
+
+        let rows = &report.table.rows;
+
+        // Each commit counts once per extension
+        assert_eq!(
+            rows[0],
+            vec![
+                Value::Text("md".to_string()),
+                Value::Count(2),
+                Value::Percent(0.0),
+                Value::Percent(1.0),
+                Value::Percent(0.0),
+                Value::Percent(0.0),
+                Value::Text("08".to_string()),
+            ]
+        );
+        assert_eq!(rows[1][0], Value::Text("yml".to_string()));
+        assert_eq!(rows[1][2], Value::Percent(0.5));
+        assert_eq!(rows[1][5], Value::Percent(0.5));
+        assert_eq!(rows.len(), 4);
+
+        // Images have no lines so aren't counted by them
+        let rows = super::report(&commits, Weight::Lines).table.rows;
+
+        assert_eq!(rows[0][0], Value::Text("rs".to_string()));
+        assert_eq!(
+            rows[1][..2],
+            [Value::Text("md".to_string()), Value::Count(16)]
+        );
+        assert_eq!(rows[1][6], Value::Text("09".to_string()));
+        assert_eq!(rows.len(), 3);
+
+        let rows = super::report(&commits, Weight::Files).table.rows;
+        assert_eq!(
+            rows[0][..2],
+            [Value::Text("md".to_string()), Value::Count(3)]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::{HashMap, HashSet};
+
+use time::OffsetDateTime;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// How many files are listed
+const TOP: usize = 10;
+
+/// When a file was first and last changed, and by how many commits
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Span {
+    first: OffsetDateTime,
+    last: OffsetDateTime,
+    commits: u64,
+}
+
+/// The files still tracked that have gone longest without a change, which may
+/// be dead code, and the files no longer tracked that lived the shortest time
+/// from their first change to their last, i.e. created and deleted quickly.
+/// Ages are as of the latest commit so they only change when the history
+/// does. A renamed file's history before the rename is under its old path,
+/// which looks deleted.
+pub fn reports(commits: &[Commit], tracked: &[String]) -> Vec<Report> {
+    let spans = spans(commits);
+    let tracked: HashSet<&str> = tracked.iter().map(String::as_str).collect();
+    let newest = commits.iter().map(|commit| commit.date).max();
+
+    let (surviving, deleted) = spans
+        .into_iter()
+        .partition(|(path, _)| tracked.contains(path));
+
+    vec![untouched(surviving, newest), short_lived(deleted)]
+}
+
+fn spans(commits: &[Commit]) -> HashMap<&str, Span> {
+    let mut spans: HashMap<&str, Span> = HashMap::new();
+
+    for commit in commits {
+        for change in &commit.file_changes {
+            let span = spans.entry(&change.path).or_insert(Span {
+                first: commit.date,
+                last: commit.date,
+                commits: 0,
+            });
+
+            span.first = span.first.min(commit.date);
+            span.last = span.last.max(commit.date);
+            span.commits += 1;
+        }
+    }
+
+    spans
+}
+
+fn untouched(surviving: Vec<(&str, Span)>, newest: Option<OffsetDateTime>) -> Report {
+    let mut files = surviving;
+    files.sort_by(|a, b| a.1.last.cmp(&b.1.last).then(a.0.cmp(b.0)));
+    files.truncate(TOP);
+
+    let mut table = Table::new(&["file", "last changed", "days untouched", "commits"]);
+
+    for (path, span) in files {
+        let days = newest.map_or(0, |newest| (newest - span.last).whole_days());
+
+        table.push(vec![
+            Value::Text(path.to_string()),
+            Value::Date(span.last.date()),
+            Value::Count(days.max(0) as u64),
+            Value::Count(span.commits),
+        ]);
+    }
+
+    Report::new("Oldest untouched files:", table)
+}
+
+fn short_lived(deleted: Vec<(&str, Span)>) -> Report {
+    let mut files = deleted;
+    files.sort_by(|a, b| {
+        (a.1.last - a.1.first)
+            .cmp(&(b.1.last - b.1.first))
+            .then(a.0.cmp(b.0))
+    });
+    files.truncate(TOP);
+
+    let mut table = Table::new(&["file", "first changed", "last changed", "days", "commits"]);
+
+    for (path, span) in files {
+        table.push(vec![
+            Value::Text(path.to_string()),
+            Value::Date(span.first.date()),
+            Value::Date(span.last.date()),
+            Value::Count((span.last - span.first).whole_days().max(0) as u64),
+            Value::Count(span.commits),
+        ]);
+    }
+
+    Report::new("Shortest-lived files:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn untouched_and_short_lived() {
+        let commits = vec![
+            commit()
+                .on(datetime!(2022-12-31 12:00 UTC))
+                .paths(&["b.rs", "tmp.rs"])
+                .build(),
+            commit()
+                .on(datetime!(2022-12-30 12:00 UTC))
+                .paths(&["tmp.rs"])
+                .build(),
+            commit()
+                .on(datetime!(2022-06-01 12:00 UTC))
+                .paths(&["b.rs", "gone.rs"])
+                .build(),
+            commit()
+                .on(datetime!(2022-01-01 12:00 UTC))
+                .paths(&["a.rs", "b.rs", "gone.rs"])
+                .build(),
+        ];
+        let tracked = vec!["a.rs".to_string(), "b.rs".to_string()];
+
+        let reports = super::reports(&commits, &tracked);
+        let text = |text: &str| Value::Text(text.to_string());
+
+        let untouched = &reports[0].table.rows;
+        assert_eq!(untouched.len(), 2);
+        assert_eq!(
+            untouched[0],
+            vec![
+                text("a.rs"),
+                Value::Date(date!(2022 - 01 - 01)),
+                Value::Count(364),
+                Value::Count(1)
+            ]
+        );
+        assert_eq!(untouched[1][0], text("b.rs"));
+        assert_eq!(untouched[1][3], Value::Count(3));
+
+        let short_lived = &reports[1].table.rows;
+        assert_eq!(short_lived[0][0], text("tmp.rs"));
+        assert_eq!(short_lived[0][3], Value::Count(1));
+        assert_eq!(short_lived[1][0], text("gone.rs"));
+        assert_eq!(short_lived[1][3], Value::Count(151));
+    }
+}
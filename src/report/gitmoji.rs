@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// How many emoji are listed
+const TOP: usize = 15;
+
+/// Joins emoji into one, e.g. a family
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// Asks for the emoji rather than the text style of a character
+const VARIATION_SELECTOR: char = '\u{FE0F}';
+
+/// The common gitmoji, without variation selectors, with their codes and what
+/// they mean, from https://gitmoji.dev
+const GITMOJI: [(&str, &str, &str); 34] = [
+    ("🎨", ":art:", "structure or format"),
+    ("⚡", ":zap:", "performance"),
+    ("🔥", ":fire:", "remove code or files"),
+    ("🐛", ":bug:", "fix a bug"),
+    ("🚑", ":ambulance:", "critical hotfix"),
+    ("✨", ":sparkles:", "new feature"),
+    ("📝", ":memo:", "documentation"),
+    ("🚀", ":rocket:", "deploy"),
+    ("💄", ":lipstick:", "UI and style"),
+    ("🎉", ":tada:", "begin a project"),
+    ("✅", ":white_check_mark:", "tests"),
+    ("🔒", ":lock:", "security"),
+    ("🔖", ":bookmark:", "release"),
+    ("🚨", ":rotating_light:", "compiler or linter warnings"),
+    ("🚧", ":construction:", "work in progress"),
+    ("💚", ":green_heart:", "fix CI"),
+    ("⬇", ":arrow_down:", "downgrade dependencies"),
+    ("⬆", ":arrow_up:", "upgrade dependencies"),
+    ("📌", ":pushpin:", "pin dependencies"),
+    ("👷", ":construction_worker:", "CI build system"),
+    ("♻", ":recycle:", "refactor"),
+    ("➕", ":heavy_plus_sign:", "add a dependency"),
+    ("➖", ":heavy_minus_sign:", "remove a dependency"),
+    ("🔧", ":wrench:", "configuration"),
+    ("🌐", ":globe_with_meridians:", "internationalization"),
+    ("✏", ":pencil2:", "fix typos"),
+    ("⏪", ":rewind:", "revert"),
+    ("🔀", ":twisted_rightwards_arrows:", "merge branches"),
+    ("📦", ":package:", "packages"),
+    ("🚚", ":truck:", "move or rename"),
+    ("💥", ":boom:", "breaking change"),
+    ("♿", ":wheelchair:", "accessibility"),
+    ("🔇", ":mute:", "remove logs"),
+    ("🏷", ":label:", "types"),
+];
+
+/// The emoji or `:code:` the subject starts with, as the emoji when it's a
+/// known gitmoji's code, e.g. `🐛` for both `🐛 Fix crash` and
+/// `:bug: Fix crash`
+pub fn prefix(subject: &str) -> Option<String> {
+    let subject = subject.trim_start();
+
+    if let Some(rest) = subject.strip_prefix(':') {
+        let code = rest.split(':').next()?;
+        let is_code = !code.is_empty()
+            && rest.len() > code.len()
+            && code
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_+-".contains(c));
+
+        if !is_code {
+            return None;
+        }
+
+        let code = format!(":{}:", code);
+
+        return Some(match GITMOJI.iter().find(|(_, known, _)| *known == code) {
+            Some((emoji, _, _)) => emoji.to_string(),
+            None => code,
+        });
+    }
+
+    let mut chars = subject.chars().peekable();
+    let first = chars.next().filter(|c| is_emoji(*c))?;
+    let mut emoji = first.to_string();
+
+    // Joined emoji are one, and the variation selector is left out so the
+    // same emoji with or without it are counted together
+    while let Some(&c) = chars.peek() {
+        match c {
+            VARIATION_SELECTOR => {}
+            ZERO_WIDTH_JOINER => emoji.push(c),
+            c if emoji.ends_with(ZERO_WIDTH_JOINER) && is_emoji(c) => emoji.push(c),
+            // Skin tones
+            '\u{1F3FB}'..='\u{1F3FF}' => emoji.push(c),
+            _ => break,
+        }
+
+        chars.next();
+    }
+
+    Some(emoji)
+}
+
+/// Roughly, the blocks of pictographs, symbols, and arrows emoji come from
+fn is_emoji(c: char) -> bool {
+    matches!(c,
+        '\u{1F000}'..='\u{1FAFF}'
+        | '\u{2190}'..='\u{21FF}'
+        | '\u{2300}'..='\u{23FF}'
+        | '\u{2600}'..='\u{27BF}'
+        | '\u{2B00}'..='\u{2BFF}')
+}
+
+/// How many commit subjects start with an emoji, e.g. by following gitmoji,
+/// and which emoji are used the most
+pub fn reports(commits: &[Commit]) -> Vec<Report> {
+    let mut counts: HashMap<String, u64> = HashMap::new();
+
+    for commit in commits {
+        if let Some(emoji) = prefix(commit.subject()) {
+            *counts.entry(emoji).or_default() += 1;
+        }
+    }
+
+    let prefixed: u64 = counts.values().sum();
+    let known: u64 = counts
+        .iter()
+        .filter(|(emoji, _)| GITMOJI.iter().any(|(known, _, _)| known == emoji))
+        .map(|(_, count)| count)
+        .sum();
+
+    let share = |count: u64| Value::Percent(count as f64 / commits.len().max(1) as f64);
+
+    let mut summary = Table::new(&["measure", "commits", "share"]);
+
+    for (measure, count) in [("starts with an emoji", prefixed), ("known gitmoji", known)] {
+        summary.push(vec![
+            Value::Text(measure.to_string()),
+            Value::Count(count),
+            share(count),
+        ]);
+    }
+
+    let mut counts: Vec<(String, u64)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    counts.truncate(TOP);
+
+    let mut table = Table::new(&["emoji", "code", "meaning", "commits", "share"]);
+
+    for (emoji, count) in counts {
+        let known = GITMOJI.iter().find(|(known, _, _)| *known == emoji);
+        let text = |text: &str| Value::Text(text.to_string());
+
+        table.push(vec![
+            text(&emoji),
+            known.map_or(Value::Empty, |(_, code, _)| text(code)),
+            known.map_or(Value::Empty, |(_, _, meaning)| text(meaning)),
+            Value::Count(count),
+            share(count),
+        ]);
+    }
+
+    vec![
+        Report::new("Emoji in subjects:", summary),
+        Report::new("Most used emoji:", table),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn prefix() {
+        let prefix = |subject| super::prefix(subject);
+
+        assert_eq!(prefix("🐛 Fix crash"), Some("🐛".to_string()));
+        assert_eq!(prefix(":bug: Fix crash"), Some("🐛".to_string()));
+        assert_eq!(prefix("⚡️ Faster"), Some("⚡".to_string()));
+        assert_eq!(prefix(":zap: Faster"), Some("⚡".to_string()));
+        assert_eq!(prefix(":llama: Woo"), Some(":llama:".to_string()));
+        assert_eq!(prefix("👩‍💻 Code"), Some("👩‍💻".to_string()));
+        assert_eq!(prefix("👍🏽 Thanks"), Some("👍🏽".to_string()));
+        assert_eq!(prefix("Fix crash 🐛"), None);
+        assert_eq!(prefix(": not a code"), None);
+        assert_eq!(prefix(":unclosed"), None);
+    }
+
+    #[test]
+    fn summary_and_top() {
+        let commits = vec![
+            commit().message("🐛 Fix crash").build(),
+            commit().message(":bug: Fix another crash").build(),
+            commit().message("✨ Add flag").build(),
+            commit().message("🦙 Llamas").build(),
+            commit().message("Plain").build(),
+        ];
+
+        let reports = super::reports(&commits);
+
+        let summary = &reports[0].table.rows;
+        assert_eq!(summary[0][1], Value::Count(4));
+        assert_eq!(summary[0][2], Value::Percent(0.8));
+        assert_eq!(summary[1][1], Value::Count(3));
+
+        let top = &reports[1].table.rows;
+        assert_eq!(top[0][0], Value::Text("🐛".to_string()));
+        assert_eq!(top[0][1], Value::Text(":bug:".to_string()));
+        assert_eq!(top[0][3], Value::Count(2));
+        assert_eq!(top.len(), 3);
+        assert!(top.iter().any(|row| row[1] == Value::Empty));
+    }
+}
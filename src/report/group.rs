@@ -0,0 +1,370 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::commit::{self, Commit};
+use crate::git::Tag;
+use crate::histogram::{Weight, WEEKDAYS};
+use crate::report::{activity, list, release, Report, Table, Value};
+
+/// Commits without a release after them, or files without an extension
+const NONE: &str = "(none)";
+
+/// What commits are counted by in a histogram, e.g. `--group-by month`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Hour,
+    Weekday,
+    /// In the author's time, e.g. `2022-11`
+    Month,
+    Author,
+    /// Of the author's email, e.g. `email.ca`
+    Domain,
+    /// Of the files changed, counting a commit once for each extension
+    Extension,
+    /// The release a commit went out in, i.e. the first tag on or after it
+    Tag,
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "hour" => Ok(GroupBy::Hour),
+            "weekday" => Ok(GroupBy::Weekday),
+            "month" => Ok(GroupBy::Month),
+            "author" => Ok(GroupBy::Author),
+            "domain" => Ok(GroupBy::Domain),
+            "extension" => Ok(GroupBy::Extension),
+            "tag" => Ok(GroupBy::Tag),
+            _ => Err(format!("Unknown group {}", name)),
+        }
+    }
+}
+
+/// A group of a histogram named on the command line, e.g. `hour=23`, to list
+/// the commits in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bucket {
+    pub group_by: GroupBy,
+    /// As the histogram names it, e.g. `02` for hour 2 or `Sat` for saturday
+    pub name: String,
+}
+
+impl FromStr for Bucket {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (group, name) = text
+            .split_once('=')
+            .ok_or_else(|| format!("Expected <group>=<bucket>, not {}", text))?;
+
+        let group_by = group.parse()?;
+
+        let name = match group_by {
+            GroupBy::Hour => format!("{:02}", list::parse_hour(name)?),
+            GroupBy::Weekday => WEEKDAYS[list::parse_weekday(name)? as usize].to_string(),
+            _ => name.to_string(),
+        };
+
+        Ok(Bucket { group_by, name })
+    }
+}
+
+/// How many commits fall in each group. Hours and weekdays are in order with
+/// every one listed, months and releases oldest first, and the rest most
+/// commits first.
+pub fn report(commits: &[Commit], tags: &[Tag], group_by: GroupBy) -> Report {
+    let title = match group_by {
+        GroupBy::Hour => return activity::hours(commits, Weight::Count),
+        GroupBy::Weekday => return activity::weekdays(commits, Weight::Count),
+        GroupBy::Month => "By month:",
+        GroupBy::Author => "By author:",
+        GroupBy::Domain => "By email domain:",
+        GroupBy::Extension => "By file extension:",
+        GroupBy::Tag => "By release:",
+    };
+
+    let mut table = Table::new(&[label(group_by), "commits"]);
+
+    for (group, commits) in groups(commits, tags, group_by) {
+        table.push(vec![Value::Text(group), Value::Count(commits.len() as u64)]);
+    }
+
+    Report::histogram(title, table)
+}
+
+/// The commits in the bucket, ignoring case, e.g. those behind a busy hour
+pub fn bucket(commits: &[Commit], tags: &[Tag], bucket: &Bucket) -> Report {
+    let name = bucket.name.to_lowercase();
+
+    let commits = groups(commits, tags, bucket.group_by)
+        .into_iter()
+        .find(|(group, _)| group.to_lowercase() == name)
+        .map(|(_, commits)| commits)
+        .unwrap_or_default();
+
+    let title = format!("Commits in {} {}:", label(bucket.group_by), bucket.name);
+    list::commits(&title, commits)
+}
+
+/// What the column of groups is called
+fn label(group_by: GroupBy) -> &'static str {
+    match group_by {
+        GroupBy::Hour => "hour",
+        GroupBy::Weekday => "weekday",
+        GroupBy::Month => "month",
+        GroupBy::Author => "author",
+        GroupBy::Domain => "domain",
+        GroupBy::Extension => "extension",
+        GroupBy::Tag => "tag",
+    }
+}
+
+/// The commits in each group, in the order `report()` lists them
+pub fn groups<'a>(
+    commits: &'a [Commit],
+    tags: &[Tag],
+    group_by: GroupBy,
+) -> Vec<(String, Vec<&'a Commit>)> {
+    match group_by {
+        GroupBy::Hour => {
+            let mut hours: Vec<(String, Vec<&Commit>)> = (0..24)
+                .map(|hour| (format!("{:02}", hour), Vec::new()))
+                .collect();
+
+            for commit in commits {
+                hours[commit.date.hour() as usize].1.push(commit);
+            }
+
+            hours
+        }
+        GroupBy::Weekday => {
+            let mut weekdays: Vec<(String, Vec<&Commit>)> = WEEKDAYS
+                .iter()
+                .map(|weekday| (weekday.to_string(), Vec::new()))
+                .collect();
+
+            for commit in commits {
+                let weekday = commit.date.weekday().number_days_from_monday() as usize;
+                weekdays[weekday].1.push(commit);
+            }
+
+            weekdays
+        }
+        GroupBy::Month => {
+            let mut months: BTreeMap<String, Vec<&Commit>> = BTreeMap::new();
+
+            for commit in commits {
+                let date = commit.date.date();
+                let month = format!("{}-{:02}", date.year(), date.month() as u8);
+                months.entry(month).or_default().push(commit);
+            }
+
+            months.into_iter().collect()
+        }
+        GroupBy::Author => {
+            let authors = commit::by_author(commits)
+                .into_iter()
+                .map(|(author, commits)| (author.name.clone(), commits))
+                .collect();
+
+            most_first(authors)
+        }
+        GroupBy::Domain => {
+            let mut domains: HashMap<String, Vec<&Commit>> = HashMap::new();
+
+            for commit in commits {
+                let domain = match commit.author.email.rsplit_once('@') {
+                    Some((_, domain)) => domain.to_lowercase(),
+                    None => NONE.to_string(),
+                };
+                domains.entry(domain).or_default().push(commit);
+            }
+
+            most_first(domains.into_iter().collect())
+        }
+        GroupBy::Extension => {
+            let mut extensions: HashMap<String, Vec<&Commit>> = HashMap::new();
+
+            for commit in commits {
+                let touched: HashSet<String> = commit
+                    .file_changes
+                    .iter()
+                    .map(|change| extension(&change.path))
+                    .collect();
+
+                for extension in touched {
+                    extensions.entry(extension).or_default().push(commit);
+                }
+            }
+
+            most_first(extensions.into_iter().collect())
+        }
+        GroupBy::Tag => {
+            let releases = release::releases(commits, tags);
+
+            let mut groups: Vec<(String, Vec<&Commit>)> = releases
+                .iter()
+                .map(|release| (release.tag.to_string(), Vec::new()))
+                .collect();
+            let mut unreleased = Vec::new();
+
+            // Like `releases()`, each commit is in the first release on or
+            // after it
+            for commit in commits {
+                match releases
+                    .iter()
+                    .position(|release| commit.date <= release.date)
+                {
+                    Some(i) => groups[i].1.push(commit),
+                    None => unreleased.push(commit),
+                }
+            }
+
+            if !unreleased.is_empty() {
+                groups.push((NONE.to_string(), unreleased));
+            }
+
+            groups
+        }
+    }
+}
+
+/// The file's extension in lowercase, e.g. `rs`, or `(none)`
+pub fn extension(path: &str) -> String {
+    match Path::new(path).extension() {
+        Some(extension) => extension.to_string_lossy().to_lowercase(),
+        None => NONE.to_string(),
+    }
+}
+
+fn most_first(mut groups: Vec<(String, Vec<&Commit>)>) -> Vec<(String, Vec<&Commit>)> {
+    groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::commit::Commit;
+    use crate::git::Tag;
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    use super::{Bucket, GroupBy};
+
+    fn counts(commits: &[Commit], tags: &[Tag], group_by: GroupBy) -> Vec<(String, u64)> {
+        super::report(commits, tags, group_by)
+            .table
+            .rows
+            .iter()
+            .map(|row| match (&row[0], &row[1]) {
+                (Value::Text(group), Value::Count(count)) => (group.clone(), *count),
+                _ => panic!("Unexpected row {:?}", row),
+            })
+            .collect()
+    }
+
+    fn expected(counts: &[(&str, u64)]) -> Vec<(String, u64)> {
+        counts
+            .iter()
+            .map(|(group, count)| (group.to_string(), *count))
+            .collect()
+    }
+
+    #[test]
+    fn groups() {
+        let commits = vec![
+            commit()
+                .hash("c")
+                .email("not.jon@other.org")
+                .on(datetime!(2023-01-02 12:00 UTC))
+                .paths(&["Makefile"])
+                .build(),
+            commit()
+                .hash("b")
+                .email("jon@email.ca")
+                .on(datetime!(2022-11-30 12:00 UTC))
+                .paths(&["src/main.rs", "src/lib.rs"])
+                .build(),
+            commit()
+                .hash("a")
+                .email("jon@Email.ca")
+                .on(datetime!(2022-11-01 12:00 UTC))
+                .paths(&["README.md", "src/main.rs"])
+                .build(),
+        ];
+        let tags = vec![Tag::new("v1.0.0".to_string(), "b".to_string())];
+
+        assert_eq!(
+            counts(&commits, &tags, GroupBy::Month),
+            expected(&[("2022-11", 2), ("2023-01", 1)])
+        );
+        assert_eq!(
+            counts(&commits, &tags, GroupBy::Domain),
+            expected(&[("email.ca", 2), ("other.org", 1)])
+        );
+        assert_eq!(
+            counts(&commits, &tags, GroupBy::Extension),
+            expected(&[("rs", 2), ("(none)", 1), ("md", 1)])
+        );
+        assert_eq!(
+            counts(&commits, &tags, GroupBy::Tag),
+            expected(&[("v1.0.0", 2), ("(none)", 1)])
+        );
+        assert_eq!(counts(&commits, &tags, GroupBy::Hour).len(), 24);
+
+        assert_eq!("extension".parse(), Ok(GroupBy::Extension));
+        assert!("year".parse::<GroupBy>().is_err());
+    }
+
+    #[test]
+    fn bucket() {
+        let commits = vec![
+            commit()
+                .hash("c")
+                .email("sam@email.ca")
+                .on(datetime!(2022-12-03 23:30 -8))
+                .paths(&[])
+                .build(),
+            commit()
+                .hash("b")
+                .email("jon@email.ca")
+                .on(datetime!(2022-11-30 12:00 UTC))
+                .paths(&[])
+                .build(),
+            commit()
+                .hash("a")
+                .email("jon@email.ca")
+                .on(datetime!(2022-11-28 23:05 -8))
+                .paths(&[])
+                .build(),
+        ];
+
+        let bucket: Bucket = "hour=23".parse().unwrap();
+        let report = super::bucket(&commits, &[], &bucket);
+
+        assert_eq!(report.title, "Commits in hour 23:");
+        assert_eq!(report.table.rows.len(), 2);
+        assert_eq!(report.table.rows[0][0], Value::Text("c".to_string()));
+        assert_eq!(report.table.rows[1][0], Value::Text("a".to_string()));
+
+        let bucket: Bucket = "weekday=saturday".parse().unwrap();
+        assert_eq!(bucket.name, "Sat");
+        assert_eq!(super::bucket(&commits, &[], &bucket).table.rows.len(), 1);
+
+        let bucket: Bucket = "author=JON@EMAIL.CA".parse().unwrap();
+        assert_eq!(super::bucket(&commits, &[], &bucket).table.rows.len(), 2);
+
+        let bucket: Bucket = "month=1999-12".parse().unwrap();
+        assert!(super::bucket(&commits, &[], &bucket).table.rows.is_empty());
+
+        assert_eq!("hour=7".parse::<Bucket>().unwrap().name, "07");
+        assert!("hour=24".parse::<Bucket>().is_err());
+        assert!("hour".parse::<Bucket>().is_err());
+        assert!("year=2022".parse::<Bucket>().is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use hdrhistogram::Histogram;
+
+use crate::report::{Report, Table, Value};
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+/// Pick at most `size` paths spread evenly across the sorted list. Blaming
+/// every file of a large repository takes far too long, and taking every n-th
+/// path keeps each directory represented in proportion to its size. Sampling
+/// the same paths every run also keeps results comparable.
+pub fn sample(mut paths: Vec<String>, size: usize) -> Vec<String> {
+    paths.sort();
+
+    if size == 0 || paths.len() <= size {
+        return paths;
+    }
+
+    let step = paths.len() as f64 / size as f64;
+
+    (0..size)
+        .map(|i| paths[(i as f64 * step) as usize].clone())
+        .collect()
+}
+
+/// Median age in days of surviving lines per directory, given when each line
+/// of the sampled files was authored. Directories whose lines are oldest, i.e.
+/// the most stable, come first.
+pub fn report(blamed: &[(String, Vec<i64>)], now: i64) -> Report {
+    let mut by_directory: BTreeMap<String, (u64, Histogram<u64>)> = BTreeMap::new();
+    let mut overall = Histogram::<u64>::new(3).unwrap();
+
+    for (path, times) in blamed {
+        if times.is_empty() {
+            continue;
+        }
+
+        let (files, ages) = by_directory
+            .entry(directory_of(path))
+            .or_insert_with(|| (0, Histogram::new(3).unwrap()));
+
+        *files += 1;
+
+        for time in times {
+            // Clocks are wrong sometimes and lines from the future are brand new
+            let days = (now - time).max(0) / SECONDS_PER_DAY;
+
+            ages.record(days as u64).unwrap();
+            overall.record(days as u64).unwrap();
+        }
+    }
+
+    let mut directories: Vec<(String, u64, Histogram<u64>)> = by_directory
+        .into_iter()
+        .map(|(directory, (files, ages))| (directory, files, ages))
+        .collect();
+
+    directories.sort_by(|a, b| median(&b.2).cmp(&median(&a.2)).then(a.0.cmp(&b.0)));
+
+    let mut table = Table::new(&["directory", "files", "lines", "median days"]);
+
+    for (directory, files, ages) in &directories {
+        table.push(vec![
+            Value::Text(directory.clone()),
+            Value::Count(*files),
+            Value::Count(ages.len()),
+            Value::Count(median(ages)),
+        ]);
+    }
+
+    let files = directories.iter().map(|(_, files, _)| files).sum();
+
+    table.push(vec![
+        Value::Text("(all)".to_string()),
+        Value::Count(files),
+        Value::Count(overall.len()),
+        Value::Count(median(&overall)),
+    ]);
+
+    Report::new("Median age of surviving lines:", table)
+}
+
+fn median(ages: &Histogram<u64>) -> u64 {
+    ages.value_at_quantile(0.5)
+}
+
+/// The directory the file is in, or `.` at the top of the repository
+pub fn directory_of(path: &str) -> String {
+    match Path::new(path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.display().to_string(),
+        _ => ".".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::Value;
+
+    const DAY: i64 = 86400;
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn sample_spreads_across_paths() {
+        let all: Vec<String> = (0..10).map(|i| format!("src/{}.rs", i)).collect();
+
+        assert_eq!(
+            super::sample(all.clone(), 5),
+            paths(&["src/0.rs", "src/2.rs", "src/4.rs", "src/6.rs", "src/8.rs"])
+        );
+        assert_eq!(super::sample(all.clone(), 20), all);
+    }
+
+    #[test]
+    fn median_age_by_directory() {
+        let now = 1000 * DAY;
+
+        let blamed = vec![
+            ("README.md".to_string(), vec![now - 10 * DAY]),
+            (
+                "src/main.rs".to_string(),
+                vec![now - 300 * DAY, now - 200 * DAY, now - 100 * DAY],
+            ),
+            ("src/lib.rs".to_string(), vec![now - 400 * DAY]),
+            ("logo.png".to_string(), vec![]),
+        ];
+
+        let report = super::report(&blamed, now);
+        let rows = &report.table.rows;
+
+        assert_eq!(rows.len(), 3);
+
+        assert_eq!(rows[0][0], Value::Text("src".to_string()));
+        assert_eq!(rows[0][1], Value::Count(2));
+        assert_eq!(rows[0][2], Value::Count(4));
+        assert_eq!(rows[0][3], Value::Count(200));
+
+        assert_eq!(rows[1][0], Value::Text(".".to_string()));
+        assert_eq!(rows[1][3], Value::Count(10));
+
+        assert_eq!(rows[2][0], Value::Text("(all)".to_string()));
+        assert_eq!(rows[2][1], Value::Count(3));
+        assert_eq!(rows[2][2], Value::Count(5));
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use crate::commit::{self, Commit};
+use crate::report::{Report, Table, Value};
+
+/// How many directories deep owners are found for, e.g. `src/report`
+pub const DEPTH: usize = 2;
+
+/// Directories whose owner, the author changing the most lines in them, isn't
+/// the same now as in the previous period, e.g. to check knowledge was passed
+/// on. Directories only changed in one of the periods are left out.
+pub fn report(commits: &[Commit], previous: &[Commit]) -> Report {
+    let before = owners(previous);
+    let now = owners(commits);
+
+    let mut table = Table::new(&["directory", "before", "share", "now", "share"]);
+
+    for (dir, (owner, share)) in &now {
+        match before.get(dir) {
+            Some((previous_owner, previous_share)) if previous_owner != owner => {
+                table.push(vec![
+                    Value::Text(dir.clone()),
+                    Value::Text(previous_owner.clone()),
+                    Value::Percent(*previous_share),
+                    Value::Text(owner.clone()),
+                    Value::Percent(*share),
+                ]);
+            }
+            _ => {}
+        }
+    }
+
+    Report::new("Owner handoffs:", table)
+}
+
+/// Each directory's owner and their share of the lines changed in it, leaving
+/// out directories where no lines changed
+fn owners(commits: &[Commit]) -> BTreeMap<String, (String, f64)> {
+    let mut owners = BTreeMap::new();
+
+    for (dir, commits) in commit::by_dir(commits, DEPTH) {
+        let mut authors: Vec<(&str, u64)> = commit::by_author(&commits)
+            .into_iter()
+            .map(|(author, commits)| {
+                let churn = commits.iter().map(|commit| commit.churn()).sum();
+                (author.name.as_str(), churn)
+            })
+            .collect();
+
+        authors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        let total: u64 = authors.iter().map(|(_, churn)| churn).sum();
+
+        if let Some((owner, churn)) = authors.first().filter(|_| total > 0) {
+            owners.insert(dir, (owner.to_string(), *churn as f64 / total as f64));
+        }
+    }
+
+    owners
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn report() {
+        let previous = vec![
+            commit()
+                .by("Jon")
+                .files(1)
+                .lines(80, 0)
+                .changes(&[("src/parser/mod.rs", 80)])
+                .build(),
+            commit()
+                .by("Sam")
+                .files(1)
+                .lines(20, 0)
+                .changes(&[("src/parser/lexer.rs", 20)])
+                .build(),
+            commit()
+                .by("Sam")
+                .files(1)
+                .lines(50, 0)
+                .changes(&[("src/report/size.rs", 50)])
+                .build(),
+            commit()
+                .by("Ana")
+                .files(1)
+                .lines(10, 0)
+                .changes(&[("docs/guide.md", 10)])
+                .build(),
+        ];
+        let commits = vec![
+            commit()
+                .by("Sam")
+                .files(1)
+                .lines(30, 0)
+                .changes(&[("src/parser/mod.rs", 30)])
+                .build(),
+            commit()
+                .by("Jon")
+                .files(1)
+                .lines(10, 0)
+                .changes(&[("src/parser/mod.rs", 10)])
+                .build(),
+            commit()
+                .by("Sam")
+                .files(1)
+                .lines(5, 0)
+                .changes(&[("src/report/size.rs", 5)])
+                .build(),
+            commit()
+                .by("Kim")
+                .files(1)
+                .lines(10, 0)
+                .changes(&[("web/index.html", 10)])
+                .build(),
+        ];
+
+        let report = super::report(&commits, &previous);
+        let rows = &report.table.rows;
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0],
+            vec![
+                Value::Text("src/parser".to_string()),
+                Value::Text("Jon".to_string()),
+                Value::Percent(0.8),
+                Value::Text("Sam".to_string()),
+                Value::Percent(0.75),
+            ]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use time::OffsetDateTime;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// Where the commits being reported on came from
+#[derive(Debug, Default)]
+pub struct Context {
+    /// The repository's path or the files imported
+    pub repository: String,
+    /// The branch checked out, or the commit when HEAD is detached. Imported
+    /// history has none.
+    pub branch: Option<String>,
+    /// Which commits were read, e.g. `HEAD` for its entire history
+    pub range: String,
+    /// Anything that left commits out, e.g. `--author jon`
+    pub filters: Vec<String>,
+}
+
+/// A block describing the reports that follow so output that's saved or
+/// shared explains itself
+pub fn report(context: &Context, commits: &[Commit]) -> Report {
+    let mut table = Table::new(&["field", "value"]);
+
+    let text = |text: &str| Value::Text(text.to_string());
+
+    let date =
+        |date: Option<OffsetDateTime>| date.map_or(Value::Empty, |date| Value::Date(date.date()));
+
+    let filters = if context.filters.is_empty() {
+        "none".to_string()
+    } else {
+        context.filters.join(", ")
+    };
+
+    let rows = [
+        ("repository", text(&context.repository)),
+        (
+            "branch",
+            context.branch.as_deref().map(text).unwrap_or(Value::Empty),
+        ),
+        ("range", text(&context.range)),
+        (
+            "first commit",
+            date(commits.iter().map(|commit| commit.date).min()),
+        ),
+        (
+            "last commit",
+            date(commits.iter().map(|commit| commit.date).max()),
+        ),
+        ("filters", text(&filters)),
+        ("commits", Value::Count(commits.len() as u64)),
+    ];
+
+    for (field, value) in rows {
+        table.push(vec![text(field), value]);
+    }
+
+    Report::new("Repository:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    use super::Context;
+
+    #[test]
+    fn report() {
+        let commits = vec![
+            commit().on(datetime!(2022-11-28 16:00 -8)).build(),
+            commit().on(datetime!(2022-01-03 9:00 -8)).build(),
+        ];
+
+        let context = Context {
+            repository: "/src/yeesh".to_string(),
+            branch: Some("main".to_string()),
+            range: "HEAD".to_string(),
+            filters: Vec::new(),
+        };
+
+        let report = super::report(&context, &commits);
+        let values: Vec<String> = report
+            .table
+            .rows
+            .iter()
+            .map(|row| row[1].to_string())
+            .collect();
+
+        assert_eq!(
+            values,
+            vec![
+                "/src/yeesh",
+                "main",
+                "HEAD",
+                "2022-01-03",
+                "2022-11-28",
+                "none",
+                "2"
+            ]
+        );
+        assert_eq!(report.table.rows[6][1], Value::Count(2));
+    }
+}
@@ -0,0 +1,178 @@
+use regex::Regex;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// The conventional limit for a subject line
+const SUBJECT_CHARS: usize = 50;
+
+/// A single yes/no question asked of every commit message
+struct Check<'a> {
+    name: &'static str,
+    passes: Box<dyn Fn(&Commit) -> bool + 'a>,
+}
+
+/// The checks, with issues found by `pattern` like `issue::reports()`
+fn checks(pattern: &Regex) -> [Check<'_>; 4] {
+    [
+        Check {
+            name: "has a body",
+            passes: Box::new(has_body),
+        },
+        Check {
+            name: "subject of 50 characters or less",
+            passes: Box::new(has_short_subject),
+        },
+        Check {
+            name: "references an issue",
+            passes: Box::new(|commit| references_issue(commit, pattern)),
+        },
+        Check {
+            name: "capitalized imperative subject",
+            passes: Box::new(is_capitalized_imperative),
+        },
+    ]
+}
+
+/// A scorecard of how many commit messages follow common conventions, with
+/// issue references found by `pattern`, e.g. `issue::DEFAULT_PATTERN`
+pub fn report(commits: &[Commit], pattern: &Regex) -> Report {
+    let mut table = Table::new(&["check", "commits", "share"]);
+
+    for check in checks(pattern) {
+        let passing = commits
+            .iter()
+            .filter(|commit| (check.passes)(commit))
+            .count();
+        let share = passing as f64 / commits.len().max(1) as f64;
+
+        table.push(vec![
+            Value::Text(check.name.to_string()),
+            Value::Count(passing as u64),
+            Value::Percent(share),
+        ]);
+    }
+
+    Report::new("Commit message hygiene:", table)
+}
+
+fn has_body(commit: &Commit) -> bool {
+    !commit.body().is_empty()
+}
+
+fn has_short_subject(commit: &Commit) -> bool {
+    commit.subject().chars().count() <= SUBJECT_CHARS
+}
+
+fn references_issue(commit: &Commit, pattern: &Regex) -> bool {
+    pattern.is_match(&commit.message)
+}
+
+/// There's no telling a verb from a noun without a dictionary, so this settles
+/// for catching the usual past and present tense slips like `Added`, `Adding`
+/// and `Adds`
+fn is_capitalized_imperative(commit: &Commit) -> bool {
+    let first_word = commit
+        .subject()
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+
+    let is_capitalized = first_word.chars().next().is_some_and(char::is_uppercase);
+
+    let is_past = first_word.ends_with("ed");
+    let is_continuous = first_word.ends_with("ing");
+    let is_third_person = first_word.ends_with('s') && !first_word.ends_with("ss");
+
+    is_capitalized && !is_past && !is_continuous && !is_third_person
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use crate::report::issue::DEFAULT_PATTERN;
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn body() {
+        assert!(super::has_body(
+            &commit().message("Fix it\n\nIt was broken").build()
+        ));
+        assert!(!super::has_body(&commit().message("Fix it").build()));
+    }
+
+    #[test]
+    fn short_subject() {
+        assert!(super::has_short_subject(
+            &commit().message(&"a".repeat(50)).build()
+        ));
+        assert!(!super::has_short_subject(
+            &commit().message(&"a".repeat(51)).build()
+        ));
+    }
+
+    #[test]
+    fn issue_references() {
+        let references =
+            |message| super::references_issue(&commit().message(message).build(), &DEFAULT_PATTERN);
+
+        assert!(references("Fix #12"));
+        assert!(references("Fix it\n\nCloses #12"));
+        assert!(references("PROJ-42 Fix it"));
+        assert!(!references("Fix it"));
+        assert!(!references("Escape &#39; properly"));
+    }
+
+    #[test]
+    fn issue_pattern() {
+        let pattern = Regex::new(r"\bT\d+\b").unwrap();
+        let commits = vec![
+            commit().message("Fix T12").build(),
+            commit().message("Fix #12").build(),
+        ];
+
+        let report = super::report(&commits, &pattern);
+
+        assert!(super::references_issue(&commits[0], &pattern));
+        assert!(!super::references_issue(&commits[1], &pattern));
+        assert_eq!(report.table.rows[2][1], Value::Count(1));
+    }
+
+    #[test]
+    fn capitalized_imperative() {
+        assert!(super::is_capitalized_imperative(
+            &commit().message("Fix parser").build()
+        ));
+        assert!(super::is_capitalized_imperative(
+            &commit().message("Address review").build()
+        ));
+        assert!(!super::is_capitalized_imperative(
+            &commit().message("fix parser").build()
+        ));
+        assert!(!super::is_capitalized_imperative(
+            &commit().message("Fixed parser").build()
+        ));
+        assert!(!super::is_capitalized_imperative(
+            &commit().message("Fixing parser").build()
+        ));
+        assert!(!super::is_capitalized_imperative(
+            &commit().message("Fixes parser").build()
+        ));
+    }
+
+    #[test]
+    fn scorecard() {
+        let commits = vec![
+            commit().message("Fix parser\n\nSee #1").build(),
+            commit().message("wip").build(),
+        ];
+
+        let report = super::report(&commits, &DEFAULT_PATTERN);
+
+        assert_eq!(report.table.rows.len(), 4);
+        assert_eq!(report.table.rows[0][1], Value::Count(1));
+        assert_eq!(report.table.rows[0][2], Value::Percent(0.5));
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::{HashMap, HashSet};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use time::OffsetDateTime;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+use crate::stats::median;
+
+/// How many issues are listed
+const TOP: usize = 10;
+
+lazy_static! {
+    // Either a GitHub style `#123` or a JIRA style `ABC-123`
+    pub static ref DEFAULT_PATTERN: Regex =
+        Regex::new(r"(?:^|[^\w&])(#\d+)\b|\b([A-Z][A-Z0-9]+-\d+)\b").unwrap();
+}
+
+/// Every issue the commit's message references, in order without repeats.
+/// Each reference is the first group of the pattern that matched or, when it
+/// has none, the whole match.
+pub fn references<'a>(commit: &'a Commit, pattern: &Regex) -> Vec<&'a str> {
+    let mut references = Vec::new();
+
+    for captures in pattern.captures_iter(&commit.message) {
+        let reference = captures
+            .iter()
+            .skip(1)
+            .flatten()
+            .next()
+            .or_else(|| captures.get(0))
+            .map(|matched| matched.as_str());
+
+        if let Some(reference) = reference {
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+    }
+
+    references
+}
+
+/// How many commits reference an issue or ticket and which issues gather the
+/// most commits, with references found by `pattern`, e.g. `DEFAULT_PATTERN`
+pub fn reports(commits: &[Commit], pattern: &Regex) -> Vec<Report> {
+    let mut issues: HashMap<&str, Vec<&Commit>> = HashMap::new();
+    let mut referencing = 0;
+
+    for commit in commits {
+        let references = references(commit, pattern);
+
+        if !references.is_empty() {
+            referencing += 1;
+        }
+
+        for reference in references {
+            issues.entry(reference).or_default().push(commit);
+        }
+    }
+
+    vec![summary(commits, referencing, &issues), top(&issues)]
+}
+
+fn summary(commits: &[Commit], referencing: usize, issues: &HashMap<&str, Vec<&Commit>>) -> Report {
+    let per_issue = issues
+        .values()
+        .map(|commits| commits.len() as f64)
+        .collect();
+
+    let mut table = Table::new(&["measure", "value"]);
+
+    let rows = [
+        (
+            "commits referencing an issue",
+            Value::Count(referencing as u64),
+        ),
+        (
+            "share of commits",
+            Value::Percent(referencing as f64 / commits.len().max(1) as f64),
+        ),
+        ("issues", Value::Count(issues.len() as u64)),
+        (
+            "median commits per issue",
+            median(per_issue).map_or(Value::Empty, Value::Decimal),
+        ),
+    ];
+
+    for (measure, value) in rows {
+        table.push(vec![Value::Text(measure.to_string()), value]);
+    }
+
+    Report::new("Issue references:", table)
+}
+
+fn top(issues: &HashMap<&str, Vec<&Commit>>) -> Report {
+    let mut issues: Vec<(&str, &Vec<&Commit>)> = issues
+        .iter()
+        .map(|(issue, commits)| (*issue, commits))
+        .collect();
+    issues.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+    issues.truncate(TOP);
+
+    let mut table = Table::new(&["issue", "commits", "authors", "first", "last"]);
+
+    let date = |date: Option<OffsetDateTime>| match date {
+        Some(date) => Value::Date(date.date()),
+        None => Value::Empty,
+    };
+
+    for (issue, commits) in issues {
+        let authors: HashSet<&str> = commits
+            .iter()
+            .map(|commit| commit.author.email.as_str())
+            .collect();
+
+        table.push(vec![
+            Value::Text(issue.to_string()),
+            Value::Count(commits.len() as u64),
+            Value::Count(authors.len() as u64),
+            date(commits.iter().map(|commit| commit.date).min()),
+            date(commits.iter().map(|commit| commit.date).max()),
+        ]);
+    }
+
+    Report::new("Most committed issues:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use time::macros::datetime;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    use super::DEFAULT_PATTERN;
+
+    #[test]
+    fn references() {
+        let commit = commit()
+            .email("jon")
+            .on(datetime!(2022-11-28 16:00 -8))
+            .message("Fix #12 and ABC-3\n\nSee #12, &#39; and abc-4")
+            .build();
+
+        assert_eq!(
+            super::references(&commit, &DEFAULT_PATTERN),
+            vec!["#12", "ABC-3"]
+        );
+
+        let custom = Regex::new(r"abc-\d+").unwrap();
+        assert_eq!(super::references(&commit, &custom), vec!["abc-4"]);
+    }
+
+    #[test]
+    fn summary_and_top() {
+        let commits = vec![
+            commit()
+                .email("jon")
+                .on(datetime!(2022-11-28 16:00 -8))
+                .message("Start #1")
+                .build(),
+            commit()
+                .email("not jon")
+                .on(datetime!(2022-11-28 16:00 -8))
+                .message("Continue #1")
+                .build(),
+            commit()
+                .email("jon")
+                .on(datetime!(2022-11-28 16:00 -8))
+                .message("Fix #2")
+                .build(),
+            commit()
+                .email("jon")
+                .on(datetime!(2022-11-28 16:00 -8))
+                .message("Tidy up")
+                .build(),
+        ];
+
+        let reports = super::reports(&commits, &DEFAULT_PATTERN);
+
+        let summary = &reports[0].table.rows;
+        assert_eq!(summary[0][1], Value::Count(3));
+        assert_eq!(summary[1][1], Value::Percent(0.75));
+        assert_eq!(summary[2][1], Value::Count(2));
+        assert_eq!(summary[3][1], Value::Decimal(1.5));
+
+        let top = &reports[1].table.rows;
+        assert_eq!(top[0][0], Value::Text("#1".to_string()));
+        assert_eq!(top[0][1], Value::Count(2));
+        assert_eq!(top[0][2], Value::Count(2));
+        assert_eq!(top[1][0], Value::Text("#2".to_string()));
+    }
+}
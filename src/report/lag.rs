@@ -0,0 +1,107 @@
+use crate::commit::Commit;
+use crate::histogram::{self, Durations};
+use crate::report::{Report, Table, Value};
+use crate::stats;
+
+/// Upper bounds of each bucket of the histogram in hours, and their labels
+const BUCKETS: &Durations = &[
+    (1.0 / 60.0, "same minute"),
+    (1.0, "under an hour"),
+    (24.0, "under a day"),
+    (24.0 * 7.0, "under a week"),
+    (24.0 * 28.0, "under 4 weeks"),
+    (f64::INFINITY, "4 weeks or more"),
+];
+
+/// How long commits took to land after they were written, i.e. from their
+/// author date to their commit date. Rebasing, amending, and applying patches
+/// after review all move the commit date, so this is a rough measure of review
+/// and rebase latency. Commits without a commit date are left out.
+pub fn reports(commits: &[Commit]) -> Vec<Report> {
+    let lags: Vec<f64> = commits.iter().filter_map(hours).collect();
+
+    vec![summary(&lags), histogram(&lags)]
+}
+
+/// Hours from when the commit was authored to when it was committed
+fn hours(commit: &Commit) -> Option<f64> {
+    Some(stats::hours_between(commit.date, commit.committed?))
+}
+
+fn summary(lags: &[f64]) -> Report {
+    let later = lags.iter().filter(|&&lag| lag >= BUCKETS[0].0).count();
+
+    let mut table = Table::new(&["measure", "value"]);
+
+    table.push(vec![
+        Value::Text("commits".to_string()),
+        Value::Count(lags.len() as u64),
+    ]);
+    table.push(vec![
+        Value::Text("landed later".to_string()),
+        Value::Percent(later as f64 / lags.len().max(1) as f64),
+    ]);
+
+    for (measure, hours) in stats::hours_summary(lags, &[0.5, 0.9, 0.99], "") {
+        table.push(vec![
+            Value::Text(measure),
+            hours.map_or(Value::Empty, Value::Decimal),
+        ]);
+    }
+
+    Report::new("Landing lag:", table)
+}
+
+fn histogram(lags: &[f64]) -> Report {
+    histogram::of_durations("Landing lag by commit:", ["lag", "commits"], lags, BUCKETS)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn summary_and_histogram() {
+        let date = datetime!(2022-11-28 16:00 -8);
+        let landed = |lag| commit().on(date).committed(date + lag).build();
+
+        let commits = vec![
+            landed(Duration::ZERO),
+            landed(Duration::ZERO),
+            landed(Duration::hours(2)),
+            landed(Duration::days(3)),
+            landed(Duration::days(60)),
+            // Clocks disagree
+            landed(Duration::hours(-1)),
+            commit().on(date).build(),
+        ];
+
+        let reports = super::reports(&commits);
+
+        let summary = &reports[0].table.rows;
+        assert_eq!(summary[0][1], Value::Count(6));
+        assert_eq!(summary[1][1], Value::Percent(0.5));
+        assert_eq!(summary[2][1], Value::Decimal(1.0));
+
+        let counts: Vec<Value> = reports[1]
+            .table
+            .rows
+            .iter()
+            .map(|row| row[1].clone())
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                Value::Count(3),
+                Value::Count(0),
+                Value::Count(1),
+                Value::Count(1),
+                Value::Count(0),
+                Value::Count(1),
+            ]
+        );
+    }
+}
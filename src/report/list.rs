@@ -0,0 +1,166 @@
+use time::Weekday;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// Which commits `yeesh list` prints, of those by the authors of `--author`.
+/// A commit has to match one of the values given for each filter, and any
+/// value when a filter has none.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    /// Hours of the day in each commit's own offset, like `--hours`
+    pub hours: Vec<u8>,
+    /// Days from Monday
+    pub weekdays: Vec<u8>,
+}
+
+impl Filter {
+    pub fn matches(&self, commit: &Commit) -> bool {
+        let weekday = commit.date.weekday().number_days_from_monday();
+
+        (self.hours.is_empty() || self.hours.contains(&commit.date.hour()))
+            && (self.weekdays.is_empty() || self.weekdays.contains(&weekday))
+    }
+}
+
+/// An hour of the day from 0 to 23
+pub fn parse_hour(text: &str) -> Result<u8, String> {
+    match text.parse() {
+        Ok(hour) if hour < 24 => Ok(hour),
+        _ => Err(format!("Invalid hour {}", text)),
+    }
+}
+
+/// Days from Monday for a weekday's name or the first three or more letters
+/// of it, e.g. `sat`
+pub fn parse_weekday(text: &str) -> Result<u8, String> {
+    let name = text.to_lowercase();
+    let mut weekday = Weekday::Monday;
+
+    for _ in 0..7 {
+        if name.chars().count() >= 3 && weekday.to_string().to_lowercase().starts_with(&name) {
+            return Ok(weekday.number_days_from_monday());
+        }
+
+        weekday = weekday.next();
+    }
+
+    Err(format!("Invalid weekday {}", text))
+}
+
+/// Every commit the filter matches, in the order they were read
+pub fn report(commits: &[Commit], filter: &Filter) -> Report {
+    let matching = commits.iter().filter(|commit| filter.matches(commit));
+    self::commits("Commits:", matching)
+}
+
+/// A row for each commit, with its date, author, subject, and lines changed
+pub fn commits<'a>(title: &str, commits: impl IntoIterator<Item = &'a Commit>) -> Report {
+    let mut table = Table::new(&[
+        "commit", "date", "author", "subject", "files", "inserts", "deletes",
+    ]);
+
+    for commit in commits {
+        table.push(vec![
+            Value::Text(commit.hash.chars().take(8).collect()),
+            Value::Date(commit.date.date()),
+            Value::Text(commit.author.name.clone()),
+            Value::Text(commit.subject().to_string()),
+            Value::Count(commit.files as u64),
+            Value::Count(commit.inserts as u64),
+            Value::Count(commit.deletes as u64),
+        ]);
+    }
+
+    Report::new(title, table)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    use super::Filter;
+
+    #[test]
+    fn report() {
+        let commits = vec![
+            // A Tuesday
+            commit()
+                .hash("aaaaaaaaaa")
+                .by("Jon")
+                .on(datetime!(2022-11-29 2:15 -8))
+                .message("Commit aaaaaaaaaa\n\nWith a body")
+                .files(2)
+                .lines(10, 3)
+                .build(),
+            commit()
+                .hash("bbbbbbbbbb")
+                .by("Jon")
+                .on(datetime!(2022-11-28 14:00 -8))
+                .message("Commit bbbbbbbbbb\n\nWith a body")
+                .files(2)
+                .lines(10, 3)
+                .build(),
+            commit()
+                .hash("cccccccccc")
+                .by("Sam")
+                .on(datetime!(2022-11-27 2:30 +1))
+                .message("Commit cccccccccc\n\nWith a body")
+                .files(2)
+                .lines(10, 3)
+                .build(),
+        ];
+
+        let filter = Filter {
+            hours: vec![2],
+            ..Default::default()
+        };
+        let rows = super::report(&commits, &filter).table.rows;
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            vec![
+                Value::Text("aaaaaaaa".to_string()),
+                Value::Date(date!(2022 - 11 - 29)),
+                Value::Text("Jon".to_string()),
+                Value::Text("Commit aaaaaaaaaa".to_string()),
+                Value::Count(2),
+                Value::Count(10),
+                Value::Count(3),
+            ]
+        );
+        assert_eq!(rows[1][0], Value::Text("cccccccc".to_string()));
+
+        let filter = Filter {
+            hours: vec![2, 14],
+            weekdays: vec![1],
+        };
+        let rows = super::report(&commits, &filter).table.rows;
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0][0], Value::Text("aaaaaaaa".to_string()));
+
+        let rows = super::report(&commits, &Filter::default()).table.rows;
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse_hour("0"), Ok(0));
+        assert_eq!(super::parse_hour("23"), Ok(23));
+        assert!(super::parse_hour("24").is_err());
+        assert!(super::parse_hour("2am").is_err());
+
+        assert_eq!(super::parse_weekday("Mon"), Ok(0));
+        assert_eq!(super::parse_weekday("saturday"), Ok(5));
+        assert_eq!(super::parse_weekday("SUN"), Ok(6));
+        assert_eq!(super::parse_weekday("thurs"), Ok(3));
+        assert!(super::parse_weekday("su").is_err());
+        assert!(super::parse_weekday("sunny").is_err());
+        assert!(super::parse_weekday("someday").is_err());
+    }
+}
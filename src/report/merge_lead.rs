@@ -0,0 +1,191 @@
+use time::OffsetDateTime;
+
+use crate::commit::Commit;
+use crate::histogram::{self, Durations};
+use crate::report::{Report, Table, Value};
+use crate::stats;
+
+/// Upper bounds of each bucket of the histogram in hours, and their labels
+const BUCKETS: &Durations = &[
+    (1.0, "under an hour"),
+    (24.0, "under a day"),
+    (24.0 * 3.0, "under 3 days"),
+    (24.0 * 7.0, "under a week"),
+    (24.0 * 28.0, "under 4 weeks"),
+    (f64::INFINITY, "4 weeks or more"),
+];
+
+/// A branch merged in, with when its first and last commits were authored
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge {
+    pub first: OffsetDateTime,
+    pub last: OffsetDateTime,
+    /// When the merge landed
+    pub merged: OffsetDateTime,
+}
+
+impl Merge {
+    /// Hours from the branch's first commit to the merge
+    fn hours_from_first(&self) -> f64 {
+        stats::hours_between(self.first, self.merged)
+    }
+
+    fn hours_from_last(&self) -> f64 {
+        stats::hours_between(self.last, self.merged)
+    }
+}
+
+/// The branches each merge commit brings in, i.e. the commits reachable from
+/// each parent after the first but not from the first, which
+/// `branch_times(base, merged)` gives as Unix timestamps. Merges of branches
+/// with no commits of their own are left out.
+pub fn merges<F>(commits: &[Commit], mut branch_times: F) -> Vec<Merge>
+where
+    F: FnMut(&str, &str) -> Vec<i64>,
+{
+    let mut merges = Vec::new();
+
+    for commit in commits.iter().filter(|commit| commit.parents.len() > 1) {
+        let base = &commit.parents[0];
+
+        for parent in &commit.parents[1..] {
+            let times = branch_times(base, parent);
+
+            let (first, last) = match (times.iter().min(), times.iter().max()) {
+                (Some(&first), Some(&last)) => (first, last),
+                _ => continue,
+            };
+
+            let (first, last) = match (
+                OffsetDateTime::from_unix_timestamp(first),
+                OffsetDateTime::from_unix_timestamp(last),
+            ) {
+                (Ok(first), Ok(last)) => (first, last),
+                _ => continue,
+            };
+
+            merges.push(Merge {
+                first,
+                last,
+                merged: commit.committed.unwrap_or(commit.date),
+            });
+        }
+    }
+
+    merges
+}
+
+/// How long branches took to be merged from their first and last commits,
+/// roughly how long pull requests took from the first work on them to landing
+/// and from the last push to landing, without asking a provider
+pub fn reports(merges: &[Merge]) -> Vec<Report> {
+    vec![summary(merges), histogram(merges)]
+}
+
+fn summary(merges: &[Merge]) -> Report {
+    let from_first: Vec<f64> = merges.iter().map(Merge::hours_from_first).collect();
+    let from_last: Vec<f64> = merges.iter().map(Merge::hours_from_last).collect();
+
+    let mut table = Table::new(&["measure", "value"]);
+
+    table.push(vec![
+        Value::Text("merged branches".to_string()),
+        Value::Count(merges.len() as u64),
+    ]);
+
+    let rows = stats::hours_summary(&from_first, &[0.5, 0.9], " from first commit")
+        .into_iter()
+        .chain(stats::hours_summary(
+            &from_last,
+            &[0.5, 0.9],
+            " from last commit",
+        ));
+
+    for (measure, hours) in rows {
+        table.push(vec![
+            Value::Text(measure),
+            hours.map_or(Value::Empty, Value::Decimal),
+        ]);
+    }
+
+    Report::new("Merge lead time:", table)
+}
+
+fn histogram(merges: &[Merge]) -> Report {
+    let from_first: Vec<f64> = merges.iter().map(Merge::hours_from_first).collect();
+
+    histogram::of_durations(
+        "Merge lead time by branch:",
+        ["from first commit", "merges"],
+        &from_first,
+        BUCKETS,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn lead_times() {
+        let merged = datetime!(2022-11-30 0:00 UTC).unix_timestamp();
+        let hours = |hours: i64| merged - hours * 3600;
+
+        let commits = vec![
+            commit()
+                .parents(&["a", "b"])
+                .on(datetime!(2022-11-28 0:00 UTC))
+                .committed(datetime!(2022-11-30 0:00 UTC))
+                .build(),
+            commit()
+                .parents(&[])
+                .on(datetime!(2022-11-28 0:00 UTC))
+                .committed(datetime!(2022-11-30 0:00 UTC))
+                .build(),
+            // Octopus
+            commit()
+                .parents(&["c", "d", "e"])
+                .on(datetime!(2022-11-28 0:00 UTC))
+                .committed(datetime!(2022-11-30 0:00 UTC))
+                .build(),
+            // Already merged
+            commit()
+                .parents(&["f", "g"])
+                .on(datetime!(2022-11-28 0:00 UTC))
+                .committed(datetime!(2022-11-30 0:00 UTC))
+                .build(),
+        ];
+
+        let merges = super::merges(&commits, |base, merged| match (base, merged) {
+            ("a", "b") => vec![hours(2), hours(50), hours(10)],
+            ("c", "d") => vec![hours(4)],
+            ("c", "e") => vec![hours(24 * 30), hours(6)],
+            _ => vec![],
+        });
+
+        assert_eq!(merges.len(), 3);
+
+        let reports = super::reports(&merges);
+
+        let summary = &reports[0].table.rows;
+        assert_eq!(summary[0][1], Value::Count(3));
+        assert_eq!(summary[1][1], Value::Decimal(50.0));
+        assert_eq!(summary[3][1], Value::Decimal(4.0));
+
+        let counts: Vec<&Value> = reports[1].table.rows.iter().map(|row| &row[1]).collect();
+        assert_eq!(
+            counts,
+            vec![
+                &Value::Count(0),
+                &Value::Count(1),
+                &Value::Count(1),
+                &Value::Count(0),
+                &Value::Count(0),
+                &Value::Count(1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use crate::commit::{self, Commit};
+use crate::report::{Report, Table, Value};
+
+/// Authors of the commits whose first commit ever is among them rather than
+/// the earlier ones, e.g. to thank in release notes. They're listed in the
+/// order they first committed, with their commits since.
+pub fn report(commits: &[Commit], earlier: &[Commit]) -> Report {
+    let known: HashSet<&str> = earlier
+        .iter()
+        .map(|commit| commit.author.email.as_str())
+        .collect();
+
+    let mut newcomers: Vec<(&str, &Commit, usize)> = commit::by_author(commits)
+        .into_iter()
+        .filter(|(author, _)| !known.contains(author.email.as_str()))
+        .map(|(author, commits)| {
+            let first = commits.iter().min_by_key(|commit| commit.date).unwrap();
+            (author.name.as_str(), *first, commits.len())
+        })
+        .collect();
+
+    newcomers.sort_by(|a, b| a.1.date.cmp(&b.1.date).then(a.0.cmp(b.0)));
+
+    let mut table = Table::new(&["author", "first commit", "subject", "commits"]);
+
+    for (name, first, count) in newcomers {
+        table.push(vec![
+            Value::Text(name.to_string()),
+            Value::Date(first.date.date()),
+            Value::Text(first.subject().to_string()),
+            Value::Count(count as u64),
+        ]);
+    }
+
+    Report::new("New contributors:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn report() {
+        let commits = vec![
+            commit()
+                .by("Sam")
+                .on(datetime!(2022-11-30 9:00 UTC))
+                .message("Commit by Sam")
+                .build(),
+            commit()
+                .by("Jon")
+                .on(datetime!(2022-11-29 9:00 UTC))
+                .message("Commit by Jon")
+                .build(),
+            commit()
+                .by("Ana")
+                .on(datetime!(2022-11-28 16:00 -8))
+                .message("Commit by Ana")
+                .build(),
+            commit()
+                .by("Sam")
+                .on(datetime!(2022-11-28 9:00 UTC))
+                .message("Commit by Sam")
+                .build(),
+        ];
+        let earlier = vec![commit()
+            .by("Jon")
+            .on(datetime!(2022-01-03 9:00 UTC))
+            .message("Commit by Jon")
+            .build()];
+
+        let report = super::report(&commits, &earlier);
+        let rows = &report.table.rows;
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            vec![
+                Value::Text("Sam".to_string()),
+                Value::Date(date!(2022 - 11 - 28)),
+                Value::Text("Commit by Sam".to_string()),
+                Value::Count(2),
+            ]
+        );
+        assert_eq!(rows[1][0], Value::Text("Ana".to_string()));
+        assert_eq!(rows[1][3], Value::Count(1));
+
+        // Everyone's new without anything earlier
+        assert_eq!(super::report(&commits, &[]).table.rows.len(), 3);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::commit::Author;
+use crate::report::half_life::directory_of;
+use crate::report::{Report, Table, Value};
+
+/// How many authors are listed
+const TOP: usize = 10;
+
+/// Surviving lines by author, counted by lowercase email and named as first
+/// seen
+#[derive(Debug, Default)]
+struct Lines<'a> {
+    by_email: HashMap<String, (&'a str, u64)>,
+    total: u64,
+}
+
+impl<'a> Lines<'a> {
+    fn add(&mut self, author: &'a Author) {
+        let lines = self
+            .by_email
+            .entry(author.email.to_lowercase())
+            .or_insert((&author.name, 0));
+
+        lines.1 += 1;
+        self.total += 1;
+    }
+
+    /// Authors with the most lines first
+    fn owners(&self) -> Vec<(&'a str, u64)> {
+        let mut owners: Vec<(&str, u64)> = self.by_email.values().copied().collect();
+        owners.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        owners
+    }
+}
+
+/// Whose lines make up the code as it is now, given who authored each line of
+/// the sampled files, overall and per directory. Unlike reports on commits,
+/// lines since rewritten or deleted don't count.
+pub fn reports(blamed: &[(String, Vec<Author>)]) -> Vec<Report> {
+    let mut overall = Lines::default();
+    let mut by_directory: BTreeMap<String, Lines> = BTreeMap::new();
+
+    for (path, authors) in blamed {
+        for author in authors {
+            overall.add(author);
+            by_directory
+                .entry(directory_of(path))
+                .or_default()
+                .add(author);
+        }
+    }
+
+    vec![owners(&overall), directories(&by_directory)]
+}
+
+fn owners(lines: &Lines) -> Report {
+    let mut table = Table::new(&["author", "lines", "share"]);
+
+    for (name, count) in lines.owners().into_iter().take(TOP) {
+        table.push(vec![
+            Value::Text(name.to_string()),
+            Value::Count(count),
+            Value::Percent(count as f64 / lines.total.max(1) as f64),
+        ]);
+    }
+
+    Report::new("Current ownership:", table)
+}
+
+fn directories(by_directory: &BTreeMap<String, Lines>) -> Report {
+    let mut table = Table::new(&["directory", "lines", "authors", "top owner", "share"]);
+
+    for (directory, lines) in by_directory {
+        let owners = lines.owners();
+        let (owner, count) = owners[0];
+
+        table.push(vec![
+            Value::Text(directory.clone()),
+            Value::Count(lines.total),
+            Value::Count(owners.len() as u64),
+            Value::Text(owner.to_string()),
+            Value::Percent(count as f64 / lines.total.max(1) as f64),
+        ]);
+    }
+
+    Report::new("Current ownership by directory:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commit::Author;
+    use crate::report::Value;
+
+    fn lines(emails: &[&str]) -> Vec<Author> {
+        emails
+            .iter()
+            .map(|email| Author::new(email.to_string(), email.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn overall_and_by_directory() {
+        let blamed = vec![
+            ("README.md".to_string(), lines(&["jon"])),
+            (
+                "src/main.rs".to_string(),
+                lines(&["jon", "not jon", "not jon"]),
+            ),
+            ("src/lib.rs".to_string(), lines(&["NOT JON", "zed"])),
+            ("logo.png".to_string(), lines(&[])),
+        ];
+
+        let reports = super::reports(&blamed);
+        let text = |text: &str| Value::Text(text.to_string());
+
+        let owners = &reports[0].table.rows;
+        assert_eq!(owners.len(), 3);
+        assert_eq!(owners[0][0], text("not jon"));
+        assert_eq!(owners[0][1], Value::Count(3));
+        assert_eq!(owners[0][2], Value::Percent(0.5));
+        assert_eq!(owners[1][0], text("jon"));
+
+        let directories = &reports[1].table.rows;
+        assert_eq!(directories.len(), 2);
+        assert_eq!(
+            directories[0],
+            vec![
+                text("."),
+                Value::Count(1),
+                Value::Count(1),
+                text("jon"),
+                Value::Percent(1.0)
+            ]
+        );
+        assert_eq!(directories[1][0], text("src"));
+        assert_eq!(directories[1][2], Value::Count(3));
+        assert_eq!(directories[1][3], text("not jon"));
+        assert_eq!(directories[1][4], Value::Percent(0.6));
+    }
+}
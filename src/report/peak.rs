@@ -0,0 +1,188 @@
+use crate::commit::Commit;
+use crate::histogram::{self, Weight, WEEKDAYS};
+use crate::report::{Report, Table, Value};
+
+/// Candidate window lengths in hours. Longer windows are preferred when they
+/// are just as dense.
+const WINDOW_HOURS: [usize; 2] = [4, 3];
+
+/// The stretch of the week where commits are most concentrated, e.g. Tuesday
+/// through Thursday from 13:00 to 16:00
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    /// Weekday starting the window where Monday is zero
+    pub first_day: usize,
+    pub days: usize,
+    pub first_hour: usize,
+    pub hours: usize,
+    /// Or whatever they're weighted by
+    pub commits: u64,
+}
+
+impl Window {
+    pub fn days_label(&self) -> String {
+        let first = WEEKDAYS[self.first_day];
+        let last = WEEKDAYS[(self.first_day + self.days - 1) % 7];
+
+        if self.days == 1 {
+            first.to_string()
+        } else {
+            format!("{}–{}", first, last)
+        }
+    }
+
+    pub fn hours_label(&self) -> String {
+        let last = (self.first_hour + self.hours) % 24;
+        format!("{:02}:00–{:02}:00", self.first_hour, last)
+    }
+}
+
+/// Find the densest window of hours across the whole week, then the run of
+/// neighbouring weekdays where that window is at least as busy as it is on an
+/// average day. Windows may wrap past midnight and past the end of the week.
+pub fn detect(commits: &[Commit], weight: Weight) -> Option<Window> {
+    let counts = histogram::by_weekday_and_hour(commits, weight);
+
+    let mut by_hour = [0; 24];
+
+    for day in counts.iter() {
+        for (hour, count) in day.iter().enumerate() {
+            by_hour[hour] += count;
+        }
+    }
+
+    let (first_hour, hours) = densest_hours(&by_hour)?;
+
+    let by_day: Vec<u64> = counts
+        .iter()
+        .map(|day| (0..hours).map(|i| day[(first_hour + i) % 24]).sum())
+        .collect();
+
+    let average = by_day.iter().sum::<u64>() as f64 / 7.0;
+
+    // Ties go to the earliest day in the week
+    let peak_day = (0..7).rev().max_by_key(|day| by_day[*day])?;
+
+    let mut first_day = peak_day;
+    let mut days = 1;
+
+    while days < 7 && by_day[(first_day + 6) % 7] as f64 >= average {
+        first_day = (first_day + 6) % 7;
+        days += 1;
+    }
+
+    while days < 7 && by_day[(first_day + days) % 7] as f64 >= average {
+        days += 1;
+    }
+
+    let commits = (0..days).map(|i| by_day[(first_day + i) % 7]).sum();
+
+    Some(Window {
+        first_day,
+        days,
+        first_hour,
+        hours,
+        commits,
+    })
+}
+
+pub fn report(commits: &[Commit], weight: Weight) -> Report {
+    let mut table = Table::new(&["days", "hours", weight.name(), "share"]);
+
+    if let Some(window) = detect(commits, weight) {
+        let total: u64 = commits.iter().map(|commit| weight.of(commit)).sum();
+        let share = window.commits as f64 / total as f64;
+
+        table.push(vec![
+            Value::Text(window.days_label()),
+            Value::Text(window.hours_label()),
+            Value::Count(window.commits),
+            Value::Percent(share),
+        ]);
+    }
+
+    Report::new("Peak activity:", table)
+}
+
+/// Returns the first hour and length of the window with the most commits per
+/// hour, or `None` if there are no commits at all
+fn densest_hours(by_hour: &[u64; 24]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize, f64)> = None;
+
+    for hours in WINDOW_HOURS {
+        for first_hour in 0..24 {
+            let total: u64 = (0..hours).map(|i| by_hour[(first_hour + i) % 24]).sum();
+            let density = total as f64 / hours as f64;
+
+            let is_better = match best {
+                None => total > 0,
+                Some((_, _, best_density)) => density > best_density,
+            };
+
+            if is_better {
+                best = Some((first_hour, hours, density));
+            }
+        }
+    }
+
+    best.map(|(first_hour, hours, _)| (first_hour, hours))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use crate::commit::Commit;
+    use crate::histogram::Weight;
+    use crate::test_support::commit;
+
+    /// Commits on the given day of the week of 2022-11-28, which is a Monday
+    fn commits_at(weekday: i64, hour: i64, count: usize) -> Vec<Commit> {
+        let date = datetime!(2022-11-28 00:30 -8) + Duration::days(weekday) + Duration::hours(hour);
+
+        (0..count).map(|_| commit().on(date).build()).collect()
+    }
+
+    #[test]
+    fn midweek_afternoons() {
+        let mut commits = Vec::new();
+
+        for weekday in 1..=3 {
+            for hour in 13..16 {
+                commits.extend(commits_at(weekday, hour, 5));
+            }
+        }
+
+        commits.extend(commits_at(0, 9, 1));
+        commits.extend(commits_at(4, 22, 2));
+
+        let window = super::detect(&commits, Weight::Count).unwrap();
+
+        assert_eq!(window.days_label(), "Tue–Thu");
+        assert_eq!(window.hours_label(), "13:00–16:00");
+        assert_eq!(window.commits, 45);
+    }
+
+    #[test]
+    fn wraps_past_midnight_and_sunday() {
+        let mut commits = Vec::new();
+
+        for weekday in [6, 0] {
+            for hour in [22, 23, 0] {
+                commits.extend(commits_at(weekday, hour, 3));
+            }
+        }
+
+        commits.extend(commits_at(3, 12, 1));
+
+        let window = super::detect(&commits, Weight::Count).unwrap();
+
+        assert_eq!(window.days_label(), "Sun–Mon");
+        assert_eq!(window.hours_label(), "22:00–01:00");
+    }
+
+    #[test]
+    fn no_commits() {
+        assert_eq!(super::detect(&[], Weight::Count), None);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::commit::Commit;
+use crate::provider::Pipeline;
+use crate::report::{Report, Table, Value};
+use crate::stats::{median, percentile};
+
+/// How many failing commits are listed
+const TOP: usize = 10;
+
+/// How reliably and quickly CI pipelines run, and the most recent commits in
+/// history whose latest pipeline failed
+pub fn reports(commits: &[Commit], pipelines: &[Pipeline]) -> Vec<Report> {
+    // Pipelines are newest first, so the first seen for a commit is its latest
+    let mut latest: HashMap<&str, &Pipeline> = HashMap::new();
+    for pipeline in pipelines {
+        latest.entry(&pipeline.commit).or_insert(pipeline);
+    }
+
+    vec![
+        summary(commits, pipelines, &latest),
+        failing(commits, &latest),
+    ]
+}
+
+fn summary(
+    commits: &[Commit],
+    pipelines: &[Pipeline],
+    latest: &HashMap<&str, &Pipeline>,
+) -> Report {
+    let finished: Vec<&Pipeline> = pipelines
+        .iter()
+        .filter(|pipeline| pipeline.succeeded() || pipeline.failed())
+        .collect();
+
+    let succeeded = finished
+        .iter()
+        .filter(|pipeline| pipeline.succeeded())
+        .count();
+    let success_rate = succeeded as f64 / finished.len().max(1) as f64;
+
+    let minutes: Vec<f64> = finished.iter().map(|pipeline| pipeline.minutes()).collect();
+
+    let covered = commits
+        .iter()
+        .filter(|commit| latest.contains_key(commit.hash.as_str()))
+        .count();
+
+    let mut table = Table::new(&["measure", "value"]);
+
+    let rows = [
+        ("pipelines", Value::Count(pipelines.len() as u64)),
+        (
+            "commits in history with pipelines",
+            Value::Count(covered as u64),
+        ),
+        (
+            "pipelines per commit",
+            Value::Ratio(pipelines.len() as f64 / latest.len().max(1) as f64),
+        ),
+        (
+            "finished pipelines succeeding",
+            Value::Percent(success_rate),
+        ),
+        ("median minutes", minutes_value(median(minutes.clone()))),
+        ("p90 minutes", minutes_value(percentile(minutes, 0.9))),
+    ];
+
+    for (measure, value) in rows {
+        table.push(vec![Value::Text(measure.to_string()), value]);
+    }
+
+    Report::new("Pipelines:", table)
+}
+
+fn failing(commits: &[Commit], latest: &HashMap<&str, &Pipeline>) -> Report {
+    let mut table = Table::new(&["commit", "author", "subject"]);
+
+    let failing = commits
+        .iter()
+        .filter(|commit| latest.get(commit.hash.as_str()).is_some_and(|p| p.failed()))
+        .take(TOP);
+
+    for commit in failing {
+        table.push(vec![
+            Value::Text(commit.hash.chars().take(8).collect()),
+            Value::Text(commit.author.name.clone()),
+            Value::Text(commit.subject().to_string()),
+        ]);
+    }
+
+    Report::new("Commits with failing pipelines:", table)
+}
+
+fn minutes_value(minutes: Option<f64>) -> Value {
+    minutes.map_or(Value::Empty, Value::Decimal)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use crate::provider::Pipeline;
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    fn pipeline(commit: &str, status: &str, minutes: i64) -> Pipeline {
+        let created_at = datetime!(2022-11-28 16:00 UTC);
+
+        Pipeline {
+            commit: commit.to_string(),
+            status: status.to_string(),
+            created_at,
+            updated_at: created_at + Duration::minutes(minutes),
+        }
+    }
+
+    #[test]
+    fn summary_and_failing() {
+        let commits = vec![
+            commit().hash("aaa").message("Commit aaa").build(),
+            commit().hash("bbb").message("Commit bbb").build(),
+            commit().hash("ccc").message("Commit ccc").build(),
+        ];
+
+        // Newest first: bbb failed before passing on a retry
+        let pipelines = vec![
+            pipeline("aaa", "failed", 10),
+            pipeline("bbb", "success", 20),
+            pipeline("bbb", "failed", 5),
+            pipeline("elsewhere", "running", 1),
+        ];
+
+        let reports = super::reports(&commits, &pipelines);
+
+        let summary = &reports[0].table.rows;
+        assert_eq!(summary[0][1], Value::Count(4));
+        assert_eq!(summary[1][1], Value::Count(2));
+        assert_eq!(summary[2][1], Value::Ratio(4.0 / 3.0));
+        assert_eq!(summary[3][1], Value::Percent(1.0 / 3.0));
+        assert_eq!(summary[4][1], Value::Decimal(10.0));
+
+        let failing = &reports[1].table.rows;
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0][0], Value::Text("aaa".to_string()));
+    }
+}
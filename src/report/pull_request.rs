@@ -0,0 +1,162 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::commit::Commit;
+use crate::provider::PullRequest;
+use crate::report::{Report, Table, Value};
+use crate::stats::{median, percentile};
+
+/// How long pull requests take to be reviewed and merged, overall and for
+/// each author, and how much of the history arrived through them
+pub fn reports(commits: &[Commit], pulls: &[PullRequest]) -> Vec<Report> {
+    let merged: Vec<&PullRequest> = pulls
+        .iter()
+        .filter(|pull| pull.merged_at.is_some())
+        .collect();
+
+    vec![summary(commits, &merged), by_author(&merged)]
+}
+
+fn summary(commits: &[Commit], merged: &[&PullRequest]) -> Report {
+    let merge_commits: HashSet<&str> = merged
+        .iter()
+        .filter_map(|pull| pull.merge_commit.as_deref())
+        .collect();
+
+    let landed = commits
+        .iter()
+        .filter(|commit| merge_commits.contains(commit.hash.as_str()))
+        .count();
+
+    let to_merge: Vec<f64> = merged
+        .iter()
+        .filter_map(|pull| pull.hours_to_merge())
+        .collect();
+    let to_review: Vec<f64> = merged
+        .iter()
+        .filter_map(|pull| pull.hours_to_first_review())
+        .collect();
+
+    let unreviewed = merged.len() - to_review.len();
+
+    let mut table = Table::new(&["measure", "value"]);
+
+    let rows = [
+        ("merged pull requests", Value::Count(merged.len() as u64)),
+        ("merged commits in history", Value::Count(landed as u64)),
+        ("unreviewed", Value::Count(unreviewed as u64)),
+        ("median hours to merge", hours(median(to_merge.clone()))),
+        ("p90 hours to merge", hours(percentile(to_merge, 0.9))),
+        (
+            "median hours to first review",
+            hours(median(to_review.clone())),
+        ),
+        (
+            "p90 hours to first review",
+            hours(percentile(to_review, 0.9)),
+        ),
+    ];
+
+    for (measure, value) in rows {
+        table.push(vec![Value::Text(measure.to_string()), value]);
+    }
+
+    Report::new("Pull requests:", table)
+}
+
+fn by_author(merged: &[&PullRequest]) -> Report {
+    let mut authors: BTreeMap<&str, Vec<&PullRequest>> = BTreeMap::new();
+
+    for pull in merged {
+        authors.entry(&pull.author).or_default().push(pull);
+    }
+
+    let mut authors: Vec<(&str, Vec<&PullRequest>)> = authors.into_iter().collect();
+    authors.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+
+    let mut table = Table::new(&[
+        "author",
+        "merged",
+        "median hours to merge",
+        "median hours to review",
+    ]);
+
+    for (author, pulls) in authors {
+        let to_merge = pulls
+            .iter()
+            .filter_map(|pull| pull.hours_to_merge())
+            .collect();
+        let to_review = pulls
+            .iter()
+            .filter_map(|pull| pull.hours_to_first_review())
+            .collect();
+
+        table.push(vec![
+            Value::Text(author.to_string()),
+            Value::Count(pulls.len() as u64),
+            hours(median(to_merge)),
+            hours(median(to_review)),
+        ]);
+    }
+
+    Report::new("Pull requests by author:", table)
+}
+
+fn hours(hours: Option<f64>) -> Value {
+    hours.map_or(Value::Empty, Value::Decimal)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use crate::provider::PullRequest;
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    fn pull(
+        author: &str,
+        merge_hours: Option<i64>,
+        review_hours: Option<i64>,
+        merge_commit: &str,
+    ) -> PullRequest {
+        let created_at = datetime!(2022-11-28 16:00 UTC);
+
+        PullRequest {
+            number: 1,
+            title: "Things".to_string(),
+            author: author.to_string(),
+            created_at,
+            merged_at: merge_hours.map(|hours| created_at + Duration::hours(hours)),
+            first_review_at: review_hours.map(|hours| created_at + Duration::hours(hours)),
+            merge_commit: Some(merge_commit.to_string()),
+        }
+    }
+
+    #[test]
+    fn summary_and_authors() {
+        let commits = vec![commit().hash("abc").build(), commit().hash("def").build()];
+
+        let pulls = vec![
+            pull("jon", Some(10), Some(2), "abc"),
+            pull("jon", Some(20), None, "elsewhere"),
+            pull("not-jon", Some(30), Some(4), "zzz"),
+            pull("not-jon", None, None, "def"),
+        ];
+
+        let reports = super::reports(&commits, &pulls);
+
+        let summary = &reports[0].table.rows;
+        assert_eq!(summary[0][1], Value::Count(3));
+        assert_eq!(summary[1][1], Value::Count(1));
+        assert_eq!(summary[2][1], Value::Count(1));
+        assert_eq!(summary[3][1], Value::Decimal(20.0));
+        assert_eq!(summary[5][1], Value::Decimal(3.0));
+
+        let authors = &reports[1].table.rows;
+        assert_eq!(authors[0][0], Value::Text("jon".to_string()));
+        assert_eq!(authors[0][1], Value::Count(2));
+        assert_eq!(authors[0][2], Value::Decimal(15.0));
+        assert_eq!(authors[1][0], Value::Text("not-jon".to_string()));
+        assert_eq!(authors[1][1], Value::Count(1));
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+
+use time::OffsetDateTime;
+
+use crate::commit::Commit;
+use crate::git::Tag;
+use crate::report::{Report, Table, Value};
+use crate::stats::median;
+
+/// A tagged commit along with the work that went into it since the previous
+/// release
+#[derive(Debug, Clone, PartialEq)]
+pub struct Release<'a> {
+    pub tag: &'a str,
+    pub date: OffsetDateTime,
+    /// `None` for the first release
+    pub days_since_previous: Option<f64>,
+    pub commits: usize,
+    pub contributors: usize,
+}
+
+/// Releases from oldest to newest. Tags pointing at commits outside the
+/// analyzed history are skipped.
+///
+/// Commits belong to the first release dated on or after them. Dates are used
+/// rather than ancestry so branches merged into a release are counted without
+/// walking the commit graph.
+pub fn releases<'a>(commits: &[Commit], tags: &'a [Tag]) -> Vec<Release<'a>> {
+    let mut tagged: Vec<(&str, OffsetDateTime)> = tags
+        .iter()
+        .filter_map(|tag| {
+            let commit = commits.iter().find(|commit| commit.hash == tag.hash)?;
+            Some((tag.name.as_str(), commit.date))
+        })
+        .collect();
+
+    tagged.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(b.0)));
+
+    let mut releases = Vec::new();
+    let mut previous: Option<OffsetDateTime> = None;
+
+    for (tag, date) in tagged {
+        let included: Vec<&Commit> = commits
+            .iter()
+            .filter(|commit| commit.date <= date)
+            .filter(|commit| previous.is_none_or(|previous| commit.date > previous))
+            .collect();
+
+        let contributors: HashSet<&str> = included
+            .iter()
+            .map(|commit| commit.author.email.as_str())
+            .collect();
+
+        releases.push(Release {
+            tag,
+            date,
+            days_since_previous: previous
+                .map(|previous| (date - previous).as_seconds_f64() / 86400.0),
+            commits: included.len(),
+            contributors: contributors.len(),
+        });
+
+        previous = Some(date);
+    }
+
+    releases
+}
+
+/// Every release followed by a summary of the typical release
+pub fn reports(commits: &[Commit], tags: &[Tag]) -> Vec<Report> {
+    let releases = releases(commits, tags);
+
+    let mut table = Table::new(&["tag", "date", "days", "commits", "contributors"]);
+
+    for release in &releases {
+        table.push(vec![
+            Value::Text(release.tag.to_string()),
+            Value::Date(release.date.date()),
+            release
+                .days_since_previous
+                .map_or(Value::Empty, Value::Decimal),
+            Value::Count(release.commits as u64),
+            Value::Count(release.contributors as u64),
+        ]);
+    }
+
+    let days: Vec<f64> = releases
+        .iter()
+        .filter_map(|release| release.days_since_previous)
+        .collect();
+    let commits: Vec<f64> = releases
+        .iter()
+        .map(|release| release.commits as f64)
+        .collect();
+    let contributors: Vec<f64> = releases
+        .iter()
+        .map(|release| release.contributors as f64)
+        .collect();
+
+    let mut cadence = Table::new(&[
+        "releases",
+        "median days",
+        "median commits",
+        "median contributors",
+    ]);
+    cadence.push(vec![
+        Value::Count(releases.len() as u64),
+        median(days).map_or(Value::Empty, Value::Decimal),
+        median(commits).map_or(Value::Empty, Value::Decimal),
+        median(contributors).map_or(Value::Empty, Value::Decimal),
+    ]);
+
+    vec![
+        Report::new("Releases:", table),
+        Report::new("Release cadence:", cadence),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use crate::git::Tag;
+    use crate::test_support::commit;
+
+    fn tag(name: &str, hash: &str) -> Tag {
+        Tag::new(name.to_string(), hash.to_string())
+    }
+
+    #[test]
+    fn releases_between_tags() {
+        // Newest first, just like git log
+        let commits = vec![
+            commit()
+                .hash("e")
+                .email("jon")
+                .on(datetime!(2022-11-01 12:00 -8) + Duration::days(30))
+                .build(),
+            commit()
+                .hash("d")
+                .email("not jon")
+                .on(datetime!(2022-11-01 12:00 -8) + Duration::days(20))
+                .build(),
+            commit()
+                .hash("c")
+                .email("jon")
+                .on(datetime!(2022-11-01 12:00 -8) + Duration::days(14))
+                .build(),
+            commit()
+                .hash("b")
+                .email("not jon")
+                .on(datetime!(2022-11-01 12:00 -8) + Duration::days(7))
+                .build(),
+            commit()
+                .hash("a")
+                .email("jon")
+                .on(datetime!(2022-11-01 12:00 -8) + Duration::days(0))
+                .build(),
+        ];
+
+        let tags = vec![tag("v2", "d"), tag("v1", "b"), tag("elsewhere", "z")];
+
+        let releases = super::releases(&commits, &tags);
+
+        assert_eq!(releases.len(), 2);
+
+        assert_eq!(releases[0].tag, "v1");
+        assert_eq!(releases[0].days_since_previous, None);
+        assert_eq!(releases[0].commits, 2);
+        assert_eq!(releases[0].contributors, 2);
+
+        assert_eq!(releases[1].tag, "v2");
+        assert_eq!(releases[1].days_since_previous, Some(13.0));
+        assert_eq!(releases[1].commits, 2);
+        assert_eq!(releases[1].contributors, 2);
+    }
+}
@@ -0,0 +1,105 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// A row for each repository reported on together, most recently active first
+pub fn report(repos: &[(&str, &[Commit])]) -> Report {
+    let mut table = Table::new(&[
+        "repository",
+        "commits",
+        "authors",
+        "lines changed",
+        "first",
+        "last",
+        "share",
+    ]);
+
+    let total: usize = repos.iter().map(|(_, commits)| commits.len()).sum();
+
+    let mut repos = repos.to_vec();
+    repos.sort_by_key(|(name, commits)| {
+        let last = commits.iter().map(|commit| commit.date).max();
+        (Reverse(last), *name)
+    });
+
+    for (name, commits) in repos {
+        let authors: HashSet<&str> = commits
+            .iter()
+            .map(|commit| commit.author.email.as_str())
+            .collect();
+
+        let date = |date: Option<time::OffsetDateTime>| match date {
+            Some(date) => Value::Date(date.date()),
+            None => Value::Empty,
+        };
+
+        table.push(vec![
+            Value::Text(name.to_string()),
+            Value::Count(commits.len() as u64),
+            Value::Count(authors.len() as u64),
+            Value::Count(commits.iter().map(Commit::churn).sum()),
+            date(commits.iter().map(|commit| commit.date).min()),
+            date(commits.iter().map(|commit| commit.date).max()),
+            Value::Percent(commits.len() as f64 / total.max(1) as f64),
+        ]);
+    }
+
+    Report::new("Repositories:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn most_recently_active_first() {
+        let old = vec![
+            commit()
+                .email("jon")
+                .on(datetime!(2022-11-02 12:00 UTC))
+                .lines(2, 1)
+                .build(),
+            commit()
+                .email("jon")
+                .on(datetime!(2022-11-01 12:00 UTC))
+                .lines(2, 1)
+                .build(),
+            commit()
+                .email("not jon")
+                .on(datetime!(2022-10-01 12:00 UTC))
+                .lines(2, 1)
+                .build(),
+        ];
+        let new = vec![commit()
+            .email("jon")
+            .on(datetime!(2023-01-01 12:00 UTC))
+            .lines(2, 1)
+            .build()];
+
+        let report = super::report(&[("old", &old), ("new", &new), ("empty", &[])]);
+        let rows = &report.table.rows;
+
+        let text = |text: &str| Value::Text(text.to_string());
+
+        assert_eq!(rows[0][0], text("new"));
+        assert_eq!(
+            rows[1],
+            vec![
+                text("old"),
+                Value::Count(3),
+                Value::Count(2),
+                Value::Count(9),
+                Value::Date(date!(2022 - 10 - 01)),
+                Value::Date(date!(2022 - 11 - 02)),
+                Value::Percent(0.75),
+            ]
+        );
+        assert_eq!(rows[2][0], text("empty"));
+        assert_eq!(rows[2][4], Value::Empty);
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use crate::commit::{self, Commit};
+use crate::report::{Report, Table, Value};
+
+/// Months after their first commit that authors are checked for another
+pub const MONTHS_LATER: [u32; 3] = [3, 6, 12];
+
+/// Authors grouped by the month of their first commit, and the share of each
+/// group still committing 3, 6, and 12 months later, i.e. with a commit in that
+/// month or after. Months after the latest commit can't be told yet so are
+/// left empty.
+pub fn report(commits: &[Commit]) -> Report {
+    // Each author's first and last months as months since year zero
+    let spans: Vec<(i32, i32)> = commit::by_author(commits)
+        .into_iter()
+        .map(|(_, commits)| {
+            let months = commits.iter().map(|commit| month(commit));
+            (months.clone().min().unwrap(), months.max().unwrap())
+        })
+        .collect();
+
+    let latest = spans.iter().map(|(_, last)| *last).max().unwrap_or(0);
+
+    let mut cohorts: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+
+    for (first, last) in spans {
+        cohorts.entry(first).or_default().push(last);
+    }
+
+    let later: Vec<String> = MONTHS_LATER
+        .iter()
+        .map(|months| format!("{} months", months))
+        .collect();
+    let columns: Vec<&str> = ["cohort", "authors"]
+        .into_iter()
+        .chain(later.iter().map(String::as_str))
+        .collect();
+    let mut table = Table::new(&columns);
+
+    for (first, lasts) in cohorts {
+        let mut row = vec![
+            Value::Text(format!("{}-{:02}", first / 12, first % 12 + 1)),
+            Value::Count(lasts.len() as u64),
+        ];
+
+        for later in MONTHS_LATER {
+            let month = first + later as i32;
+
+            row.push(if month > latest {
+                Value::Empty
+            } else {
+                let retained = lasts.iter().filter(|last| **last >= month).count();
+                Value::Percent(retained as f64 / lasts.len() as f64)
+            });
+        }
+
+        table.push(row);
+    }
+
+    Report::new("Retention by first month:", table)
+}
+
+fn month(commit: &Commit) -> i32 {
+    commit.date.year() * 12 + commit.date.month() as i32 - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn report() {
+        let commits = vec![
+            commit()
+                .email("jon@email.ca")
+                .on(datetime!(2023-05-31 23:00 -8))
+                .build(),
+            commit()
+                .email("sam@email.ca")
+                .on(datetime!(2022-12-01 9:00 UTC))
+                .build(),
+            commit()
+                .email("jon@email.ca")
+                .on(datetime!(2022-04-15 9:00 UTC))
+                .build(),
+            commit()
+                .email("ana@email.ca")
+                .on(datetime!(2022-04-20 9:00 UTC))
+                .build(),
+            commit()
+                .email("kim@email.ca")
+                .on(datetime!(2022-01-03 9:00 UTC))
+                .build(),
+            commit()
+                .email("sam@email.ca")
+                .on(datetime!(2022-01-03 9:00 UTC))
+                .build(),
+        ];
+
+        let report = super::report(&commits);
+        let rows = &report.table.rows;
+
+        assert_eq!(
+            report.table.columns,
+            vec!["cohort", "authors", "3 months", "6 months", "12 months"]
+        );
+        assert_eq!(rows.len(), 2);
+
+        assert_eq!(
+            rows[0],
+            vec![
+                Value::Text("2022-01".to_string()),
+                Value::Count(2),
+                Value::Percent(0.5),
+                Value::Percent(0.5),
+                Value::Percent(0.0),
+            ]
+        );
+
+        // The latest commit is more than a year after April 2022
+        assert_eq!(
+            rows[1],
+            vec![
+                Value::Text("2022-04".to_string()),
+                Value::Count(2),
+                Value::Percent(0.5),
+                Value::Percent(0.5),
+                Value::Percent(0.5),
+            ]
+        );
+
+        // Nothing's known about the months after the latest commit
+        let report = super::report(&commits[..1]);
+        assert_eq!(report.table.rows[0][2], Value::Empty);
+    }
+}
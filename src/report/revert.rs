@@ -0,0 +1,346 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+use crate::stats::{median, percentile};
+
+/// How many files and authors are listed
+const TOP: usize = 10;
+
+lazy_static! {
+    /// What `git revert` writes in the body, e.g. `This reverts commit 1a2b3c.`
+    static ref REVERTED_HASH: Regex = Regex::new(r"This reverts commit ([0-9a-f]{7,40})").unwrap();
+}
+
+/// Commits that undo or amend earlier work
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Made by `git revert`, e.g. `Revert "Add feature"`
+    Revert,
+    /// Made by `git commit --fixup`, e.g. `fixup! Add feature`
+    Fixup,
+    /// Made by `git commit --squash`, e.g. `squash! Add feature`
+    Squash,
+}
+
+impl Kind {
+    pub fn of(commit: &Commit) -> Option<Kind> {
+        let subject = commit.subject();
+
+        if subject.starts_with("Revert \"") {
+            Some(Kind::Revert)
+        } else if subject.starts_with("fixup! ") {
+            Some(Kind::Fixup)
+        } else if subject.starts_with("squash! ") {
+            Some(Kind::Squash)
+        } else {
+            None
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Kind::Revert => "revert",
+            Kind::Fixup => "fixup!",
+            Kind::Squash => "squash!",
+        }
+    }
+}
+
+/// The subject of the commit a revert undoes, e.g. `Add feature` given
+/// `Revert "Add feature"`
+pub fn reverted_subject(commit: &Commit) -> Option<&str> {
+    commit
+        .subject()
+        .strip_prefix("Revert \"")
+        .and_then(|rest| rest.strip_suffix('"'))
+}
+
+/// The hash of the commit a revert undoes, as `git revert` writes it in the
+/// body, which may be abbreviated
+pub fn reverted_hash(commit: &Commit) -> Option<&str> {
+    REVERTED_HASH
+        .captures(commit.body())
+        .and_then(|captures| captures.get(1))
+        .map(|hash| hash.as_str())
+}
+
+/// How often each kind of commit occurs, the files reverts touch most, whose
+/// commits get reverted most, and how long commits lasted before they were
+pub fn reports(commits: &[Commit]) -> Vec<Report> {
+    vec![
+        frequency(commits),
+        files(commits),
+        authors(commits),
+        time_to_revert(commits),
+    ]
+}
+
+/// Each revert with the commit it undoes, newest revert first. The original
+/// is found by the hash in the revert's body or, failing that, by subject, so
+/// reverts of commits outside the history being analyzed (or whose hash and
+/// subject were both edited) are left out. Commits are put newest first,
+/// whatever order they're given in, so the original found by subject is the
+/// closest match before the revert. Reverting a revert is a chain of two.
+fn chains(commits: &[Commit]) -> Vec<(&Commit, &Commit)> {
+    let mut commits: Vec<&Commit> = commits.iter().collect();
+    commits.sort_by_key(|commit| Reverse(commit.date));
+
+    let by_hash = |hash: &str| {
+        commits.iter().find(|commit| {
+            !commit.hash.is_empty()
+                && (commit.hash.starts_with(hash) || hash.starts_with(commit.hash.as_str()))
+        })
+    };
+
+    let mut chains = Vec::new();
+
+    for (i, revert) in commits.iter().enumerate() {
+        let original = reverted_hash(revert).and_then(by_hash).or_else(|| {
+            let subject = reverted_subject(revert)?;
+
+            commits[i + 1..]
+                .iter()
+                .find(|commit| commit.subject() == subject)
+        });
+
+        if let Some(original) = original {
+            chains.push((*revert, *original));
+        }
+    }
+
+    chains
+}
+
+fn frequency(commits: &[Commit]) -> Report {
+    let mut table = Table::new(&["kind", "commits", "share"]);
+
+    for kind in [Kind::Revert, Kind::Fixup, Kind::Squash] {
+        let count = commits
+            .iter()
+            .filter(|commit| Kind::of(commit) == Some(kind))
+            .count();
+
+        let share = count as f64 / commits.len().max(1) as f64;
+
+        table.push(vec![
+            Value::Text(kind.name().to_string()),
+            Value::Count(count as u64),
+            Value::Percent(share),
+        ]);
+    }
+
+    Report::new("Reverts and fixups:", table)
+}
+
+fn files(commits: &[Commit]) -> Report {
+    let mut counts: HashMap<&str, u64> = HashMap::new();
+
+    for commit in reverts(commits) {
+        for change in &commit.file_changes {
+            *counts.entry(&change.path).or_default() += 1;
+        }
+    }
+
+    top_table("Most reverted files:", "file", counts)
+}
+
+fn authors(commits: &[Commit]) -> Report {
+    let mut counts: HashMap<&str, u64> = HashMap::new();
+
+    for (_, original) in chains(commits) {
+        *counts.entry(&original.author.name).or_default() += 1;
+    }
+
+    top_table("Most reverted authors:", "author", counts)
+}
+
+/// Hours from each reverted commit to its revert, never less than none since
+/// clocks disagree
+fn time_to_revert(commits: &[Commit]) -> Report {
+    let hours: Vec<f64> = chains(commits)
+        .into_iter()
+        .map(|(revert, original)| {
+            ((revert.date - original.date).as_seconds_f64() / 3600.0).max(0.0)
+        })
+        .collect();
+
+    let value = |hours: Option<f64>| hours.map_or(Value::Empty, Value::Decimal);
+    let longest = hours.iter().copied().reduce(f64::max);
+
+    let mut table = Table::new(&["measure", "value"]);
+
+    let rows = [
+        ("reverts matched", Value::Count(hours.len() as u64)),
+        ("median hours", value(median(hours.clone()))),
+        ("p90 hours", value(percentile(hours.clone(), 0.9))),
+        ("longest hours", value(longest)),
+    ];
+
+    for (measure, value) in rows {
+        table.push(vec![Value::Text(measure.to_string()), value]);
+    }
+
+    Report::new("Time to revert:", table)
+}
+
+fn reverts(commits: &[Commit]) -> impl Iterator<Item = &Commit> {
+    commits
+        .iter()
+        .filter(|commit| Kind::of(commit) == Some(Kind::Revert))
+}
+
+fn top_table(title: &str, label: &str, counts: HashMap<&str, u64>) -> Report {
+    let mut counts: Vec<(&str, u64)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    counts.truncate(TOP);
+
+    let mut table = Table::new(&[label, "reverts"]);
+
+    for (name, count) in counts {
+        table.push(vec![Value::Text(name.to_string()), Value::Count(count)]);
+    }
+
+    Report::new(title, table)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    use super::Kind;
+
+    #[test]
+    fn kinds() {
+        let kind = |message| {
+            Kind::of(
+                &commit()
+                    .on(datetime!(2022-11-28 12:00 UTC))
+                    .by("Jon")
+                    .message(message)
+                    .paths(&[])
+                    .build(),
+            )
+        };
+
+        assert_eq!(kind("Revert \"Add feature\""), Some(Kind::Revert));
+        assert_eq!(kind("fixup! Add feature"), Some(Kind::Fixup));
+        assert_eq!(kind("squash! Add feature"), Some(Kind::Squash));
+        assert_eq!(kind("Add feature"), None);
+        assert_eq!(kind("Revert the thing"), None);
+    }
+
+    #[test]
+    fn most_reverted() {
+        let commits = vec![
+            commit()
+                .on(datetime!(2022-11-28 12:00 UTC))
+                .by("Jon")
+                .message("Revert \"Add feature\"")
+                .paths(&["src/main.rs"])
+                .build(),
+            commit()
+                .on(datetime!(2022-11-28 12:00 UTC))
+                .by("Jon")
+                .message("Revert \"Fix bug\"")
+                .paths(&["src/main.rs", "README.md"])
+                .build(),
+            commit()
+                .on(datetime!(2022-11-28 12:00 UTC))
+                .by("Not Jon")
+                .message("Add feature")
+                .paths(&["src/main.rs"])
+                .build(),
+            commit()
+                .on(datetime!(2022-11-28 12:00 UTC))
+                .by("Not Jon")
+                .message("Fix bug")
+                .paths(&["src/main.rs", "README.md"])
+                .build(),
+        ];
+
+        let reports = super::reports(&commits);
+
+        let frequency = &reports[0].table;
+        assert_eq!(frequency.rows[0][1], Value::Count(2));
+        assert_eq!(frequency.rows[0][2], Value::Percent(0.5));
+
+        let files = &reports[1].table;
+        assert_eq!(files.rows[0][0], Value::Text("src/main.rs".to_string()));
+        assert_eq!(files.rows[0][1], Value::Count(2));
+        assert_eq!(files.rows[1][0], Value::Text("README.md".to_string()));
+
+        let authors = &reports[2].table;
+        assert_eq!(authors.rows.len(), 1);
+        assert_eq!(authors.rows[0][0], Value::Text("Not Jon".to_string()));
+        assert_eq!(authors.rows[0][1], Value::Count(2));
+    }
+
+    #[test]
+    fn any_order() {
+        let dated = |name, message, day| {
+            commit()
+                .by(name)
+                .message(message)
+                .on(datetime!(2022-11-01 12:00 UTC).replace_day(day).unwrap())
+                .build()
+        };
+
+        let oldest_first = vec![
+            dated("Not Jon", "Add feature", 26),
+            dated("Jon", "Revert \"Add feature\"", 27),
+            dated("Jon", "Add feature", 28),
+        ];
+        let mut newest_first = oldest_first.clone();
+        newest_first.reverse();
+
+        for commits in [oldest_first, newest_first] {
+            let authors = super::authors(&commits);
+
+            assert_eq!(authors.table.rows.len(), 1);
+            assert_eq!(authors.table.rows[0][0], Value::Text("Not Jon".to_string()));
+        }
+    }
+
+    #[test]
+    fn by_hash_and_time() {
+        let dated = |hash, name, message, hour| {
+            commit()
+                .hash(hash)
+                .by(name)
+                .message(message)
+                .on(datetime!(2022-11-28 0:00 UTC).replace_hour(hour).unwrap())
+                .build()
+        };
+
+        let commits = vec![
+            dated(
+                "ccc333",
+                "Jon",
+                "Undo the feature\n\nThis reverts commit aaa1112.",
+                10,
+            ),
+            dated("bbb222", "Jon", "Revert \"Add feature\"", 4),
+            dated("aaa111222", "Not Jon", "Add feature", 2),
+            dated("ddd444", "Jon", "Revert \"Not in history\"", 1),
+        ];
+
+        assert_eq!(super::reverted_hash(&commits[0]), Some("aaa1112"));
+
+        let authors = super::authors(&commits);
+        assert_eq!(authors.table.rows[0][0], Value::Text("Not Jon".to_string()));
+        assert_eq!(authors.table.rows[0][1], Value::Count(2));
+
+        let time = &super::time_to_revert(&commits).table.rows;
+        assert_eq!(time[0][1], Value::Count(2));
+        assert_eq!(time[1][1], Value::Decimal(5.0));
+        assert_eq!(time[3][1], Value::Decimal(8.0));
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use time::{Date, Duration};
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// How many weeks are charted unless asked for more or fewer
+pub const DEFAULT_WEEKS: usize = 12;
+
+/// From a little to the most, for days with any commits
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Commits in the seven days up to each day of the last `weeks` weeks before
+/// the latest commit, a row per week. Each day is a character of the week's
+/// chart, taller the more commits, which smooths out daily noise but still
+/// shows the rhythm of each week. Days are in each commit's own offset.
+pub fn report(commits: &[Commit], weeks: usize) -> Report {
+    let mut table = Table::new(&["week of", "commits", "rolling 7 days"]);
+
+    let end = match commits.iter().map(|commit| commit.date.date()).max() {
+        Some(end) => end,
+        None => return Report::new("Rolling 7-day commits:", table),
+    };
+
+    let mut by_day: HashMap<Date, u64> = HashMap::new();
+
+    for commit in commits {
+        *by_day.entry(commit.date.date()).or_default() += 1;
+    }
+
+    let days = weeks as i64 * 7;
+    let start = end - Duration::days(days - 1);
+
+    let rolling: Vec<(Date, u64, u64)> = (0..days)
+        .map(|i| {
+            let day = start + Duration::days(i);
+            let total = (0..7)
+                .map(|back| by_day.get(&(day - Duration::days(back))))
+                .map(|count| count.copied().unwrap_or_default())
+                .sum();
+
+            (day, by_day.get(&day).copied().unwrap_or_default(), total)
+        })
+        .collect();
+
+    let max = rolling
+        .iter()
+        .map(|(_, _, total)| *total)
+        .max()
+        .unwrap_or(1);
+
+    for week in rolling.chunks(7) {
+        let chart: String = week
+            .iter()
+            .map(|(_, _, total)| spark(*total, max))
+            .collect();
+
+        table.push(vec![
+            Value::Text(week[0].0.to_string()),
+            Value::Count(week.iter().map(|(_, count, _)| count).sum()),
+            Value::Text(chart),
+        ]);
+    }
+
+    Report::new("Rolling 7-day commits:", table)
+}
+
+/// A blank for none, otherwise a bar up to full height at `max`
+fn spark(total: u64, max: u64) -> char {
+    if total == 0 {
+        return ' ';
+    }
+
+    let height = (total as f64 / max.max(1) as f64 * SPARKS.len() as f64).ceil() as usize;
+    SPARKS[height.clamp(1, SPARKS.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn rolling_weeks() {
+        let commits = vec![
+            // Before the two weeks charted but within a week of their start
+            commit().on(datetime!(2022-11-10 12:00 -8)).build(),
+            commit().on(datetime!(2022-11-15 12:00 -8)).build(),
+            commit().on(datetime!(2022-11-15 13:00 -8)).build(),
+            commit().on(datetime!(2022-11-21 12:00 -8)).build(),
+            commit().on(datetime!(2022-11-27 23:00 -8)).build(),
+        ];
+
+        let report = super::report(&commits, 2);
+        let rows = &report.table.rows;
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], Value::Text("2022-11-14".to_string()));
+        assert_eq!(rows[0][1], Value::Count(2));
+        assert_eq!(rows[0][2], Value::Text("▃██▆▆▆▆".to_string()));
+        assert_eq!(rows[1][0], Value::Text("2022-11-21".to_string()));
+        assert_eq!(rows[1][1], Value::Count(2));
+        assert_eq!(rows[1][2], Value::Text("█▃▃▃▃▃▆".to_string()));
+
+        assert!(super::report(&[], 2).table.rows.is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// How many identities are listed
+const TOP: usize = 10;
+
+lazy_static! {
+    // Trailers certifying the Developer Certificate of Origin, e.g.
+    // `Signed-off-by: Jon <jon@email.ca>`
+    static ref SIGNOFF_REGEX: Regex =
+        Regex::new(r"(?mi)^signed-off-by:\s*(.+?)\s*<([^>]+)>").unwrap();
+}
+
+/// A name and email from a `Signed-off-by` trailer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signoff<'a> {
+    pub name: &'a str,
+    pub email: &'a str,
+}
+
+/// Every sign-off in the commit's message, in order
+pub fn signoffs(commit: &Commit) -> Vec<Signoff<'_>> {
+    SIGNOFF_REGEX
+        .captures_iter(&commit.message)
+        .filter_map(|captures| {
+            Some(Signoff {
+                name: captures.get(1)?.as_str(),
+                email: captures.get(2)?.as_str(),
+            })
+        })
+        .collect()
+}
+
+/// How many commits are signed off, as projects following the Developer
+/// Certificate of Origin require, and who signs off the most. A commit
+/// complies when its author signed it off. Merges are left out since they
+/// rarely need to be.
+pub fn reports(commits: &[Commit]) -> Vec<Report> {
+    let commits: Vec<&Commit> = commits
+        .iter()
+        .filter(|commit| commit.parents.len() < 2)
+        .collect();
+
+    vec![summary(&commits), identities(&commits)]
+}
+
+fn summary(commits: &[&Commit]) -> Report {
+    let signed = commits
+        .iter()
+        .filter(|commit| !signoffs(commit).is_empty())
+        .count();
+
+    let compliant = commits
+        .iter()
+        .filter(|commit| {
+            signoffs(commit)
+                .iter()
+                .any(|signoff| signoff.email.eq_ignore_ascii_case(&commit.author.email))
+        })
+        .count();
+
+    let share = |count: usize| Value::Percent(count as f64 / commits.len().max(1) as f64);
+
+    let mut table = Table::new(&["measure", "commits", "share"]);
+
+    let rows = [
+        ("signed off", signed),
+        ("signed off by the author", compliant),
+    ];
+
+    for (measure, count) in rows {
+        table.push(vec![
+            Value::Text(measure.to_string()),
+            Value::Count(count as u64),
+            share(count),
+        ]);
+    }
+
+    Report::new("Sign-offs:", table)
+}
+
+/// Identities by how many commits they signed off, and how many of those
+/// someone else wrote, e.g. as a maintainer applying patches
+fn identities(commits: &[&Commit]) -> Report {
+    // Counted by lowercase email, named as first seen
+    let mut counts: HashMap<String, (&str, &str, u64, u64)> = HashMap::new();
+
+    for commit in commits {
+        for signoff in signoffs(commit) {
+            let counts = counts.entry(signoff.email.to_lowercase()).or_insert((
+                signoff.name,
+                signoff.email,
+                0,
+                0,
+            ));
+
+            counts.2 += 1;
+
+            if !signoff.email.eq_ignore_ascii_case(&commit.author.email) {
+                counts.3 += 1;
+            }
+        }
+    }
+
+    let mut counts: Vec<(&str, &str, u64, u64)> = counts.into_values().collect();
+    counts.sort_by(|a, b| b.2.cmp(&a.2).then(a.1.cmp(b.1)));
+    counts.truncate(TOP);
+
+    let mut table = Table::new(&["name", "email", "sign-offs", "for others"]);
+
+    for (name, email, signoffs, others) in counts {
+        table.push(vec![
+            Value::Text(name.to_string()),
+            Value::Text(email.to_string()),
+            Value::Count(signoffs),
+            Value::Count(others),
+        ]);
+    }
+
+    Report::new("Most sign-offs:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn signoffs() {
+        let commit = commit()
+            .email("jon@email.ca")
+            .message(
+                "Fix it\n\n\
+                 Signed-off-by: Jon <jon@email.ca>\n\
+                 signed-off-by: Not Jon <not.jon@email.ca>",
+            )
+            .build();
+
+        let signoffs = super::signoffs(&commit);
+
+        assert_eq!(signoffs.len(), 2);
+        assert_eq!(signoffs[1].name, "Not Jon");
+        assert_eq!(signoffs[1].email, "not.jon@email.ca");
+    }
+
+    #[test]
+    fn compliance_and_identities() {
+        let merge = commit()
+            .email("jon@email.ca")
+            .message("Merge")
+            .parents(&["a", "b"])
+            .build();
+
+        let commits = vec![
+            commit()
+                .email("jon@email.ca")
+                .message("One\n\nSigned-off-by: Jon <JON@email.ca>")
+                .build(),
+            commit()
+                .email("zed@email.ca")
+                .message(
+                    "Two\n\nSigned-off-by: Zed <zed@email.ca>\nSigned-off-by: Jon <jon@email.ca>",
+                )
+                .build(),
+            commit()
+                .email("zed@email.ca")
+                .message("Three\n\nSigned-off-by: Jon <jon@email.ca>")
+                .build(),
+            commit().email("zed@email.ca").message("Four").build(),
+            merge,
+        ];
+
+        let reports = super::reports(&commits);
+
+        let summary = &reports[0].table.rows;
+        assert_eq!(summary[0][1], Value::Count(3));
+        assert_eq!(summary[0][2], Value::Percent(0.75));
+        assert_eq!(summary[1][1], Value::Count(2));
+
+        let identities = &reports[1].table.rows;
+        assert_eq!(identities[0][0], Value::Text("Jon".to_string()));
+        assert_eq!(identities[0][2], Value::Count(3));
+        assert_eq!(identities[0][3], Value::Count(2));
+        assert_eq!(identities[1][2], Value::Count(1));
+        assert_eq!(identities[1][3], Value::Count(0));
+    }
+}
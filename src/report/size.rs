@@ -0,0 +1,138 @@
+use hdrhistogram::Histogram;
+
+use crate::commit::{self, Commit};
+use crate::report::{Report, Table, Value};
+
+/// Percentiles of lines changed per commit, i.e. `inserts + deletes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Percentiles {
+    pub commits: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+impl Percentiles {
+    /// Unlike the hour and weekday histograms, commit sizes have no sensible
+    /// upper bound so the distribution is left to grow as values are recorded.
+    /// Three significant figures keeps small commits exact.
+    pub fn of<'a>(commits: impl IntoIterator<Item = &'a Commit>) -> Percentiles {
+        let mut distribution = Percentiles::distribution();
+
+        for commit in commits {
+            distribution.record(commit.churn()).unwrap();
+        }
+
+        Percentiles::of_distribution(&distribution)
+    }
+
+    /// Lines changed per commit, to record each commit's in as it's read
+    pub fn distribution() -> Histogram<u64> {
+        Histogram::<u64>::new(3).unwrap()
+    }
+
+    pub fn of_distribution(distribution: &Histogram<u64>) -> Percentiles {
+        Percentiles {
+            commits: distribution.len(),
+            p50: distribution.value_at_quantile(0.50),
+            p90: distribution.value_at_quantile(0.90),
+            p99: distribution.value_at_quantile(0.99),
+        }
+    }
+
+    fn values(&self) -> Vec<Value> {
+        vec![
+            Value::Count(self.commits),
+            Value::Count(self.p50),
+            Value::Count(self.p90),
+            Value::Count(self.p99),
+        ]
+    }
+}
+
+/// Percentiles of commit size across every commit
+pub fn overall(commits: &[Commit]) -> Report {
+    overall_of(Percentiles::of(commits))
+}
+
+pub fn overall_of(percentiles: Percentiles) -> Report {
+    let mut table = Table::new(&["commits", "p50", "p90", "p99"]);
+    table.push(percentiles.values());
+
+    Report::new("Lines changed per commit:", table)
+}
+
+/// Percentiles of commit size for each author, most active authors first
+pub fn by_author(commits: &[Commit]) -> Report {
+    let authors: Vec<(&str, Percentiles)> = commit::by_author(commits)
+        .into_iter()
+        .map(|(author, commits)| (author.name.as_str(), Percentiles::of(commits)))
+        .collect();
+
+    by_author_of(authors)
+}
+
+/// Like `by_author()`, from each author's name and percentiles
+pub fn by_author_of(mut authors: Vec<(&str, Percentiles)>) -> Report {
+    authors.sort_by(|a, b| b.1.commits.cmp(&a.1.commits).then(a.0.cmp(b.0)));
+
+    let mut table = Table::new(&["author", "commits", "p50", "p90", "p99"]);
+
+    for (name, percentiles) in authors {
+        let mut row = vec![Value::Text(name.to_string())];
+        row.extend(percentiles.values());
+        table.push(row);
+    }
+
+    Report::new("Lines changed per commit by author:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commit::Commit;
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    use super::Percentiles;
+
+    #[test]
+    fn percentiles() {
+        let commits: Vec<Commit> = (1..=100)
+            .map(|size| commit().by("Jon").lines(size, 0).build())
+            .collect();
+
+        let percentiles = Percentiles::of(&commits);
+
+        assert_eq!(percentiles.commits, 100);
+        assert_eq!(percentiles.p50, 50);
+        assert_eq!(percentiles.p90, 90);
+        assert_eq!(percentiles.p99, 99);
+    }
+
+    #[test]
+    fn inserts_and_deletes_both_count() {
+        let commits = vec![commit().by("Jon").lines(3, 4).build()];
+
+        let percentiles = Percentiles::of(&commits);
+
+        assert_eq!(percentiles.p50, 7);
+        assert_eq!(percentiles.p99, 7);
+    }
+
+    #[test]
+    fn by_author_sorts_most_commits_first() {
+        let commits = vec![
+            commit().by("Jon").lines(1, 1).build(),
+            commit().by("Not Jon").lines(10, 0).build(),
+            commit().by("Not Jon").lines(20, 0).build(),
+        ];
+
+        let report = super::by_author(&commits);
+
+        assert_eq!(report.table.rows.len(), 2);
+        assert_eq!(report.table.rows[0][0], Value::Text("Not Jon".to_string()));
+        assert_eq!(report.table.rows[0][1], Value::Count(2));
+        assert_eq!(report.table.rows[1][0], Value::Text("Jon".to_string()));
+        assert_eq!(report.table.rows[1][2], Value::Count(2));
+    }
+}
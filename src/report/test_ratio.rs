@@ -0,0 +1,163 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::commit::{self, Commit};
+use crate::report::{Report, Table, Value};
+
+lazy_static! {
+    // Test directories, e.g. `tests/` or `__tests__/`, and test files named
+    // the usual ways, e.g. `parser_test.go`, `app.spec.ts`, or `test_app.py`
+    pub static ref DEFAULT_PATTERN: Regex = Regex::new(
+        r"(^|/)(tests?|spec|__tests__)/|[_.](test|spec)\.\w+$|(^|/)test_[^/]*\.py$|Tests?\.\w+$"
+    )
+    .unwrap();
+}
+
+/// Whether any of the patterns matches the file's path
+pub fn is_test(path: &str, patterns: &[Regex]) -> bool {
+    patterns.iter().any(|pattern| pattern.is_match(path))
+}
+
+/// Lines changed in test files, found by `patterns`, against lines changed in
+/// every other file, overall and by author. More test churn per line of
+/// source churn roughly suggests more of the work is tested.
+pub fn reports(commits: &[Commit], patterns: &[Regex]) -> Vec<Report> {
+    vec![overall(commits, patterns), by_author(commits, patterns)]
+}
+
+/// Lines changed in test files and in source files
+fn churn<'a>(commits: impl IntoIterator<Item = &'a Commit>, patterns: &[Regex]) -> (u64, u64) {
+    let (mut tests, mut sources) = (0, 0);
+
+    for commit in commits {
+        for change in &commit.file_changes {
+            if is_test(&change.path, patterns) {
+                tests += change.changes as u64;
+            } else {
+                sources += change.changes as u64;
+            }
+        }
+    }
+
+    (tests, sources)
+}
+
+fn ratio(tests: u64, sources: u64) -> Value {
+    match sources {
+        0 => Value::Empty,
+        _ => Value::Decimal(tests as f64 / sources as f64),
+    }
+}
+
+fn overall(commits: &[Commit], patterns: &[Regex]) -> Report {
+    let (tests, sources) = churn(commits, patterns);
+
+    let mut table = Table::new(&["test lines", "source lines", "test share", "ratio"]);
+
+    table.push(vec![
+        Value::Count(tests),
+        Value::Count(sources),
+        Value::Percent(tests as f64 / (tests + sources).max(1) as f64),
+        ratio(tests, sources),
+    ]);
+
+    Report::new("Test churn:", table)
+}
+
+/// Authors who changed the most lines first
+fn by_author(commits: &[Commit], patterns: &[Regex]) -> Report {
+    let mut authors: Vec<(&str, u64, u64)> = commit::by_author(commits)
+        .into_iter()
+        .map(|(author, commits)| {
+            let (tests, sources) = churn(commits, patterns);
+            (author.name.as_str(), tests, sources)
+        })
+        .collect();
+
+    authors.sort_by(|a, b| (b.1 + b.2).cmp(&(a.1 + a.2)).then(a.0.cmp(b.0)));
+
+    let mut table = Table::new(&["author", "test lines", "source lines", "ratio"]);
+
+    for (name, tests, sources) in authors {
+        table.push(vec![
+            Value::Text(name.to_string()),
+            Value::Count(tests),
+            Value::Count(sources),
+            ratio(tests, sources),
+        ]);
+    }
+
+    Report::new("Test churn by author:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use regex::Regex;
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    use super::DEFAULT_PATTERN;
+
+    #[test]
+    fn is_test() {
+        let default = slice::from_ref(&*DEFAULT_PATTERN);
+
+        for path in [
+            "tests/cli.rs",
+            "src/__tests__/app.js",
+            "pkg/parser_test.go",
+            "web/app.spec.ts",
+            "test_app.py",
+            "src/FooTest.java",
+        ] {
+            assert!(super::is_test(path, default), "{}", path);
+        }
+
+        for path in ["src/main.rs", "src/testing.rs", "contest/main.c"] {
+            assert!(!super::is_test(path, default), "{}", path);
+        }
+
+        let custom = [Regex::new(r"^checks/").unwrap()];
+        assert!(super::is_test("checks/a.rs", &custom));
+        assert!(!super::is_test("tests/a.rs", &custom));
+    }
+
+    #[test]
+    fn overall_and_by_author() {
+        let commits = vec![
+            commit()
+                .email("jon")
+                .changes(&[("src/main.rs", 30), ("tests/cli.rs", 15)])
+                .build(),
+            commit()
+                .email("not jon")
+                .changes(&[("src/lib.rs", 10)])
+                .build(),
+            commit()
+                .email("jon")
+                .changes(&[("tests/cli.rs", 5)])
+                .build(),
+            commit()
+                .email("tester")
+                .changes(&[("tests/more.rs", 4)])
+                .build(),
+        ];
+
+        let reports = super::reports(&commits, slice::from_ref(&*DEFAULT_PATTERN));
+
+        let overall = &reports[0].table.rows[0];
+        assert_eq!(overall[0], Value::Count(24));
+        assert_eq!(overall[1], Value::Count(40));
+        assert_eq!(overall[2], Value::Percent(24.0 / 64.0));
+        assert_eq!(overall[3], Value::Decimal(0.6));
+
+        let authors = &reports[1].table.rows;
+        assert_eq!(authors[0][0], Value::Text("jon".to_string()));
+        assert_eq!(authors[0][3], Value::Decimal(20.0 / 30.0));
+        assert_eq!(authors[1][3], Value::Decimal(0.0));
+        assert_eq!(authors[2][3], Value::Empty);
+    }
+}
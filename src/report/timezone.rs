@@ -0,0 +1,166 @@
+use std::ops::Range;
+
+use time::UtcOffset;
+
+use crate::commit::Commit;
+use crate::histogram::Histogram;
+use crate::report::activity;
+use crate::report::{Report, Table, Value};
+
+/// Hours of the day counted as working hours, 09:00 to 17:00
+pub const WORKING_HOURS: Range<u8> = 9..17;
+
+/// An offset like `+05:30`, `-0800`, `-8`, or `UTC`
+pub fn parse_offset(text: &str) -> Result<UtcOffset, String> {
+    let invalid = || format!("Invalid UTC offset {}", text);
+
+    if text.eq_ignore_ascii_case("utc") || text == "Z" {
+        return Ok(UtcOffset::UTC);
+    }
+
+    let sign = match text.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Err(invalid()),
+    };
+
+    let digits = text[1..].replace(':', "");
+
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.as_str(), "0"),
+        4 => digits.split_at(2),
+        _ => return Err(invalid()),
+    };
+
+    let hours: i8 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i8 = minutes.parse().map_err(|_| invalid())?;
+
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).map_err(|_| invalid())
+}
+
+/// Commits by hour of the day in their authors' own offsets against the same
+/// commits in the team's, followed by how far from the team's offset authors
+/// are and how many commits each way of telling the time puts in working
+/// hours. A team in one place has the same hours both ways.
+pub fn reports(commits: &[Commit], team: UtcOffset) -> Vec<Report> {
+    let team_hour = |commit: &Commit| commit.date.to_offset(team).hour();
+
+    let mut local = Histogram::<24>::default();
+    let mut in_team = Histogram::<24>::default();
+
+    for commit in commits {
+        local.record(commit.date.hour() as usize);
+        in_team.record(team_hour(commit) as usize);
+    }
+
+    let (local, in_team) = (local.totals(), in_team.totals());
+
+    let team_name = activity::offset(team.whole_seconds());
+
+    let mut hours = Table::new(&["hour", "local", &team_name]);
+
+    for hour in 0..24 {
+        hours.push(vec![
+            Value::Text(format!("{:02}", hour)),
+            Value::Count(local[hour]),
+            Value::Count(in_team[hour]),
+        ]);
+    }
+
+    let total = commits.len().max(1) as f64;
+    let share = |count: usize| Value::Percent(count as f64 / total);
+
+    let elsewhere = commits
+        .iter()
+        .filter(|commit| commit.date.offset() != team)
+        .count();
+
+    let hours_away: f64 = commits
+        .iter()
+        .map(|commit| (commit.date.offset().whole_seconds() - team.whole_seconds()).abs() as f64)
+        .sum::<f64>()
+        / 3600.0;
+
+    let working = |hour: u8| WORKING_HOURS.contains(&hour);
+    let working_local = commits
+        .iter()
+        .filter(|commit| working(commit.date.hour()))
+        .count();
+    let working_team = commits
+        .iter()
+        .filter(|commit| working(team_hour(commit)))
+        .count();
+
+    let mut summary = Table::new(&["measure", "value"]);
+
+    summary.push(vec![
+        Value::Text(format!("commits made outside {}", team_name)),
+        share(elsewhere),
+    ]);
+    summary.push(vec![
+        Value::Text("average hours from it".to_string()),
+        Value::Decimal(hours_away / total),
+    ]);
+    summary.push(vec![
+        Value::Text("in working hours, local".to_string()),
+        share(working_local),
+    ]);
+    summary.push(vec![
+        Value::Text(format!("in working hours, {}", team_name)),
+        share(working_team),
+    ]);
+
+    vec![
+        Report::new(&format!("By hour, local and {}:", team_name), hours),
+        Report::new("Distribution:", summary),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{datetime, offset};
+
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn reports() {
+        let commits = vec![
+            commit().on(datetime!(2022-11-28 10:00 -8)).build(),
+            commit().on(datetime!(2022-11-28 11:00 -8)).build(),
+            // 10:00 in Berlin is 01:00 in Vancouver
+            commit().on(datetime!(2022-11-28 10:00 +1)).build(),
+            commit().on(datetime!(2022-11-28 14:00 +5:30)).build(),
+        ];
+
+        let reports = super::reports(&commits, offset!(-8));
+        let hours = &reports[0].table.rows;
+
+        assert_eq!(reports[0].title, "By hour, local and -08:00:");
+        assert_eq!(reports[0].table.columns[2], "-08:00");
+        assert_eq!(hours[10][1], Value::Count(2));
+        assert_eq!(hours[10][2], Value::Count(1));
+        assert_eq!(hours[1][2], Value::Count(1));
+        assert_eq!(hours[0][2], Value::Count(1));
+
+        let summary = &reports[1].table.rows;
+
+        assert_eq!(summary[0][1], Value::Percent(0.5));
+        // (0 + 0 + 9 + 13.5) / 4
+        assert_eq!(summary[1][1], Value::Decimal(5.625));
+        assert_eq!(summary[2][1], Value::Percent(1.0));
+        assert_eq!(summary[3][1], Value::Percent(0.5));
+    }
+
+    #[test]
+    fn parse_offset() {
+        assert_eq!(super::parse_offset("+05:30"), Ok(offset!(+5:30)));
+        assert_eq!(super::parse_offset("-0800"), Ok(offset!(-8)));
+        assert_eq!(super::parse_offset("-8"), Ok(offset!(-8)));
+        assert_eq!(super::parse_offset("UTC"), Ok(offset!(UTC)));
+        assert!(super::parse_offset("8").is_err());
+        assert!(super::parse_offset("+123").is_err());
+        assert!(super::parse_offset("+25").is_err());
+        assert!(super::parse_offset("Europe/Berlin").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::commit::Commit;
+use crate::report::{Report, Table, Value};
+
+/// How many words are listed unless asked for more or fewer
+pub const DEFAULT_COUNT: usize = 20;
+
+/// Words too common to say what the work was about
+const STOPWORDS: [&str; 60] = [
+    "a", "about", "after", "all", "an", "and", "any", "are", "as", "at", "be", "been", "before",
+    "but", "by", "can", "do", "does", "don't", "for", "from", "has", "have", "if", "in", "into",
+    "is", "it", "its", "more", "no", "not", "now", "of", "off", "on", "only", "or", "out", "over",
+    "so", "some", "than", "that", "the", "their", "them", "then", "there", "these", "this", "to",
+    "up", "use", "was", "when", "which", "with", "without", "yet",
+];
+
+/// The lowercase words of the subject worth counting, in order, e.g. `fix`,
+/// `parser`, and `crash` from `Fix the parser crash`. Numbers and single
+/// letters are left out along with stopwords.
+pub fn terms(subject: &str) -> Vec<String> {
+    subject
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| word.chars().count() > 1)
+        .filter(|word| !word.chars().all(|c| c.is_numeric()))
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// The `count` words in the most commit subjects, a quick look at what the
+/// work has been about. A word is counted once per commit however often the
+/// subject repeats it.
+pub fn report(commits: &[Commit], count: usize) -> Report {
+    let mut counts: HashMap<String, u64> = HashMap::new();
+
+    for commit in commits {
+        let terms: HashSet<String> = terms(commit.subject()).into_iter().collect();
+
+        for term in terms {
+            *counts.entry(term).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<(String, u64)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    counts.truncate(count);
+
+    let mut table = Table::new(&["word", "commits", "share"]);
+
+    for (word, words) in counts {
+        table.push(vec![
+            Value::Text(word),
+            Value::Count(words),
+            Value::Percent(words as f64 / commits.len().max(1) as f64),
+        ]);
+    }
+
+    Report::new("Most common words:", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::Value;
+    use crate::test_support::commit;
+
+    #[test]
+    fn terms() {
+        assert_eq!(
+            super::terms("Fix the parser's crash on v2, don't panic (#12)"),
+            vec!["fix", "parser's", "crash", "v2", "panic"]
+        );
+    }
+
+    #[test]
+    fn most_common() {
+        let commits = vec![
+            commit()
+                .message("Fix parser crash\n\nThe body isn't counted")
+                .build(),
+            commit().message("Fix fix FIX").build(),
+            commit().message("Speed up the parser").build(),
+            commit().message("Add docs").build(),
+        ];
+
+        let report = super::report(&commits, 2);
+        let rows = &report.table.rows;
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], Value::Text("fix".to_string()));
+        assert_eq!(rows[0][1], Value::Count(2));
+        assert_eq!(rows[0][2], Value::Percent(0.5));
+        assert_eq!(rows[1][0], Value::Text("parser".to_string()));
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Context;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::commit::Alias;
+use crate::git::Diffstat;
+use crate::report::{self, Report};
+use crate::{commit, history, html, json};
+
+/// A repository served under a short name taken from its directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repo {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Every report for a repository at a point in time
+#[derive(Debug)]
+pub struct Analysis {
+    pub reports: Vec<Report>,
+    pub analyzed_at: OffsetDateTime,
+}
+
+/// Read and report on a repository's entire history, or only the commits by
+/// the authors, once aliased ones are merged. One odd commit shouldn't take
+/// the dashboard down so invalid UTF-8 is always replaced.
+pub fn analyze(path: &Path, aliases: &[Alias], authors: &[String]) -> anyhow::Result<Analysis> {
+    let mut commits = history::commits(path, Diffstat::Files, false)?;
+    commit::alias(&mut commits, aliases);
+    commit::retain_authors(&mut commits, authors);
+
+    let tags = commit::tags(&commits);
+
+    Ok(Analysis {
+        reports: report::all(&commits, &tags),
+        analyzed_at: OffsetDateTime::now_utc(),
+    })
+}
+
+/// Name each repository after its directory, adding a number when two
+/// directories share a name
+pub fn repos(paths: &[PathBuf]) -> Vec<Repo> {
+    let mut repos: Vec<Repo> = Vec::new();
+
+    for path in paths {
+        let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+
+        let base = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "repo".to_string());
+
+        let mut name = base.clone();
+        let mut n = 1;
+
+        while repos.iter().any(|repo| repo.name == name) {
+            n += 1;
+            name = format!("{}-{}", base, n);
+        }
+
+        repos.push(Repo { name, path });
+    }
+
+    repos
+}
+
+struct Server {
+    repos: Vec<Repo>,
+    /// Without a refresh interval every request analyzes the repository again
+    refresh: Option<Duration>,
+    aliases: Vec<Alias>,
+    /// Only commits by these authors are reported on, when there are any
+    authors: Vec<String>,
+    cache: Mutex<HashMap<String, Arc<Analysis>>>,
+}
+
+impl Server {
+    fn analysis(&self, repo: &Repo) -> anyhow::Result<Arc<Analysis>> {
+        if self.refresh.is_some() {
+            if let Some(analysis) = self.cache.lock().unwrap().get(&repo.name) {
+                return Ok(analysis.clone());
+            }
+        }
+
+        self.reanalyze(repo)
+    }
+
+    fn reanalyze(&self, repo: &Repo) -> anyhow::Result<Arc<Analysis>> {
+        let analysis = Arc::new(analyze(&repo.path, &self.aliases, &self.authors)?);
+
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(repo.name.clone(), analysis.clone());
+
+        Ok(analysis)
+    }
+
+    fn route(&self, path: &str) -> Response {
+        let path = path.split('?').next().unwrap_or_default();
+        let names: Vec<&str> = self.repos.iter().map(|repo| repo.name.as_str()).collect();
+
+        if path == "/" {
+            return Response::html(html::index(&names));
+        }
+
+        if path == "/api/repos" {
+            return Response::json(json::repos(&names));
+        }
+
+        let (name, as_json) = if let Some(name) = path.strip_prefix("/api/repos/") {
+            (name, true)
+        } else if let Some(name) = path.strip_prefix("/repos/") {
+            (name, false)
+        } else {
+            return Response::not_found();
+        };
+
+        let repo = match self.repos.iter().find(|repo| repo.name == name) {
+            Some(repo) => repo,
+            None => return Response::not_found(),
+        };
+
+        let analysis = match self.analysis(repo) {
+            Ok(analysis) => analysis,
+            Err(why) => return Response::error(format!("Can't analyze {}: {:?}", name, why)),
+        };
+
+        let analyzed_at = analysis.analyzed_at.format(&Rfc3339).unwrap_or_default();
+
+        if as_json {
+            Response::json(json::analysis(name, &analyzed_at, &analysis.reports))
+        } else {
+            Response::html(html::dashboard(name, &analyzed_at, &analysis.reports))
+        }
+    }
+
+    fn handle(&self, stream: TcpStream) -> anyhow::Result<()> {
+        let mut reader = BufReader::new(&stream);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        // Headers don't matter but are read so the client isn't cut off
+        // mid-request
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let response = match request_line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["GET", path, _] => self.route(path),
+            _ => Response::new("405 Method Not Allowed", "text/plain", String::new()),
+        };
+
+        response.write(&stream)
+    }
+}
+
+/// Serve an HTML dashboard and JSON API for the repositories until killed.
+/// With a refresh interval the repositories are analyzed in the background
+/// that often, otherwise on every request. Authors are aliased and filtered
+/// like `analyze()` does.
+pub fn serve(
+    address: &str,
+    repos: Vec<Repo>,
+    refresh: Option<Duration>,
+    aliases: Vec<Alias>,
+    authors: Vec<String>,
+) -> anyhow::Result<()> {
+    let listener =
+        TcpListener::bind(address).with_context(|| format!("Can't listen on {}", address))?;
+
+    let server = Arc::new(Server {
+        repos,
+        refresh,
+        aliases,
+        authors,
+        cache: Mutex::new(HashMap::new()),
+    });
+
+    if let Some(interval) = refresh {
+        let server = server.clone();
+
+        thread::spawn(move || loop {
+            for repo in &server.repos {
+                if let Err(why) = server.reanalyze(repo) {
+                    eprintln!("Can't analyze {}: {:?}", repo.name, why);
+                }
+            }
+
+            thread::sleep(interval);
+        });
+    }
+
+    println!(
+        "Serving {} repositories at http://{}",
+        server.repos.len(),
+        address
+    );
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+
+        let server = server.clone();
+
+        thread::spawn(move || {
+            if let Err(why) = server.handle(stream) {
+                eprintln!("Can't respond to request: {:?}", why);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn new(status: &'static str, content_type: &'static str, body: String) -> Response {
+        Response {
+            status,
+            content_type,
+            body,
+        }
+    }
+
+    fn html(body: String) -> Response {
+        Response::new("200 OK", "text/html; charset=utf-8", body)
+    }
+
+    fn json(body: String) -> Response {
+        Response::new("200 OK", "application/json", body)
+    }
+
+    fn not_found() -> Response {
+        Response::new("404 Not Found", "text/plain", "Not found\n".to_string())
+    }
+
+    fn error(message: String) -> Response {
+        Response::new("500 Internal Server Error", "text/plain", message)
+    }
+
+    fn write(&self, mut stream: &TcpStream) -> anyhow::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use super::{Repo, Server};
+
+    #[test]
+    fn repos_named_after_directories() {
+        let paths = vec![
+            PathBuf::from("/nowhere/yeesh"),
+            PathBuf::from("/elsewhere/yeesh"),
+            PathBuf::from("/nowhere/other"),
+        ];
+
+        let names: Vec<String> = super::repos(&paths).into_iter().map(|r| r.name).collect();
+
+        assert_eq!(names, vec!["yeesh", "yeesh-2", "other"]);
+    }
+
+    #[test]
+    fn routes() {
+        let server = Server {
+            repos: vec![Repo {
+                name: "yeesh".to_string(),
+                path: PathBuf::from("/nowhere/yeesh"),
+            }],
+            refresh: None,
+            aliases: Vec::new(),
+            authors: Vec::new(),
+            cache: Mutex::new(HashMap::new()),
+        };
+
+        assert_eq!(server.route("/").status, "200 OK");
+        assert_eq!(
+            server.route("/api/repos").body,
+            r#"{"schema_version":1,"repos":["yeesh"]}"#
+        );
+        assert_eq!(server.route("/repos/other").status, "404 Not Found");
+        assert_eq!(server.route("/nope").status, "404 Not Found");
+    }
+}
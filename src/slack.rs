@@ -0,0 +1,152 @@
+use crate::bar::Bars;
+use crate::json::string;
+use crate::number::Numbers;
+use crate::report::{Report, Value};
+use crate::table::{self, Layout};
+
+/// Slack rejects section text longer than this
+const MAX_SECTION: usize = 3000;
+
+/// Width of the longest histogram bar in characters, unless asked otherwise
+const BAR_WIDTH: usize = 20;
+
+/// A Block Kit message with a header naming the repository and a section per
+/// report, ready to POST to an incoming webhook or `chat.postMessage`. Tables
+/// are preformatted text since Slack has no tables of its own.
+pub fn payload(repo: &str, reports: &[Report], numbers: Numbers, bars: Bars) -> String {
+    let title = format!("yeesh: {}", repo);
+
+    let mut blocks = vec![format!(
+        "{{\"type\":\"header\",\"text\":{{\"type\":\"plain_text\",\"text\":{}}}}}",
+        string(&title)
+    )];
+
+    for report in reports {
+        blocks.push(format!(
+            "{{\"type\":\"section\",\"text\":{{\"type\":\"mrkdwn\",\"text\":{}}}}}",
+            string(&section(report, numbers, bars))
+        ));
+    }
+
+    format!(
+        "{{\"text\":{},\"blocks\":[{}]}}",
+        string(&title),
+        blocks.join(",")
+    )
+}
+
+/// The report's title in bold above its table in a code block. Rows that
+/// don't fit in a section are dropped and noted.
+fn section(report: &Report, numbers: Numbers, bars: Bars) -> String {
+    let title = format!("*{}*\n", report.title);
+    let lines = table(report, numbers, bars);
+
+    let mut text = String::new();
+    let mut shown = 0;
+
+    for line in &lines {
+        // Room for the title, closing fence, and a note about dropped rows
+        if title.len() + text.len() + line.len() + 64 > MAX_SECTION {
+            break;
+        }
+
+        text.push_str(line);
+        text.push('\n');
+        shown += 1;
+    }
+
+    let mut section = format!("{}```{}```", title, text);
+
+    if shown < lines.len() {
+        section.push_str(&format!("\n_{} more rows_", lines.len() - shown));
+    }
+
+    section
+}
+
+/// The header and rows padded into columns, with bars for histograms
+fn table(report: &Report, numbers: Numbers, bars: Bars) -> Vec<String> {
+    let table = &report.table;
+    let layout = Layout::default();
+    let grid = layout.grid(table, numbers);
+
+    let max = table
+        .rows
+        .iter()
+        .filter_map(|row| match row.get(1) {
+            Some(Value::Count(count)) => Some(*count),
+            _ => None,
+        })
+        .max()
+        .unwrap_or_default()
+        .max(1);
+
+    let mut lines = vec![layout.line(&grid.header).trim_end().to_string()];
+
+    for (row, cells) in table.rows.iter().zip(&grid.rows) {
+        let line = match (report.histogram, row.get(1)) {
+            (true, Some(Value::Count(count))) => {
+                let prefix = layout.line(cells);
+                let width = bars.width(*count, max, table::width(&prefix) + 2, Some(BAR_WIDTH));
+                format!("{}  {}", prefix, "█".repeat(width))
+                    .trim_end()
+                    .to_string()
+            }
+            _ => layout.line(cells).trim_end().to_string(),
+        };
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bar::Bars;
+    use crate::number::Numbers;
+    use crate::report::{Report, Table, Value};
+
+    #[test]
+    fn payload() {
+        let mut table = Table::new(&["hour", "commits"]);
+        table.push(vec![Value::Text("00".to_string()), Value::Count(2)]);
+        table.push(vec![Value::Text("01".to_string()), Value::Count(1)]);
+
+        let payload = super::payload(
+            "yeesh",
+            &[Report::histogram("By hour:", table)],
+            Numbers::Plain,
+            Bars::default(),
+        );
+        let json: serde_json::Value = serde_json::from_str(&payload).unwrap();
+
+        assert_eq!(json["text"], "yeesh: yeesh");
+        assert_eq!(json["blocks"][0]["type"], "header");
+        assert_eq!(
+            json["blocks"][1]["text"]["text"],
+            format!(
+                "*By hour:*\n```hour  commits\n00          2  {}\n01          1  {}\n```",
+                "█".repeat(20),
+                "█".repeat(10)
+            )
+        );
+    }
+
+    #[test]
+    fn long_sections_truncated() {
+        let mut table = Table::new(&["file", "changes"]);
+        for i in 0..500 {
+            table.push(vec![Value::Text(format!("src/{}.rs", i)), Value::Count(i)]);
+        }
+
+        let section = super::section(
+            &Report::new("Files:", table),
+            Numbers::Plain,
+            Bars::default(),
+        );
+
+        assert!(section.chars().count() <= super::MAX_SECTION);
+        assert!(section.ends_with("more rows_"));
+    }
+}
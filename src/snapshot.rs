@@ -0,0 +1,224 @@
+//! Every report rendered by every format from a fixture log, compared with the
+//! output saved under `src/snapshots` so changes to formatting are reviewed
+//! rather than discovered.
+//!
+//! When output changes on purpose, run the tests with `UPDATE_SNAPSHOTS=1` to
+//! save the new output and review its diff. Otherwise a mismatch writes the
+//! new output beside the old as `.snap.new` and fails.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use time::macros::{datetime, offset};
+
+use crate::bar::Bars;
+use crate::commit::{self, Commit};
+use crate::number::Numbers;
+use crate::report::changes::Snapshot;
+use crate::report::header::Context;
+use crate::report::list::Filter;
+use crate::report::{
+    author, changes, compare, file_age, handoff, header, list, merge_lead, newcomer, timezone,
+    Report, REGISTRY,
+};
+use crate::table::Layout;
+use crate::{html, json, markdown, mermaid, org, parser, slack, terminal};
+
+/// Written by `test_support::log(2022, 40)`, and kept as text so changes to
+/// the generator don't change every snapshot
+const FIXTURE: &str = include_str!("snapshots/fixture.log");
+
+fn commits() -> Vec<Commit> {
+    parser::parse(FIXTURE).unwrap()
+}
+
+/// Each report by name: those in `report::REGISTRY`, then those needing more
+/// than the history, made from the fixture, e.g. comparing its older half
+/// with its newer half
+fn reports(commits: &[Commit]) -> Vec<(&'static str, Vec<Report>)> {
+    let tags = commit::tags(commits);
+    let (newer, older) = commits.split_at(commits.len() / 2);
+
+    let mut reports: Vec<(&str, Vec<Report>)> = REGISTRY
+        .iter()
+        .map(|(name, make)| (*name, make(commits, &tags)))
+        .collect();
+
+    // The two commits before each merge stand in for the branch it merged
+    let merges = merge_lead::merges(commits, |base, _| {
+        let merge = commits
+            .iter()
+            .position(|commit| commit.parents.first().map(String::as_str) == Some(base))
+            .unwrap();
+
+        commits[merge + 1..]
+            .iter()
+            .take(2)
+            .map(|commit| commit.date.unix_timestamp())
+            .collect()
+    });
+
+    let tracked: Vec<String> = commits
+        .iter()
+        .flat_map(|commit| &commit.file_changes)
+        .map(|change| change.path.clone())
+        .skip(1)
+        .collect();
+
+    let context = Context {
+        repository: "yeesh".to_string(),
+        branch: Some("main".to_string()),
+        range: "HEAD".to_string(),
+        filters: vec!["--author jon".to_string()],
+    };
+
+    let previous = Snapshot::of(older, datetime!(2022-11-01 0:00 UTC));
+    let current = Snapshot::of(commits, datetime!(2022-11-28 0:00 UTC));
+
+    let filter = Filter {
+        hours: (9..17).collect(),
+        weekdays: vec![],
+    };
+
+    reports.extend([
+        ("merge-lead", merge_lead::reports(&merges)),
+        ("file-ages", file_age::reports(commits, &tracked)),
+        (
+            "compare",
+            compare::reports(&[("newer", newer), ("older", older)]),
+        ),
+        ("header", vec![header::report(&context, commits)]),
+        ("changes", vec![changes::report(Some(&previous), &current)]),
+        ("list", vec![list::report(commits, &filter)]),
+        ("newcomers", vec![newcomer::report(newer, older)]),
+        ("handoffs", vec![handoff::report(newer, older)]),
+        ("timezone", timezone::reports(commits, offset!(-5))),
+    ]);
+
+    reports
+}
+
+/// Numbers as they'd be written in an English locale, whatever this one is
+const NUMBERS: Numbers = Numbers::Grouped(',');
+
+/// Compares the output with the snapshot of the given name
+fn assert_snapshot(name: &str, output: &str) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/snapshots");
+    let path = dir.join(format!("{}.snap", name));
+    let new = dir.join(format!("{}.snap.new", name));
+
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, output).unwrap();
+        let _ = fs::remove_file(&new);
+        return;
+    }
+
+    let saved = fs::read_to_string(&path).unwrap_or_default();
+
+    if saved == output {
+        let _ = fs::remove_file(&new);
+        return;
+    }
+
+    fs::write(&new, output).unwrap();
+
+    let line = saved
+        .lines()
+        .zip(output.lines())
+        .position(|(saved, output)| saved != output)
+        .unwrap_or_else(|| saved.lines().count().min(output.lines().count()));
+
+    panic!(
+        "{} differs from its snapshot at line {}, see {}. Run with UPDATE_SNAPSHOTS=1 \
+        if that's intended.\nsaved:  {:?}\noutput: {:?}",
+        name,
+        line + 1,
+        new.display(),
+        saved.lines().nth(line).unwrap_or_default(),
+        output.lines().nth(line).unwrap_or_default(),
+    );
+}
+
+#[test]
+fn terminal() {
+    let commits = commits();
+
+    for (name, reports) in reports(&commits) {
+        let output: String = reports
+            .iter()
+            .map(|report| terminal::render(report, NUMBERS, Layout::default()))
+            .collect();
+
+        assert_snapshot(&format!("terminal/{}", name), &output);
+    }
+}
+
+#[test]
+fn terminal_layouts() {
+    let commits = commits();
+    let reports = author::reports(&commits);
+
+    let layouts = [
+        (
+            "borders",
+            Layout {
+                borders: true,
+                ..Default::default()
+            },
+        ),
+        (
+            "narrow",
+            Layout {
+                max_width: Some(30),
+                ..Default::default()
+            },
+        ),
+        (
+            "ascii",
+            Layout {
+                borders: true,
+                ascii: true,
+                ..Default::default()
+            },
+        ),
+    ];
+
+    for (name, layout) in layouts {
+        let output: String = reports
+            .iter()
+            .map(|report| terminal::render(report, Numbers::Plain, layout))
+            .collect();
+
+        assert_snapshot(&format!("layout/{}", name), &output);
+    }
+}
+
+#[test]
+fn formats() {
+    let commits = commits();
+    let reports: Vec<Report> = reports(&commits)
+        .into_iter()
+        .flat_map(|(_, reports)| reports)
+        .collect();
+
+    let formats = [
+        ("markdown", markdown::reports(&reports, NUMBERS)),
+        ("org", org::reports(&reports, NUMBERS)),
+        ("mermaid", mermaid::charts(&reports)),
+        (
+            "slack",
+            slack::payload("yeesh", &reports, NUMBERS, Bars::default()),
+        ),
+        ("json", json::reports(&reports)),
+        (
+            "html",
+            html::dashboard("yeesh", "2022-11-28T16:00:00Z", &reports),
+        ),
+    ];
+
+    for (name, output) in formats {
+        assert_snapshot(&format!("format/{}", name), &output);
+    }
+}
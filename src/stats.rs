@@ -0,0 +1,111 @@
+use time::OffsetDateTime;
+
+/// The middle value, or the mean of the middle two
+pub fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort_by(f64::total_cmp);
+
+    let middle = values.len() / 2;
+
+    if values.len().is_multiple_of(2) {
+        Some((values[middle - 1] + values[middle]) / 2.0)
+    } else {
+        Some(values[middle])
+    }
+}
+
+/// The nearest-rank percentile, e.g. `0.9` for the 90th
+pub fn percentile(mut values: Vec<f64>, quantile: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort_by(f64::total_cmp);
+
+    let rank = (quantile * values.len() as f64).ceil() as usize;
+
+    Some(values[rank.clamp(1, values.len()) - 1])
+}
+
+/// The Gini coefficient of each author's total, e.g. of commits: 0 when
+/// they're spread evenly across authors and nearing 1 when one author made
+/// them all. Uses the sorted-values formula, see
+/// https://en.wikipedia.org/wiki/Gini_coefficient#Alternative_expressions
+pub fn gini(values: &[u64]) -> f64 {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+
+    let n = values.len() as f64;
+    let total: u64 = values.iter().sum();
+
+    if total == 0 {
+        return 0.0;
+    }
+
+    let weighted: f64 = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (i + 1) as f64 * *value as f64)
+        .sum();
+
+    (2.0 * weighted) / (n * total as f64) - (n + 1.0) / n
+}
+
+/// Hours from one time to another, never less than none since clocks disagree
+pub fn hours_between(from: OffsetDateTime, to: OffsetDateTime) -> f64 {
+    ((to - from).as_seconds_f64() / 3600.0).max(0.0)
+}
+
+/// The hours at each quantile, labeled like `median hours` or `p90 hours` and
+/// then `of`, e.g. as rows of a summary
+pub fn hours_summary(hours: &[f64], quantiles: &[f64], of: &str) -> Vec<(String, Option<f64>)> {
+    quantiles
+        .iter()
+        .map(|&quantile| match quantile == 0.5 {
+            true => (format!("median hours{}", of), median(hours.to_vec())),
+            false => (
+                format!("p{} hours{}", (quantile * 100.0).round(), of),
+                percentile(hours.to_vec(), quantile),
+            ),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn median() {
+        assert_eq!(super::median(vec![]), None);
+        assert_eq!(super::median(vec![3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(super::median(vec![4.0, 1.0, 2.0, 3.0]), Some(2.5));
+    }
+
+    #[test]
+    fn percentile() {
+        let values: Vec<f64> = (1..=10).map(f64::from).collect();
+
+        assert_eq!(super::percentile(vec![], 0.9), None);
+        assert_eq!(super::percentile(values.clone(), 0.9), Some(9.0));
+        assert_eq!(super::percentile(values.clone(), 0.5), Some(5.0));
+        assert_eq!(super::percentile(values, 0.0), Some(1.0));
+    }
+
+    #[test]
+    fn hours_summary() {
+        let rows = super::hours_summary(&[1.0, 2.0, 3.0, 4.0], &[0.5, 0.9], " to land");
+
+        assert_eq!(rows[0], ("median hours to land".to_string(), Some(2.5)));
+        assert_eq!(rows[1], ("p90 hours to land".to_string(), Some(4.0)));
+    }
+
+    #[test]
+    fn gini() {
+        assert_eq!(super::gini(&[]), 0.0);
+        assert_eq!(super::gini(&[5, 5, 5, 5]), 0.0);
+        assert_eq!(super::gini(&[0, 0, 0, 8]), 0.75);
+        assert!((super::gini(&[1, 2, 3, 4]) - 0.25).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,248 @@
+//! Waiting on other programs, e.g. git reading a history from a network mount,
+//! without waiting forever. A program running longer than `--timeout` is
+//! killed, one running a while is said to be waited on, and Ctrl-C stops it
+//! along with yeesh.
+
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::bail;
+
+/// How long a program runs before saying it's being waited on
+const PATIENCE: Duration = Duration::from_secs(5);
+
+/// Seconds a program may run, or none for as long as it takes
+static TIMEOUT: AtomicU64 = AtomicU64::new(0);
+
+/// The process group of the program being waited on, for Ctrl-C to stop too,
+/// or none
+static RUNNING: AtomicI32 = AtomicI32::new(0);
+
+/// Kill programs running longer than the timeout, or never with none
+pub fn set_timeout(timeout: Option<Duration>) {
+    let seconds = timeout.map(|timeout| timeout.as_secs().max(1)).unwrap_or(0);
+    TIMEOUT.store(seconds, Ordering::SeqCst);
+}
+
+fn timeout() -> Option<Duration> {
+    match TIMEOUT.load(Ordering::SeqCst) {
+        0 => None,
+        seconds => Some(Duration::from_secs(seconds)),
+    }
+}
+
+/// Kill the program being waited on as well as yeesh on Ctrl-C. The terminal
+/// interrupts both but a program stuck on a network mount, or ignoring it, could
+/// otherwise outlive yeesh.
+pub fn stop_on_interrupt() {
+    let handler: extern "C" fn(libc::c_int) = interrupt;
+
+    unsafe {
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+extern "C" fn interrupt(_signal: libc::c_int) {
+    // Nothing but what's safe in a signal handler, so no locks or allocating
+    let pid = RUNNING.load(Ordering::SeqCst);
+
+    unsafe {
+        if pid > 0 {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+
+        // The exit status of a shell's interrupted command
+        libc::_exit(130);
+    }
+}
+
+/// Like `Command::output()`, but the program is watched while it runs and an
+/// error saying what it wrote to stderr when it fails
+pub fn output(command: &mut Command, name: &str) -> anyhow::Result<Output> {
+    let output = output_with_status(command, name)?;
+    check(name, output.status, &output.stderr)?;
+
+    Ok(output)
+}
+
+/// Like `output()`, but whether the program succeeded is left to the caller,
+/// e.g. for files git can't blame
+pub fn output_with_status(command: &mut Command, name: &str) -> anyhow::Result<Output> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let (child, watch) = Watch::spawn(command, name)?;
+    let output = child.wait_with_output();
+    watch.finish()?;
+
+    Ok(output?)
+}
+
+/// An error with what the program wrote to stderr when it failed, e.g. git
+/// run outside a repository
+pub fn check(name: &str, status: ExitStatus, stderr: &[u8]) -> anyhow::Result<()> {
+    let stderr = String::from_utf8_lossy(stderr);
+
+    match stderr.trim() {
+        _ if status.success() => Ok(()),
+        "" => bail!("{} failed with {}", name, status),
+        stderr => bail!("{} failed: {}", name, stderr),
+    }
+}
+
+/// A running program, killed when it runs past the timeout. Call `finish()`
+/// once it's exited to stop watching it.
+///
+/// It's started in a process group of its own so whatever it starts in turn
+/// is killed with it, e.g. a helper holding its output open. That also keeps
+/// the terminal's Ctrl-C from reaching it, so yeesh has to pass that on.
+pub struct Watch {
+    name: String,
+    pid: i32,
+    timeout: Option<Duration>,
+    done: Sender<()>,
+    expired: Arc<AtomicBool>,
+}
+
+impl Watch {
+    pub fn spawn(command: &mut Command, name: &str) -> io::Result<(Child, Watch)> {
+        Watch::spawn_within(command, name, timeout())
+    }
+
+    fn spawn_within(
+        command: &mut Command,
+        name: &str,
+        timeout: Option<Duration>,
+    ) -> io::Result<(Child, Watch)> {
+        let child = command.process_group(0).spawn()?;
+        let pid = child.id() as i32;
+        let (done, exited) = mpsc::channel::<()>();
+        let expired = Arc::new(AtomicBool::new(false));
+
+        RUNNING.store(pid, Ordering::SeqCst);
+
+        let waiting = format!("Waiting on {}, press Ctrl-C to stop it", name);
+
+        thread::spawn({
+            let expired = expired.clone();
+
+            move || {
+                let running = |time| exited.recv_timeout(time) == Err(RecvTimeoutError::Timeout);
+
+                // Said only when there's time left to wait
+                let waited = match timeout {
+                    Some(timeout) if timeout <= PATIENCE => Duration::ZERO,
+                    _ if running(PATIENCE) => {
+                        if termion::is_tty(&io::stderr()) {
+                            eprintln!("{}", waiting);
+                        }
+                        PATIENCE
+                    }
+                    _ => return,
+                };
+
+                if let Some(timeout) = timeout {
+                    if running(timeout - waited) {
+                        expired.store(true, Ordering::SeqCst);
+
+                        unsafe {
+                            libc::kill(-pid, libc::SIGKILL);
+                        }
+                    }
+                }
+            }
+        });
+
+        let watch = Watch {
+            name: name.to_string(),
+            pid,
+            timeout,
+            done,
+            expired,
+        };
+
+        Ok((child, watch))
+    }
+
+    /// An error when the program was killed for running past the timeout
+    pub fn finish(self) -> anyhow::Result<()> {
+        let _ = self.done.send(());
+        let _ = RUNNING.compare_exchange(self.pid, 0, Ordering::SeqCst, Ordering::SeqCst);
+
+        if self.expired.load(Ordering::SeqCst) {
+            bail!(
+                "Stopped {} after {}s, see --timeout",
+                self.name,
+                self.timeout.unwrap_or_default().as_secs()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+    use std::time::{Duration, Instant};
+
+    use super::Watch;
+
+    #[test]
+    fn output() {
+        let output = super::output(Command::new("sh").arg("-c").arg("echo hi"), "sh").unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hi\n");
+    }
+
+    #[test]
+    fn failure() {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg("echo 'fatal: not a git repository' >&2; exit 128");
+
+        let error = super::output(&mut command, "git log").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "git log failed: fatal: not a git repository"
+        );
+
+        let output = super::output_with_status(&mut command, "git log").unwrap();
+        assert_eq!(output.status.code(), Some(128));
+
+        let error = super::output(&mut Command::new("false"), "false").unwrap_err();
+        assert_eq!(error.to_string(), "false failed with exit status: 1");
+    }
+
+    #[test]
+    fn timeout() {
+        let second = Some(Duration::from_secs(1));
+        let started = Instant::now();
+
+        // The shell's sleep is killed too, or it'd hold the output open
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("sleep 10; echo done");
+
+        let (child, watch) = Watch::spawn_within(&mut command, "sh", second).unwrap();
+        let output = child.wait_with_output().unwrap();
+        let error = watch.finish().unwrap_err();
+
+        assert_eq!(error.to_string(), "Stopped sh after 1s, see --timeout");
+        assert!(output.stdout.is_empty());
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Finished in time
+        let (mut child, watch) =
+            Watch::spawn_within(&mut Command::new("true"), "true", second).unwrap();
+        child.wait().unwrap();
+        assert!(watch.finish().is_ok());
+    }
+}
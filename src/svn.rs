@@ -0,0 +1,225 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Context;
+use lazy_static::lazy_static;
+use regex::Regex;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::commit::{Author, Commit, FileChange};
+use crate::subprocess;
+
+lazy_static! {
+    static ref LOG_ENTRY: Regex =
+        Regex::new(r#"(?s)<logentry\s+revision="(\d+)"\s*>(.*?)</logentry>"#).unwrap();
+    static ref AUTHOR: Regex = Regex::new(r"(?s)<author>(.*?)</author>").unwrap();
+    static ref DATE: Regex = Regex::new(r"(?s)<date>(.*?)</date>").unwrap();
+    static ref MESSAGE: Regex = Regex::new(r"(?s)<msg>(.*?)</msg>").unwrap();
+    static ref PATH: Regex = Regex::new(r"(?s)<path\b[^>]*>(.*?)</path>").unwrap();
+}
+
+/// What svn itself shows for revisions without an author, e.g. ones made by
+/// `cvs2svn`
+const NO_AUTHOR: &str = "(no author)";
+
+/// An svn command that never prompts for credentials, in the same language on
+/// every machine
+fn svn() -> Command {
+    let mut command = Command::new("svn");
+    command.env("LC_ALL", "C").arg("--non-interactive");
+    command
+}
+
+/// The repository's revisions as commits, newest first, from the working copy
+/// at the given path.
+///
+/// svn records neither lines changed nor time zones, so each path a revision
+/// touched counts as one change and dates are in UTC. Authors have no email
+/// so they're told apart by username.
+pub fn commits(repo: &Path) -> anyhow::Result<Vec<Commit>> {
+    let mut command = svn();
+    command.arg("log").arg("--xml").arg("--verbose").arg(repo);
+
+    let output =
+        subprocess::output(&mut command, "svn log").context("Can't run svn to read logs")?;
+
+    parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Where in the repository the working copy is checked out from, e.g.
+/// `^/trunk`, which is as near as svn comes to a branch
+pub fn branch(repo: &Path) -> anyhow::Result<String> {
+    let output = svn()
+        .arg("info")
+        .arg("--show-item")
+        .arg("relative-url")
+        .arg(repo)
+        .output()
+        .context("Can't run svn to find the branch")?;
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn parse(xml: &str) -> anyhow::Result<Vec<Commit>> {
+    LOG_ENTRY
+        .captures_iter(xml)
+        .map(|entry| {
+            let revision = &entry[1];
+
+            parse_entry(revision, &entry[2])
+                .with_context(|| format!("Revision {} is invalid", revision))
+        })
+        .collect()
+}
+
+fn parse_entry(revision: &str, entry: &str) -> anyhow::Result<Commit> {
+    let element = |regex: &Regex| regex.captures(entry).map(|found| unescape(&found[1]));
+
+    let name = element(&AUTHOR).unwrap_or_else(|| NO_AUTHOR.to_string());
+
+    let date = element(&DATE).context("There's no date")?;
+    let date = OffsetDateTime::parse(&date, &Rfc3339)
+        .with_context(|| format!("Can't read the date {:?}", date))?;
+
+    let file_changes: Vec<FileChange> = PATH
+        .captures_iter(entry)
+        .map(|path| {
+            let path = unescape(&path[1]);
+            FileChange::new(path.trim_start_matches('/').to_string(), 1)
+        })
+        .collect();
+
+    Ok(Commit {
+        hash: format!("r{}", revision),
+        parents: Vec::new(),
+        refs: Vec::new(),
+        author: Author::identified(&name, ""),
+        date,
+        committed: None,
+        message: element(&MESSAGE).unwrap_or_default(),
+        files: file_changes.len() as u32,
+        inserts: 0,
+        deletes: 0,
+        file_changes,
+    })
+}
+
+/// Text with XML's entities and character references replaced
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+
+        let c = entity.and_then(|(name, _)| match name {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match name.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => name.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+            }
+            .and_then(char::from_u32),
+        });
+
+        match (c, entity) {
+            (Some(c), Some((_, end))) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    unescaped.push_str(rest);
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    #[test]
+    fn parse() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<log>
+<logentry
+   revision="42">
+<author>jon</author>
+<date>2022-11-29T00:00:00.123456Z</date>
+<paths>
+<path
+   kind="file"
+   action="M"
+   prop-mods="false"
+   text-mods="true">/trunk/src/main.rs</path>
+<path
+   kind="file"
+   action="A">/trunk/R&amp;D.md</path>
+</paths>
+<msg>Fix &lt;br&gt; &#8220;quotes&#x201D;
+
+With a body</msg>
+</logentry>
+<logentry
+   revision="1">
+<date>2022-01-03T09:00:00.000000Z</date>
+<paths>
+<path
+   action="A"
+   kind="dir">/trunk</path>
+</paths>
+<msg></msg>
+</logentry>
+</log>
+"#;
+
+        let commits = super::parse(xml).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "r42");
+        assert_eq!(commits[0].author.name, "jon");
+        assert_eq!(commits[0].author.email, "jon");
+        assert_eq!(commits[0].date, datetime!(2022-11-29 0:00:00.123456 UTC));
+        assert_eq!(
+            commits[0].message,
+            "Fix <br> \u{201c}quotes\u{201d}\n\nWith a body"
+        );
+        assert_eq!(commits[0].files, 2);
+        assert_eq!(commits[0].file_changes[0].path, "trunk/src/main.rs");
+        assert_eq!(commits[0].file_changes[1].path, "trunk/R&D.md");
+        assert_eq!(commits[0].file_changes[1].changes, 1);
+
+        assert_eq!(commits[1].author.name, "(no author)");
+        assert_eq!(commits[1].message, "");
+    }
+
+    #[test]
+    fn invalid() {
+        let xml = r#"<logentry revision="7"><date>yesterday</date></logentry>"#;
+        let error = super::parse(xml).unwrap_err();
+
+        assert!(format!("{:#}", error)
+            .starts_with("Revision 7 is invalid: Can't read the date \"yesterday\""));
+    }
+
+    #[test]
+    fn unescape() {
+        assert_eq!(super::unescape("a &amp; b &lt;c&gt;"), "a & b <c>");
+        assert_eq!(super::unescape("&#65;&#x42;"), "AB");
+        assert_eq!(
+            super::unescape("fish & chips &nope;"),
+            "fish & chips &nope;"
+        );
+    }
+}
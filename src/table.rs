@@ -0,0 +1,470 @@
+use crate::ascii;
+use crate::number::Numbers;
+use crate::report::{Table, Value};
+
+/// Columns of text never shrink narrower than this to fit, so some of it shows
+const MIN_WIDTH: usize = 4;
+
+/// Ends text cut short to fit its column
+const ELLIPSIS: &str = "…";
+
+/// Which side of its column a cell's text sits against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    /// Numbers, so their digits line up
+    Right,
+}
+
+impl Align {
+    /// Right for a column of numbers, ignoring empty cells, and otherwise left
+    fn of(table: &Table, column: usize) -> Align {
+        let mut values = table
+            .rows
+            .iter()
+            .filter_map(|row| row.get(column))
+            .filter(|value| **value != Value::Empty)
+            .peekable();
+
+        let numeric = values.peek().is_some()
+            && values.all(|value| {
+                matches!(
+                    value,
+                    Value::Count(_) | Value::Decimal(_) | Value::Ratio(_) | Value::Percent(_)
+                )
+            });
+
+        if numeric {
+            Align::Right
+        } else {
+            Align::Left
+        }
+    }
+}
+
+/// How tables of columns are laid out, on the terminal or in Slack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Layout {
+    /// The widest a table may be in columns. The widest columns of text are
+    /// narrowed to fit, cutting their text short with an ellipsis.
+    pub max_width: Option<usize>,
+    /// Lines around the table, under its header, and between its columns
+    pub borders: bool,
+    /// Only ASCII, with cells spelled in it and borders drawn with it
+    pub ascii: bool,
+}
+
+/// A table's header and rows as text, each cell padded or cut short to its
+/// column's width
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub widths: Vec<usize>,
+}
+
+impl Layout {
+    /// The cells of the table laid out in columns, with numbers aligned to the
+    /// right and everything else to the left
+    pub fn grid(&self, table: &Table, numbers: Numbers) -> Grid {
+        let text = |text: String| match self.ascii {
+            true => ascii::text(&text),
+            false => text,
+        };
+
+        let header: Vec<String> = table.columns.iter().cloned().map(text).collect();
+
+        let rows: Vec<Vec<String>> = table
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| text(numbers.format(value)))
+                    .collect()
+            })
+            .collect();
+
+        let aligns: Vec<Align> = (0..table.columns.len())
+            .map(|i| Align::of(table, i))
+            .collect();
+
+        let mut widths: Vec<usize> = header
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                rows.iter()
+                    .filter_map(|row| row.get(i))
+                    .chain([column])
+                    .map(|cell| width(cell))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        if let Some(max_width) = self.max_width {
+            self.narrow(&mut widths, &aligns, max_width);
+        }
+
+        let ellipsis = if self.ascii { "..." } else { ELLIPSIS };
+
+        let pad_all = |cells: &[String]| -> Vec<String> {
+            cells
+                .iter()
+                .zip(widths.iter().zip(&aligns))
+                .map(|(cell, (width, align))| pad_with(cell, *width, *align, ellipsis))
+                .collect()
+        };
+
+        Grid {
+            header: pad_all(&header),
+            rows: rows.iter().map(|row| pad_all(row)).collect(),
+            widths: widths.clone(),
+        }
+    }
+
+    /// Shrink the widest column of text a column at a time until the table
+    /// fits, or every column of text is as narrow as it gets. Numbers are
+    /// never cut short.
+    fn narrow(&self, widths: &mut [usize], aligns: &[Align], max_width: usize) {
+        let frame =
+            width(self.gap()) * widths.len().saturating_sub(1) + if self.borders { 4 } else { 0 };
+
+        while widths.iter().sum::<usize>() + frame > max_width {
+            let widest = widths
+                .iter_mut()
+                .zip(aligns)
+                .filter(|(width, align)| **align == Align::Left && **width > MIN_WIDTH)
+                .map(|(width, _)| width)
+                .max_by_key(|width| **width);
+
+            match widest {
+                Some(width) => *width -= 1,
+                None => break,
+            }
+        }
+    }
+
+    /// Between the cells of a line, which is a border when they're drawn
+    pub fn gap(&self) -> &'static str {
+        match (self.borders, self.ascii) {
+            (true, false) => " │ ",
+            (true, true) => " | ",
+            (false, _) => "  ",
+        }
+    }
+
+    /// Before and after the cells of a line, which are borders when they're
+    /// drawn
+    pub fn edges(&self) -> (&'static str, &'static str) {
+        match (self.borders, self.ascii) {
+            (true, false) => ("│ ", " │"),
+            (true, true) => ("| ", " |"),
+            (false, _) => ("", ""),
+        }
+    }
+
+    /// Cells joined into a line, between borders when they're drawn
+    pub fn line(&self, cells: &[String]) -> String {
+        let (left, right) = self.edges();
+
+        format!("{}{}{}", left, cells.join(self.gap()), right)
+    }
+
+    /// The border above the header, under it, or under the last row, e.g.
+    /// `├──────┼─────┤`, when they're drawn
+    pub fn rule(&self, widths: &[usize], edge: Edge) -> Option<String> {
+        if !self.borders {
+            return None;
+        }
+
+        let (left, joint, right) = match (edge, self.ascii) {
+            (_, true) => ('+', '+', '+'),
+            (Edge::Top, false) => ('┌', '┬', '┐'),
+            (Edge::Header, false) => ('├', '┼', '┤'),
+            (Edge::Bottom, false) => ('└', '┴', '┘'),
+        };
+
+        let line = if self.ascii { "-" } else { "─" };
+        let lines: Vec<String> = widths.iter().map(|width| line.repeat(width + 2)).collect();
+
+        Some(format!(
+            "{}{}{}",
+            left,
+            lines.join(&joint.to_string()),
+            right
+        ))
+    }
+}
+
+/// Where a border rule goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Top,
+    Header,
+    Bottom,
+}
+
+/// Columns the text takes up on a terminal. Most emoji and East Asian
+/// characters take two, as do symbols asked to look like emoji, and emoji
+/// joined into one, e.g. 👩‍💻, take as many as the first. Variation
+/// selectors, skin tones, and combining marks take none. This is rough but
+/// covers what turns up in names, paths, and commit subjects.
+pub fn width(text: &str) -> usize {
+    let mut chars = text.chars().peekable();
+    let mut columns = 0;
+
+    while let Some(c) = chars.next() {
+        if c == '\u{200D}' {
+            chars.next();
+            continue;
+        }
+
+        columns += match (char_width(c), chars.peek()) {
+            (1, Some('\u{FE0F}')) => 2,
+            (width, _) => width,
+        };
+    }
+
+    columns
+}
+
+fn char_width(c: char) -> usize {
+    match c {
+        '\u{200B}'..='\u{200F}'
+        | '\u{0300}'..='\u{036F}'
+        | '\u{FE00}'..='\u{FE0F}'
+        | '\u{1F3FB}'..='\u{1F3FF}' => 0,
+        '\u{1100}'..='\u{115F}'
+        | '\u{2E80}'..='\u{A4CF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FE30}'..='\u{FE4F}'
+        | '\u{FF00}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE6}'
+        | '\u{1F300}'..='\u{1F64F}'
+        | '\u{1F680}'..='\u{1F6FF}'
+        | '\u{1F900}'..='\u{1FAFF}'
+        | '\u{20000}'..='\u{3FFFD}' => 2,
+        _ => 1,
+    }
+}
+
+/// The text padded with spaces to `width` columns on the side `align` says,
+/// or cut short with an ellipsis when it's wider
+pub fn pad(text: &str, width: usize, align: Align) -> String {
+    pad_with(text, width, align, ELLIPSIS)
+}
+
+fn pad_with(text: &str, width: usize, align: Align, ellipsis: &str) -> String {
+    let text = truncate(text, width, ellipsis);
+    let padding = " ".repeat(width.saturating_sub(self::width(&text)));
+
+    match align {
+        Align::Left => format!("{}{}", text, padding),
+        Align::Right => format!("{}{}", padding, text),
+    }
+}
+
+/// The text if it fits in `width` columns, or as much of it as fits with an
+/// ellipsis
+fn truncate(text: &str, width: usize, ellipsis: &str) -> String {
+    if self::width(text) <= width {
+        return text.to_string();
+    }
+
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut end = 0;
+
+    for (i, c) in text.char_indices() {
+        let next = i + c.len_utf8();
+
+        if self::width(&text[..next]) + self::width(ellipsis) > width {
+            break;
+        }
+
+        end = next;
+    }
+
+    format!("{}{}", text[..end].trim_end(), ellipsis)
+}
+
+/// How a pipe table's rule and cells are written, which is all that differs
+/// between markdown and org-mode tables
+pub struct Style {
+    /// Where the rule under the header crosses a column boundary, `|` for
+    /// markdown and `+` for org
+    pub joint: char,
+    /// Make a cell's text safe to put between pipes
+    pub escape: fn(&str) -> String,
+}
+
+/// A table with columns separated by pipes and padded to their widest cell so
+/// it reads as well as plain text as it renders
+pub fn pipes(table: &Table, style: &Style, numbers: Numbers) -> String {
+    let header: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column| (style.escape)(column))
+        .collect();
+
+    let rows: Vec<Vec<String>> = table
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| (style.escape)(&numbers.format(value)))
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .chain([column])
+                .map(|cell| width(cell))
+                .max()
+                .unwrap_or_default()
+                // Markdown needs at least three dashes in a rule
+                .max(3)
+        })
+        .collect();
+
+    let line = |cells: &[String]| -> String {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!(" {} ", pad(cell, *width, Align::Left)))
+            .collect();
+
+        format!("|{}|\n", cells.join("|"))
+    };
+
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(width + 2)).collect();
+
+    let mut text = line(&header);
+    text.push_str(&format!("|{}|\n", rule.join(&style.joint.to_string())));
+
+    for row in &rows {
+        text.push_str(&line(row));
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::number::Numbers;
+    use crate::report::{Table, Value};
+
+    use super::{Align, Edge, Layout, Style};
+
+    #[test]
+    fn pipes() {
+        let mut table = Table::new(&["author", "commits"]);
+        table.push(vec![Value::Text("Jon".to_string()), Value::Count(3)]);
+        table.push(vec![Value::Text("a|b".to_string()), Value::Count(1200)]);
+
+        let style = Style {
+            joint: '+',
+            escape: |text| text.replace('|', "/"),
+        };
+
+        assert_eq!(
+            super::pipes(&table, &style, Numbers::Grouped(',')),
+            "\
+| author | commits |
+|--------+---------|
+| Jon    | 3       |
+| a/b    | 1,200   |
+"
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let mut table = Table::new(&["author", "commits", "note"]);
+        table.push(vec![
+            Value::Text("Jonathan Neufeld".to_string()),
+            Value::Count(3),
+            Value::Empty,
+        ]);
+        table.push(vec![
+            Value::Text("🐛 bugs".to_string()),
+            Value::Count(1200),
+            Value::Text("a long note".to_string()),
+        ]);
+
+        let grid = Layout::default().grid(&table, Numbers::Grouped(','));
+        assert_eq!(grid.widths, vec![16, 7, 11]);
+        assert_eq!(grid.rows[0][1], "      3");
+        assert_eq!(grid.rows[1][0], "🐛 bugs         ");
+
+        let narrow = Layout {
+            max_width: Some(30),
+            ..Default::default()
+        };
+        let grid = narrow.grid(&table, Numbers::Grouped(','));
+        assert_eq!(grid.widths, vec![10, 7, 9]);
+        assert_eq!(grid.rows[0][0], "Jonathan… ");
+        assert_eq!(grid.rows[1][1], "  1,200");
+        assert_eq!(grid.rows[1][2], "a long n…");
+
+        // Numbers aren't cut short even when nothing else fits
+        let tiny = Layout {
+            max_width: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(tiny.grid(&table, Numbers::Plain).widths, vec![4, 7, 4]);
+    }
+
+    #[test]
+    fn borders() {
+        let layout = Layout {
+            borders: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            layout.line(&["a".to_string(), "bc".to_string()]),
+            "│ a │ bc │"
+        );
+        assert_eq!(
+            layout.rule(&[1, 2], Edge::Header),
+            Some("├───┼────┤".to_string())
+        );
+        assert_eq!(Layout::default().rule(&[1, 2], Edge::Top), None);
+
+        let ascii = Layout {
+            ascii: true,
+            max_width: Some(14),
+            ..layout
+        };
+
+        let mut table = Table::new(&["author", "commits"]);
+        table.push(vec![
+            Value::Text("José Müller".to_string()),
+            Value::Count(3),
+        ]);
+
+        let grid = ascii.grid(&table, Numbers::Plain);
+        assert_eq!(ascii.line(&grid.rows[0]), "| J... |       3 |");
+        assert_eq!(
+            ascii.rule(&grid.widths, Edge::Bottom),
+            Some("+------+---------+".to_string())
+        );
+    }
+
+    #[test]
+    fn pad() {
+        assert_eq!(super::width("👩‍💻 ⚡️ 日本"), 10);
+        assert_eq!(super::pad("abc", 5, Align::Right), "  abc");
+        assert_eq!(super::pad("abcdef", 4, Align::Left), "abc…");
+        assert_eq!(super::pad("日本語", 4, Align::Left), "日… ");
+    }
+}
@@ -0,0 +1,163 @@
+//! Reports tallied from each commit as git writes it, for `--low-memory`.
+//! Commits are borrowed from git's output and only totals are kept of them,
+//! with each author's name and email kept once, so memory doesn't grow with the
+//! history.
+
+use std::collections::HashMap;
+
+use hdrhistogram::Histogram as Distribution;
+
+use crate::commit::CommitRef;
+use crate::histogram::{self, Weight};
+use crate::report::size::{self, Percentiles};
+use crate::report::{activity, Report};
+
+/// Totals for the hour, weekday, and size reports
+pub struct Tally {
+    weight: Weight,
+    /// Like `histogram::by_weekday_and_hour()`
+    weekday_hours: [[u64; 24]; 7],
+    sizes: Distribution<u64>,
+    /// Each author's name and sizes, in the order they were first read
+    authors: Vec<(String, Distribution<u64>)>,
+    /// Where in `authors` each is, by email like `commit::by_author()`
+    by_email: HashMap<String, usize>,
+}
+
+impl Tally {
+    pub fn new(weight: Weight) -> Tally {
+        Tally {
+            weight,
+            weekday_hours: [[0; 24]; 7],
+            sizes: Percentiles::distribution(),
+            authors: Vec::new(),
+            by_email: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, commit: &CommitRef) {
+        let weekday = commit.date.weekday().number_days_from_monday() as usize;
+        let hour = commit.date.hour() as usize;
+        let churn = commit.inserts as u64 + commit.deletes as u64;
+
+        self.weekday_hours[weekday][hour] += self.weight.of_totals(commit.files, churn);
+        self.sizes.record(churn).unwrap();
+
+        // Only an author not seen before is copied
+        let author = match self.by_email.get(commit.email) {
+            Some(&author) => author,
+            None => {
+                let author = self.authors.len();
+                self.authors
+                    .push((commit.name.to_string(), Percentiles::distribution()));
+                self.by_email.insert(commit.email.to_string(), author);
+                author
+            }
+        };
+
+        self.authors[author].1.record(churn).unwrap();
+    }
+
+    pub fn hours(&self) -> [u64; 24] {
+        histogram::hours(&self.weekday_hours)
+    }
+
+    pub fn weekdays(&self) -> [u64; 7] {
+        histogram::weekdays(&self.weekday_hours)
+    }
+
+    /// Like `activity::hours()`
+    pub fn hours_report(&self) -> Report {
+        activity::hours_of(self.hours(), self.weight)
+    }
+
+    /// Like `activity::weekdays()`
+    pub fn weekdays_report(&self) -> Report {
+        activity::weekdays_of(self.weekdays(), self.weight)
+    }
+
+    /// Like `size::overall()` and `size::by_author()`
+    pub fn size_reports(&self) -> Vec<Report> {
+        let authors = self
+            .authors
+            .iter()
+            .map(|(name, sizes)| (name.as_str(), Percentiles::of_distribution(sizes)))
+            .collect();
+
+        vec![
+            size::overall_of(Percentiles::of_distribution(&self.sizes)),
+            size::by_author_of(authors),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::commit::{Commit, CommitRef};
+    use crate::histogram::{self, Weight};
+    use crate::report::{activity, size};
+    use crate::test_support::commit;
+
+    use super::Tally;
+
+    #[test]
+    fn same_as_owned() {
+        let slim = |name, email, date, inserts, deletes| CommitRef {
+            name,
+            email,
+            date,
+            files: 1,
+            inserts,
+            deletes,
+            ..Default::default()
+        };
+
+        let borrowed = vec![
+            slim("Jon", "jon@email.ca", datetime!(2022-11-28 9:15 -8), 10, 2),
+            slim("Sam", "sam@email.ca", datetime!(2022-11-27 23:00 +1), 1, 0),
+            slim("Jon", "jon@email.ca", datetime!(2022-11-29 9:45 -8), 0, 40),
+        ];
+
+        let owned: Vec<Commit> = borrowed
+            .iter()
+            .map(|borrowed| {
+                commit()
+                    .author(borrowed.name, borrowed.email)
+                    .on(borrowed.date)
+                    .files(borrowed.files)
+                    .lines(borrowed.inserts, borrowed.deletes)
+                    .build()
+            })
+            .collect();
+
+        let mut tally = Tally::new(Weight::Lines);
+
+        for commit in &borrowed {
+            tally.add(commit);
+        }
+
+        assert_eq!(tally.hours(), histogram::by_hour(&owned, Weight::Lines));
+        assert_eq!(tally.hours()[9], 52);
+        assert_eq!(
+            tally.weekdays(),
+            histogram::by_weekday(&owned, Weight::Lines)
+        );
+
+        let tallied = [tally.weekdays_report()]
+            .into_iter()
+            .chain(tally.size_reports());
+        let owned = [
+            activity::weekdays(&owned, Weight::Lines),
+            size::overall(&owned),
+            size::by_author(&owned),
+        ];
+
+        for (tallied, owned) in tallied.zip(owned) {
+            assert_eq!(tallied.title, owned.title);
+            assert_eq!(tallied.table.columns, owned.table.columns);
+            assert_eq!(tallied.table.rows, owned.table.rows);
+        }
+    }
+}
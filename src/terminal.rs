@@ -0,0 +1,84 @@
+use std::cmp::Ordering;
+
+use termion::{color, style};
+
+use crate::number::Numbers;
+use crate::report::{Report, Value};
+use crate::table::{Edge, Layout};
+
+/// The report as a table with each column padded to its widest value
+pub fn render(report: &Report, numbers: Numbers, layout: Layout) -> String {
+    let mut out = format!(
+        "{}{}{}{}\n",
+        style::Bold,
+        color::Fg(color::Magenta),
+        report.title,
+        style::Reset
+    );
+
+    let table = &report.table;
+    let grid = layout.grid(table, numbers);
+
+    let rule = |out: &mut String, edge| {
+        if let Some(rule) = layout.rule(&grid.widths, edge) {
+            out.push_str(&format!("{}{}{}\n", style::Faint, rule, style::Reset));
+        }
+    };
+
+    rule(&mut out, Edge::Top);
+    out.push_str(&format!(
+        "{}{}{}\n",
+        style::Faint,
+        layout.line(&grid.header),
+        style::Reset
+    ));
+    rule(&mut out, Edge::Header);
+
+    let (left, right) = layout.edges();
+
+    // Like the histograms, the first column is a label and stands out from the
+    // values following it
+    for (i, row) in grid.rows.iter().enumerate() {
+        let (label, values) = match row.split_first() {
+            Some((label, values)) => (label.as_str(), values),
+            None => ("", &row[..]),
+        };
+
+        let change = match (&report.previous, table.rows[i].last()) {
+            (Some(previous), Some(Value::Count(count))) => change(*count, previous[i]),
+            _ => String::new(),
+        };
+
+        let gap = if values.is_empty() { "" } else { layout.gap() };
+
+        out.push_str(&format!(
+            "{}{}{}{}{}{}{}{}{}\n",
+            left,
+            color::Fg(color::LightBlue),
+            label,
+            gap,
+            color::Fg(color::White),
+            values.join(layout.gap()),
+            right,
+            change,
+            style::Reset
+        ));
+    }
+
+    rule(&mut out, Edge::Bottom);
+
+    out.push('\n');
+    out
+}
+
+/// An arrow and the percent change from the period before, e.g. `  ↑ 25%`
+fn change(count: u64, previous: u64) -> String {
+    let percent = (count as f64 - previous as f64) * 100.0 / previous as f64;
+
+    match count.cmp(&previous) {
+        _ if previous == 0 && count > 0 => format!("  {}↑ new", color::Fg(color::Green)),
+        Ordering::Greater => format!("  {}↑ {:.0}%", color::Fg(color::Green), percent),
+        Ordering::Less => format!("  {}↓ {:.0}%", color::Fg(color::Red), -percent),
+        Ordering::Equal => format!("  {}= 0%", style::Faint),
+    }
+}
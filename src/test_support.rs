@@ -0,0 +1,552 @@
+//! Realistic, randomized `git log` output for testing the parser and anything
+//! built on it, along with the commits it should parse into. Logs have
+//! merges, renames, binary files, decorations, multi-line messages, authors
+//! with odd or missing names and emails, and unicode throughout, in the
+//! `--pretty=fuller --stat` format `git::logs()` asks for.
+//!
+//! The same seed always gives the same log so a failure can be reproduced
+//! from the seed it reports. Built for tests, or with the `test-support`
+//! feature.
+//!
+//! Tests needing only a few commits build them with `commit()` instead.
+
+use time::format_description::well_known::Rfc2822;
+use time::macros::datetime;
+use time::{Duration, OffsetDateTime, UtcOffset};
+
+use crate::commit::{Author, Commit, FileChange, Ref, RefKind};
+
+/// A commit with only what a test is about, the rest as `Commit::default()`,
+/// e.g. `commit().by("Jon").on(date).build()`
+pub fn commit() -> CommitBuilder {
+    CommitBuilder(Commit::default())
+}
+
+pub struct CommitBuilder(Commit);
+
+impl CommitBuilder {
+    pub fn hash(mut self, hash: &str) -> Self {
+        self.0.hash = hash.to_string();
+        self
+    }
+
+    pub fn parents(mut self, parents: &[&str]) -> Self {
+        self.0.parents = parents.iter().map(|parent| parent.to_string()).collect();
+        self
+    }
+
+    /// Pointed at by the branch, along with any other refs given
+    pub fn branch(mut self, name: &str) -> Self {
+        self.0
+            .refs
+            .push(Ref::new(name.to_string(), RefKind::Branch));
+        self
+    }
+
+    /// Pointed at by the tag, along with any other refs given
+    pub fn tag(mut self, name: &str) -> Self {
+        self.0.refs.push(Ref::new(name.to_string(), RefKind::Tag));
+        self
+    }
+
+    /// By the named author, emailed like `jon@email.ca`
+    pub fn by(mut self, name: &str) -> Self {
+        let email = format!("{}@email.ca", name.to_lowercase());
+        self.0.author = Author::identified(name, &email);
+        self
+    }
+
+    /// By the author as given, e.g. with odd cases or mismatched names
+    pub fn author(mut self, name: &str, email: &str) -> Self {
+        self.0.author = Author::new(name.to_string(), email.to_string());
+        self
+    }
+
+    /// By an author known only by their email
+    pub fn email(mut self, email: &str) -> Self {
+        self.0.author = Author::identified(email, email);
+        self
+    }
+
+    /// Authored at the date
+    pub fn on(mut self, date: OffsetDateTime) -> Self {
+        self.0.date = date;
+        self
+    }
+
+    pub fn committed(mut self, date: OffsetDateTime) -> Self {
+        self.0.committed = Some(date);
+        self
+    }
+
+    pub fn message(mut self, message: &str) -> Self {
+        self.0.message = message.to_string();
+        self
+    }
+
+    /// How many files the stat block says changed, whatever `paths()` or
+    /// `changes()` gave
+    pub fn files(mut self, files: u32) -> Self {
+        self.0.files = files;
+        self
+    }
+
+    pub fn lines(mut self, inserts: u32, deletes: u32) -> Self {
+        self.0.inserts = inserts;
+        self.0.deletes = deletes;
+        self
+    }
+
+    /// Changing a line of each path
+    pub fn paths(self, paths: &[&str]) -> Self {
+        let changes: Vec<(&str, u32)> = paths.iter().map(|path| (*path, 1)).collect();
+        self.changes(&changes)
+    }
+
+    /// Changing lines of each path
+    pub fn changes(mut self, changes: &[(&str, u32)]) -> Self {
+        self.0.file_changes = changes
+            .iter()
+            .map(|(path, changes)| FileChange::new(path.to_string(), *changes))
+            .collect();
+        self
+    }
+
+    pub fn build(self) -> Commit {
+        self.0
+    }
+}
+
+/// Names and emails as git would print them, some of them odd
+const AUTHORS: [(&str, &str); 8] = [
+    ("Jon Neufeld", "jneufeld@alumni.ubc.ca"),
+    ("Not Jon", "not.jon@email.ca"),
+    ("José Müller", "jose@example.com"),
+    ("山田太郎", "yamada@example.jp"),
+    // The name runs up to the last ` <`
+    ("Jon <Sr>", "jon.sr@email.ca"),
+    // Neither is required by git
+    ("build-bot", ""),
+    ("", "ci@example.com"),
+    ("Zoë O'Brien-Smith", "zoe+yeesh@example.ie"),
+];
+
+const SUBJECTS: [&str; 8] = [
+    "Refactor parser error handling",
+    "Fix crash on paths with spaces",
+    "🐛 Fix off-by-one in the hour histogram",
+    "Add café support to the menu",
+    "Translate to 日本語",
+    "feat(report)!: drop the old format",
+    "Revert \"Add caching\"",
+    "WIP",
+];
+
+const BODIES: [&str; 3] = [
+    "Errors now say which line failed.",
+    "Closes #42.\n\nSigned-off-by: Jon Neufeld <jneufeld@alumni.ubc.ca>",
+    "Lines | with pipes => and arrows\n  indented further",
+];
+
+const PATHS: [&str; 10] = [
+    "README.md",
+    "src/main.rs",
+    "src/parser.rs",
+    "src/report/size.rs",
+    "docs/résumé.md",
+    "tests/fixtures/log with spaces.txt",
+    "Cargo.toml",
+    "assets/logo.png",
+    "src/日本/mod.rs",
+    ".github/workflows/ci.yml",
+];
+
+/// Offsets in minutes east of UTC, including ones that aren't whole hours
+const OFFSETS: [i32; 6] = [-480, -300, 0, 60, 330, 540];
+
+/// A small, fast, seeded generator (SplitMix64). Good enough to vary test
+/// input, and not for anything else.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number from zero up to but not including `n`
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n.max(1)
+    }
+
+    /// True about `percent` times in a hundred
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    fn hex(&mut self, digits: usize) -> String {
+        (0..digits)
+            .map(|_| char::from_digit(self.below(16) as u32, 16).unwrap())
+            .collect()
+    }
+}
+
+/// A log and the commits it should parse into, newest first
+#[derive(Debug, Clone)]
+pub struct Log {
+    pub text: String,
+    pub commits: Vec<Commit>,
+}
+
+/// A log of `count` commits, the same for the same seed
+pub fn log(seed: u64, count: usize) -> Log {
+    let mut rng = Rng::new(seed);
+    let mut text = String::new();
+    let mut commits = Vec::with_capacity(count);
+
+    let mut date = datetime!(2022-11-28 16:52:42 UTC);
+
+    for i in 0..count {
+        if i > 0 {
+            text.push('\n');
+        }
+
+        // Newest first, anywhere from minutes to days apart
+        date -= Duration::minutes(rng.below(60 * 24 * 3) as i64 + 1);
+        let offset = UtcOffset::from_whole_seconds(rng.pick(&OFFSETS) * 60).unwrap();
+
+        let written = random_commit(&mut rng, i, date.to_offset(offset));
+        text.push_str(&write(&written));
+        commits.push(written.commit);
+    }
+
+    Log { text, commits }
+}
+
+/// A commit along with the parts of it that git writes differently than the
+/// parser keeps them
+struct Written {
+    commit: Commit,
+    /// The name and email before they're identified
+    author: (&'static str, &'static str),
+    /// Each file's path in the stat block, e.g. `src/{old.rs => main.rs}`
+    paths: Vec<String>,
+}
+
+/// A random commit, the first of which is decorated like the tip of `main`
+fn random_commit(rng: &mut Rng, i: usize, date: OffsetDateTime) -> Written {
+    let mut refs = Vec::new();
+
+    if i == 0 {
+        refs.push(Ref::new("main".to_string(), RefKind::Branch));
+        refs.push(Ref::new("origin/main".to_string(), RefKind::Branch));
+    }
+
+    if rng.chance(10) {
+        refs.push(Ref::new(format!("v0.{}.0", i), RefKind::Tag));
+    }
+
+    let author = *rng.pick(&AUTHORS);
+
+    // Rebased or applied later, sometimes by days
+    let committed = date + Duration::minutes(rng.below(3) as i64 * rng.below(60 * 24) as i64);
+
+    let merge = rng.chance(15);
+
+    let (parents, message) = if merge {
+        (
+            vec![rng.hex(7), rng.hex(7)],
+            format!("Merge branch 'feature-{}'", rng.below(100)),
+        )
+    } else if rng.chance(40) {
+        (
+            Vec::new(),
+            format!("{}\n\n{}", rng.pick(&SUBJECTS), rng.pick(&BODIES)),
+        )
+    } else {
+        (Vec::new(), rng.pick(&SUBJECTS).to_string())
+    };
+
+    // git leaves the stat block off of merges
+    let file_changes = if merge { Vec::new() } else { file_changes(rng) };
+
+    let paths = file_changes
+        .iter()
+        .map(|change| written_path(rng, &change.path))
+        .collect();
+
+    let changes: u32 = file_changes.iter().map(|change| change.changes).sum();
+    let inserts = rng.below(changes as u64 + 1) as u32;
+
+    let commit = Commit {
+        hash: rng.hex(40),
+        parents,
+        refs,
+        author: Author::identified(author.0, author.1),
+        date,
+        committed: Some(committed),
+        message,
+        files: file_changes.len() as u32,
+        inserts,
+        deletes: changes - inserts,
+        file_changes,
+    };
+
+    Written {
+        commit,
+        author,
+        paths,
+    }
+}
+
+/// One to four distinct files, some of them binary
+fn file_changes(rng: &mut Rng) -> Vec<FileChange> {
+    let count = rng.below(4) as usize + 1;
+    let mut changes: Vec<FileChange> = Vec::with_capacity(count);
+
+    while changes.len() < count {
+        let path = rng.pick(&PATHS).to_string();
+
+        if changes.iter().any(|change| change.path == path) {
+            continue;
+        }
+
+        let lines = if path.ends_with(".png") {
+            0
+        } else {
+            rng.below(200) as u32
+        };
+
+        changes.push(FileChange::new(path, lines));
+    }
+
+    changes
+}
+
+/// The commit as `git log --pretty=fuller --stat --decorate=short --date=rfc`
+/// writes it
+fn write(written: &Written) -> String {
+    let commit = &written.commit;
+    let mut text = format!("commit {}", commit.hash);
+
+    if !commit.refs.is_empty() {
+        let decorations: Vec<String> = commit
+            .refs
+            .iter()
+            .map(|r| match (r.kind, r.name.as_str()) {
+                (RefKind::Branch, "main") => "HEAD -> main".to_string(),
+                (RefKind::Branch, name) => name.to_string(),
+                (RefKind::Tag, name) => format!("tag: {}", name),
+            })
+            .collect();
+
+        text.push_str(&format!(" ({})", decorations.join(", ")));
+    }
+
+    text.push('\n');
+
+    if !commit.parents.is_empty() {
+        text.push_str(&format!("Merge: {}\n", commit.parents.join(" ")));
+    }
+
+    let (name, email) = written.author;
+
+    let date = |date: OffsetDateTime| date.format(&Rfc2822).unwrap();
+
+    text.push_str(&format!("Author:     {} <{}>\n", name, email));
+    text.push_str(&format!("AuthorDate: {}\n", date(commit.date)));
+    text.push_str(&format!("Commit:     {} <{}>\n", name, email));
+    text.push_str(&format!(
+        "CommitDate: {}\n",
+        date(commit.committed.unwrap_or(commit.date))
+    ));
+    text.push('\n');
+
+    // Every line of the message is indented, blank ones included
+    for line in commit.message.lines() {
+        text.push_str(&format!("    {}\n", line));
+    }
+
+    if commit.file_changes.is_empty() {
+        return text;
+    }
+
+    text.push('\n');
+
+    let paths = &written.paths;
+    let width = paths
+        .iter()
+        .map(|path| path.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    for (change, path) in commit.file_changes.iter().zip(paths) {
+        let padding = " ".repeat(width - path.chars().count());
+
+        if change.path.ends_with(".png") {
+            text.push_str(&format!(" {}{} | Bin 0 -> 1234 bytes\n", path, padding));
+        } else {
+            let graph = "+".repeat(change.changes.min(20) as usize);
+            let line = format!(" {}{} | {:>3} {}", path, padding, change.changes, graph);
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+    }
+
+    text.push_str(&format!(" {}\n", summary(commit)));
+    text
+}
+
+/// How git might show a path in the stat block, sometimes as renamed to it in
+/// any of the ways git shows renames. Only the new path is kept.
+fn written_path(rng: &mut Rng, path: &str) -> String {
+    if !rng.chance(25) {
+        return path.to_string();
+    }
+
+    match (rng.below(3), path.rsplit_once('/')) {
+        (0, Some((dir, file))) => format!("{{old => {}}}/{}", dir, file),
+        (1, Some((dir, file))) => format!("{}/{{old name => {}}}", dir, file),
+        (2, Some((dir, file))) => format!("{{ => {}}}/{}", dir, file),
+        _ => format!("old/{} => {}", path, path),
+    }
+}
+
+/// e.g. `2 files changed, 1 insertion(+), 3 deletions(-)`, leaving out
+/// whichever of inserts and deletes there are none of
+fn summary(commit: &Commit) -> String {
+    let plural = |count: u32, one: &str, many: &str| {
+        format!("{} {}", count, if count == 1 { one } else { many })
+    };
+
+    let mut parts = vec![plural(commit.files, "file changed", "files changed")];
+
+    if commit.inserts > 0 || commit.deletes == 0 {
+        parts.push(plural(commit.inserts, "insertion(+)", "insertions(+)"));
+    }
+
+    if commit.deletes > 0 {
+        parts.push(plural(commit.deletes, "deletion(-)", "deletions(-)"));
+    }
+
+    parts.join(", ")
+}
+
+/// Panics at the first difference between what the parser made of a log and
+/// the commits it was written from
+pub fn assert_same(parsed: &[Commit], expected: &[Commit]) {
+    assert_eq!(parsed.len(), expected.len(), "number of commits");
+
+    for (parsed, expected) in parsed.iter().zip(expected) {
+        let at = &expected.hash;
+
+        assert_eq!(parsed.hash, expected.hash);
+        assert_eq!(parsed.parents, expected.parents, "parents of {}", at);
+        assert_eq!(parsed.refs, expected.refs, "refs of {}", at);
+        assert_eq!(parsed.author.name, expected.author.name, "author of {}", at);
+        assert_eq!(
+            parsed.author.email, expected.author.email,
+            "email of {}",
+            at
+        );
+        assert_eq!(parsed.date, expected.date, "date of {}", at);
+        assert_eq!(
+            parsed.date.offset(),
+            expected.date.offset(),
+            "offset of {}",
+            at
+        );
+        assert_eq!(
+            parsed.committed, expected.committed,
+            "commit date of {}",
+            at
+        );
+        assert_eq!(parsed.message, expected.message, "message of {}", at);
+        assert_eq!(
+            (parsed.files, parsed.inserts, parsed.deletes),
+            (expected.files, expected.inserts, expected.deletes),
+            "stats of {}",
+            at
+        );
+        assert_eq!(
+            parsed.file_changes, expected.file_changes,
+            "files of {}",
+            at
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser;
+
+    use super::{assert_same, log};
+
+    /// Seeds are few enough to run quickly but cover every kind of line
+    const SEEDS: u64 = 200;
+
+    #[test]
+    fn parses_what_it_writes() {
+        for seed in 0..SEEDS {
+            let log = log(seed, 20);
+
+            let parsed = parser::parse(&log.text)
+                .unwrap_or_else(|why| panic!("seed {} doesn't parse: {:?}", seed, why));
+
+            assert_same(&parsed, &log.commits);
+        }
+    }
+
+    #[test]
+    fn parsing_line_by_line_agrees() {
+        for seed in 0..SEEDS {
+            let log = log(seed, 20);
+
+            let mut each = Vec::new();
+            parser::parse_each(log.text.lines(), |commit| each.push(commit)).unwrap();
+            assert_same(&each, &log.commits);
+
+            let borrowed = parser::parse_borrowed(&log.text).unwrap();
+            let dates: Vec<_> = borrowed.iter().map(|commit| commit.date).collect();
+            let expected: Vec<_> = log.commits.iter().map(|commit| commit.date).collect();
+            assert_eq!(dates, expected, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(log(7, 10).text, log(7, 10).text);
+        assert_ne!(log(7, 10).text, log(8, 10).text);
+    }
+
+    #[test]
+    fn covers_odd_input() {
+        let text: String = (0..SEEDS).map(|seed| log(seed, 20).text).collect();
+
+        for expected in [
+            "Merge: ",
+            " => ",
+            "Bin 0 -> ",
+            "tag: ",
+            "Author:     build-bot <>",
+            "Author:      <ci@example.com>",
+            "山田太郎",
+            "🐛",
+        ] {
+            assert!(text.contains(expected), "no {:?}", expected);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::commit::Commit;
+use crate::git::Diffstat;
+use crate::{hg, history, svn};
+
+/// Version control systems history can be read from. Only git's has the
+/// cache, hooks, and blame that some reports and subcommands need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vcs {
+    Git,
+    Hg,
+    /// Read from a working copy, since svn only keeps history on the server
+    Svn,
+}
+
+impl FromStr for Vcs {
+    type Err = String;
+
+    fn from_str(vcs: &str) -> Result<Vcs, String> {
+        match vcs {
+            "git" => Ok(Vcs::Git),
+            "hg" => Ok(Vcs::Hg),
+            "svn" => Ok(Vcs::Svn),
+            _ => Err(format!("Unknown version control system {}", vcs)),
+        }
+    }
+}
+
+impl Vcs {
+    /// Whichever the repository at the given path, or the nearest one above it,
+    /// is managed by, and git when there's no telling
+    pub fn detect(repo: &Path) -> Vcs {
+        let markers = [(".git", Vcs::Git), (".hg", Vcs::Hg), (".svn", Vcs::Svn)];
+
+        repo.ancestors()
+            .find_map(|dir| {
+                markers
+                    .iter()
+                    .find(|(marker, _)| dir.join(marker).exists())
+                    .map(|(_, vcs)| *vcs)
+            })
+            .unwrap_or(Vcs::Git)
+    }
+
+    /// The repository's commits, newest first
+    pub fn commits(
+        &self,
+        repo: &Path,
+        diffstat: Diffstat,
+        strict: bool,
+    ) -> anyhow::Result<Vec<Commit>> {
+        match self {
+            Vcs::Git => history::commits(repo, diffstat, strict),
+            Vcs::Hg => hg::commits(repo, diffstat),
+            // Every path changed is always listed, and no lines changed ever are
+            Vcs::Svn => svn::commits(repo),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::Vcs;
+
+    #[test]
+    fn detect() {
+        let dir = std::env::temp_dir().join(format!("yeesh-vcs-{}", std::process::id()));
+        let nested = dir.join("src").join("parser");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(dir.join(".hg")).unwrap();
+
+        assert_eq!(Vcs::detect(&nested), Vcs::Hg);
+
+        fs::create_dir_all(dir.join("src").join(".git")).unwrap();
+        assert_eq!(Vcs::detect(&nested), Vcs::Git);
+
+        fs::create_dir_all(nested.join(".svn")).unwrap();
+        assert_eq!(Vcs::detect(&nested), Vcs::Svn);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,476 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use time::OffsetDateTime;
+
+use crate::{cache, git, range};
+
+/// Repositories reported on together, read from a workspace file like
+///
+/// ```toml
+/// # Filters every repository starts with
+/// [defaults]
+/// since = "3 months ago"
+///
+/// [[repo]]
+/// path = "../yeesh"
+/// labels = ["cli", "rust"]
+///
+/// [[repo]]
+/// url = "https://github.com/jneufeld/yeesh"
+/// name = "upstream"
+/// until = "2023-01-01"
+/// ```
+///
+/// It's a subset of TOML: only these tables, holding strings, either basic or
+/// literal, and arrays of them, each value on one line. Anything else, e.g. a
+/// number or an array over several lines, is an error saying so.
+#[derive(Debug, Default)]
+pub struct Workspace {
+    pub repos: Vec<Repo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repo {
+    /// Given, or taken from the path or URL like `serve` names repositories
+    pub name: String,
+    pub location: Location,
+    pub labels: Vec<String>,
+    pub since: Option<OffsetDateTime>,
+    pub until: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// Relative to the workspace file
+    Path(PathBuf),
+    /// Mirrored into the cache, see `Repo::local_path()`
+    Url(String),
+}
+
+impl Repo {
+    /// Where the repository can be read. Repositories at a URL are cloned into
+    /// the cache the first time and fetched every time after.
+    pub fn local_path(&self) -> anyhow::Result<PathBuf> {
+        match &self.location {
+            Location::Path(path) => Ok(path.clone()),
+            Location::Url(url) => {
+                let dir = cache::dir()?.join("repos").join(cache::key(Path::new(url)));
+                git::mirror(url, &dir)?;
+
+                Ok(dir)
+            }
+        }
+    }
+}
+
+/// The workspace in the file at `path`, with relative dates relative to `now`
+pub fn load(path: &Path, now: OffsetDateTime) -> anyhow::Result<Workspace> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+    let base = path.parent().unwrap_or_else(|| Path::new("."));
+
+    Workspace::parse(&text, base, now).with_context(|| format!("Can't read {}", path.display()))
+}
+
+/// A value in a workspace file
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Text(String),
+    List(Vec<String>),
+}
+
+/// A table's values in the order they're given
+type Table = Vec<(String, Value)>;
+
+impl Workspace {
+    pub fn parse(text: &str, base: &Path, now: OffsetDateTime) -> anyhow::Result<Workspace> {
+        let mut defaults = Table::new();
+        let mut repos: Vec<Table> = Vec::new();
+
+        // Whether values go in the defaults or the last repository, and
+        // neither before the first table
+        let mut in_defaults = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line {
+                "[[repo]]" => {
+                    repos.push(Table::new());
+                    in_defaults = Some(false);
+                }
+                "[defaults]" => in_defaults = Some(true),
+                _ if line.starts_with('[') => bail!("Unknown table on line {}: {}", i + 1, line),
+                _ => {
+                    let (key, value) = line
+                        .split_once('=')
+                        .with_context(|| format!("Expected `key = value` on line {}", i + 1))?;
+
+                    let value =
+                        parse_value(value.trim()).with_context(|| format!("On line {}", i + 1))?;
+
+                    let table = match in_defaults {
+                        Some(true) => &mut defaults,
+                        Some(false) => repos.last_mut().unwrap(),
+                        None => bail!("Expected [[repo]] or [defaults] before line {}", i + 1),
+                    };
+
+                    table.push((key.trim().to_string(), value));
+                }
+            }
+        }
+
+        let mut workspace = Workspace::default();
+
+        for table in repos {
+            workspace.push(repo(&defaults, &table, base, now)?);
+        }
+
+        Ok(workspace)
+    }
+
+    /// Add the repository, adding a number to its name when another has it
+    fn push(&mut self, repo: Repo) {
+        let mut name = repo.name.clone();
+        let mut n = 1;
+
+        while self.repos.iter().any(|other| other.name == name) {
+            n += 1;
+            name = format!("{}-{}", repo.name, n);
+        }
+
+        self.repos.push(Repo { name, ..repo });
+    }
+}
+
+/// Every git repository in the directory or below it, named by their path
+/// from it. Repositories inside others, e.g. submodules, and hidden
+/// directories aren't searched, nor are symbolic links so there are no loops.
+/// Directories below it that can't be read are skipped with a warning.
+pub fn discover(dir: &Path) -> anyhow::Result<Workspace> {
+    let mut workspace = Workspace::default();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(next) = dirs.pop() {
+        if next.join(".git").exists() {
+            let name = match next.strip_prefix(dir) {
+                Ok(relative) if !relative.as_os_str().is_empty() => relative.display().to_string(),
+                _ => default_name(&Location::Path(next.clone())),
+            };
+
+            workspace.push(Repo {
+                name,
+                location: Location::Path(next),
+                labels: Vec::new(),
+                since: None,
+                until: None,
+            });
+
+            continue;
+        }
+
+        let mut children = match subdirectories(&next) {
+            Ok(children) => children,
+            // One unreadable directory shouldn't hide every other repository
+            Err(why) if next != dir => {
+                eprintln!("yeesh: Skipping {}, can't read it: {}", next.display(), why);
+                continue;
+            }
+            Err(why) => return Err(why).with_context(|| format!("Can't read {}", dir.display())),
+        };
+
+        // Popped in order, so pushed in reverse
+        children.sort_by(|a, b| b.cmp(a));
+        dirs.extend(children);
+    }
+
+    Ok(workspace)
+}
+
+/// The directory's directories, other than hidden ones
+fn subdirectories(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut children = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+
+        if entry.file_type()?.is_dir() && !hidden {
+            children.push(entry.path());
+        }
+    }
+
+    Ok(children)
+}
+
+/// A repository from its table, falling back on the defaults for anything it
+/// doesn't give
+fn repo(defaults: &Table, table: &Table, base: &Path, now: OffsetDateTime) -> anyhow::Result<Repo> {
+    let get = |key: &str| {
+        let value = |table: &Table| {
+            table
+                .iter()
+                .rev()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.clone())
+        };
+
+        value(table).or_else(|| value(defaults))
+    };
+
+    let text = |key: &str| match get(key) {
+        Some(Value::Text(text)) => Ok(Some(text)),
+        Some(Value::List(_)) => bail!("Expected {} to be a string", key),
+        None => Ok(None),
+    };
+
+    let date = |key: &str| {
+        // Including the whole day, like --until
+        let parse = if key == "until" {
+            range::parse_until
+        } else {
+            range::parse
+        };
+
+        match text(key)? {
+            Some(date) => parse(&date, now).map(Some).map_err(anyhow::Error::msg),
+            None => Ok(None),
+        }
+    };
+
+    let location = match (text("path")?, text("url")?) {
+        (Some(path), None) => Location::Path(base.join(path)),
+        (None, Some(url)) => Location::Url(url),
+        _ => bail!("Expected each [[repo]] to have either a path or a url"),
+    };
+
+    let name = match text("name")? {
+        Some(name) => name,
+        None => default_name(&location),
+    };
+
+    let labels = match get("labels") {
+        Some(Value::List(labels)) => labels,
+        Some(Value::Text(label)) => vec![label],
+        None => Vec::new(),
+    };
+
+    Ok(Repo {
+        name,
+        location,
+        labels,
+        since: date("since")?,
+        until: date("until")?,
+    })
+}
+
+/// The directory's name or the last part of the URL, without `.git`
+fn default_name(location: &Location) -> String {
+    let full = match location {
+        Location::Path(path) => path.canonicalize().unwrap_or_else(|_| path.clone()),
+        Location::Url(url) => PathBuf::from(url.trim_end_matches('/')),
+    };
+
+    full.file_name()
+        .map(|name| name.to_string_lossy().trim_end_matches(".git").to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "repo".to_string())
+}
+
+/// A string, e.g. `"yeesh"` or `'yeesh'`, or an array of them on one line,
+/// followed by nothing but a comment
+fn parse_value(text: &str) -> anyhow::Result<Value> {
+    let (value, rest) = if let Some(list) = text.strip_prefix('[') {
+        let mut items = Vec::new();
+        let mut rest = list.trim_start();
+
+        loop {
+            if let Some(after) = rest.strip_prefix(']') {
+                break (Value::List(items), after);
+            }
+
+            if rest.is_empty() || rest.starts_with('#') {
+                bail!("Arrays over several lines aren't supported, put it on one line");
+            }
+
+            let (item, after) = parse_string(rest)?;
+            items.push(item);
+
+            rest = after.trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+        }
+    } else {
+        let (text, rest) = parse_string(text)?;
+        (Value::Text(text), rest)
+    };
+
+    let rest = rest.trim();
+
+    if !rest.is_empty() && !rest.starts_with('#') {
+        bail!("Unexpected {:?} after the value", rest);
+    }
+
+    Ok(value)
+}
+
+/// A quoted string at the start of the text and whatever follows it
+fn parse_string(text: &str) -> anyhow::Result<(String, &str)> {
+    if text.starts_with(r#"""""#) || text.starts_with("'''") {
+        bail!("Multi-line strings aren't supported, put it on one line");
+    }
+
+    // Literal strings have no escapes
+    if let Some(literal) = text.strip_prefix('\'') {
+        return match literal.split_once('\'') {
+            Some((string, rest)) => Ok((string.to_string(), rest)),
+            None => bail!("Expected a closing quote in {:?}", text),
+        };
+    }
+
+    let quoted = match text.strip_prefix('"') {
+        Some(quoted) => quoted,
+        None if text.starts_with('{') => bail!("Inline tables aren't supported, found {:?}", text),
+        None => bail!(
+            "Expected a quoted string, found {:?}. Only strings and arrays of them are supported",
+            text
+        ),
+    };
+
+    let mut string = String::new();
+    let mut chars = quoted.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((string, &quoted[i + 1..])),
+            '\\' => match chars.next() {
+                Some((_, '"')) => string.push('"'),
+                Some((_, '\\')) => string.push('\\'),
+                Some((_, 'n')) => string.push('\n'),
+                Some((_, 't')) => string.push('\t'),
+                other => bail!("Unknown escape {:?}", other.map(|(_, c)| c)),
+            },
+            c => string.push(c),
+        }
+    }
+
+    bail!("Expected a closing quote in {:?}", text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use time::macros::datetime;
+
+    use super::{Location, Value, Workspace};
+
+    #[test]
+    fn parse() {
+        let now = datetime!(2023-03-29 15:30 UTC);
+
+        let workspace = Workspace::parse(
+            r#"
+            # Everything this year
+            [defaults]
+            since = "2023-01-01"
+
+            [[repo]]
+            path = "yeesh"  # The CLI
+            labels = ["cli", "rust"]
+
+            [[repo]]
+            url = "https://github.com/jneufeld/yeesh.git"
+            until = "today"
+
+            [[repo]]
+            path = "other"
+            name = "yeesh"
+            "#,
+            Path::new("/src"),
+            now,
+        )
+        .unwrap();
+
+        let repos = &workspace.repos;
+        assert_eq!(repos.len(), 3);
+
+        assert_eq!(repos[0].name, "yeesh");
+        assert_eq!(
+            repos[0].location,
+            Location::Path(PathBuf::from("/src/yeesh"))
+        );
+        assert_eq!(repos[0].labels, vec!["cli", "rust"]);
+        assert_eq!(repos[0].since, Some(datetime!(2023-01-01 0:00 UTC)));
+        assert_eq!(repos[0].until, None);
+
+        assert_eq!(repos[1].name, "yeesh-2");
+        assert_eq!(
+            repos[1].location,
+            Location::Url("https://github.com/jneufeld/yeesh.git".to_string())
+        );
+        // Through the end of the day
+        assert_eq!(repos[1].until, Some(datetime!(2023-03-30 0:00 UTC)));
+
+        assert_eq!(repos[2].name, "yeesh-3");
+
+        let parse = |text| Workspace::parse(text, Path::new("."), now);
+        assert!(parse("path = \"yeesh\"").is_err());
+        assert!(parse("[[repo]]\nlabels = []").is_err());
+        assert!(parse("[[repo]]\npath = yeesh").is_err());
+        assert!(parse("[other]").is_err());
+    }
+
+    #[test]
+    fn discover() {
+        let dir = std::env::temp_dir().join(format!("yeesh-discover-{}", std::process::id()));
+
+        for repo in ["a/.git", "b/c/.git", "b/c/submodule/.git", ".hidden/.git"] {
+            std::fs::create_dir_all(dir.join(repo)).unwrap();
+        }
+        std::fs::create_dir_all(dir.join("d")).unwrap();
+
+        let workspace = super::discover(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<&str> = workspace
+            .repos
+            .iter()
+            .map(|repo| repo.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a", "b/c"]);
+    }
+
+    #[test]
+    fn parse_value() {
+        let parse = |text| super::parse_value(text).unwrap();
+
+        assert_eq!(parse(r#""a \"b\"""#), Value::Text("a \"b\"".to_string()));
+        assert_eq!(
+            parse(r#"[ "a", "b", ] # c"#),
+            Value::List(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(parse("[]"), Value::List(Vec::new()));
+        assert_eq!(parse(r"'C:\src'"), Value::Text(r"C:\src".to_string()));
+        assert_eq!(
+            parse(r#"['a', "b"]"#),
+            Value::List(vec!["a".to_string(), "b".to_string()])
+        );
+
+        assert!(super::parse_value(r#""a" b"#).is_err());
+        assert!(super::parse_value(r#""a"#).is_err());
+        assert!(super::parse_value("'a").is_err());
+
+        let error = |text| super::parse_value(text).unwrap_err().to_string();
+        assert!(error("[\"a\",").contains("several lines"));
+        assert!(error(r#""""a"#).contains("Multi-line strings"));
+        assert!(error("{ a = \"b\" }").contains("Inline tables"));
+        assert!(error("12").contains("Only strings"));
+    }
+}